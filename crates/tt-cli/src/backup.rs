@@ -0,0 +1,136 @@
+//! Automatic database backups before destructive commands.
+//!
+//! Backups are written to `<state dir>/backups/` as `tt-<timestamp>-<label>.db`
+//! and only the most recent [`BACKUP_RETENTION`] are kept.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use tt_db::Database;
+
+use crate::config::{dirs_data_path, dirs_state_path};
+
+/// Number of backups kept; older ones are deleted after each new backup.
+pub const BACKUP_RETENTION: usize = 5;
+
+const BACKUP_PREFIX: &str = "tt-";
+
+/// Returns the directory backups are stored in.
+///
+/// Falls back to the data directory on platforms without a state directory.
+pub fn backup_dir() -> Option<PathBuf> {
+    dirs_state_path()
+        .or_else(dirs_data_path)
+        .map(|p| p.join("backups"))
+}
+
+/// Backs up the database before a destructive command.
+///
+/// `label` names the command that triggered the backup (e.g., `prune`).
+pub fn backup_before(db: &Database, label: &str) -> Result<PathBuf> {
+    let dir = backup_dir().context("could not determine backup directory")?;
+    backup_into(db, &dir, label, BACKUP_RETENTION)
+}
+
+/// Writes a timestamped backup into `dir` and prunes all but the newest `retention`.
+pub(crate) fn backup_into(
+    db: &Database,
+    dir: &Path,
+    label: &str,
+    retention: usize,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create backup directory {}", dir.display()))?;
+
+    let timestamp = Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+    let path = dir.join(format!("{BACKUP_PREFIX}{timestamp}-{label}.db"));
+    db.backup_to(&path)
+        .with_context(|| format!("failed to back up database to {}", path.display()))?;
+    tracing::info!(path = %path.display(), "backed up database");
+
+    prune_backups(dir, retention)?;
+    Ok(path)
+}
+
+/// Deletes the oldest backups in `dir`, keeping the newest `retention`.
+///
+/// Backup names start with a UTC timestamp, so name order is age order.
+fn prune_backups(dir: &Path, retention: usize) -> Result<()> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("failed to read backup directory {}", dir.display()))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "db")
+                && path
+                    .file_name()
+                    .and_then(std::ffi::OsStr::to_str)
+                    .is_some_and(|n| n.starts_with(BACKUP_PREFIX))
+        })
+        .collect();
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(retention);
+    for old in &backups[..excess] {
+        if let Err(e) = fs::remove_file(old) {
+            tracing::warn!(path = %old.display(), error = %e, "failed to delete old backup");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_into_creates_openable_copy() {
+        let temp = tempfile::tempdir().unwrap();
+        let db = Database::open_in_memory().unwrap();
+
+        let path = backup_into(&db, temp.path(), "prune", BACKUP_RETENTION).unwrap();
+
+        assert!(path.exists());
+        assert!(Database::open(&path).is_ok());
+    }
+
+    #[test]
+    fn test_backup_into_keeps_newest_backups() {
+        let temp = tempfile::tempdir().unwrap();
+        let db = Database::open_in_memory().unwrap();
+
+        // Pre-existing backups with old timestamps
+        for day in 1..=4 {
+            fs::write(
+                temp.path()
+                    .join(format!("tt-2025010{day}T000000.000Z-prune.db")),
+                "",
+            )
+            .unwrap();
+        }
+        // Unrelated files are left alone
+        fs::write(temp.path().join("notes.txt"), "").unwrap();
+
+        let newest = backup_into(&db, temp.path(), "prune", 3).unwrap();
+
+        let mut remaining: Vec<String> = fs::read_dir(temp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        remaining.sort();
+
+        let newest_name = newest.file_name().unwrap().to_string_lossy().into_owned();
+        assert_eq!(
+            remaining,
+            vec![
+                "notes.txt".to_string(),
+                "tt-20250103T000000.000Z-prune.db".to_string(),
+                "tt-20250104T000000.000Z-prune.db".to_string(),
+                newest_name,
+            ]
+        );
+    }
+}
@@ -2,7 +2,23 @@
 
 use std::path::PathBuf;
 
-use clap::{ArgAction, Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+
+use crate::commands::completions::CompletionKind;
+use crate::commands::report::Budget;
+use crate::commands::streams::StreamSort;
+use crate::output::ColorChoice;
+
+/// Format of tracing log output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// Newline-delimited JSON, for log collectors.
+    Json,
+}
 
 /// AI-native time tracker.
 ///
@@ -19,6 +35,31 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub config: Option<PathBuf>,
 
+    /// Log output format: human-readable text, or one JSON object per line.
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        env = "TT_LOG_FORMAT",
+        default_value_t = LogFormat::Text
+    )]
+    pub log_format: LogFormat,
+
+    /// When to color output: auto (terminal and no `NO_COLOR`), always, or never.
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "WHEN",
+        default_value_t = ColorChoice::Auto
+    )]
+    pub color: ColorChoice,
+
+    /// Suppress informational output such as progress and summaries; errors still print.
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -44,16 +85,36 @@ pub enum Commands {
         #[arg(long)]
         after: Option<String>,
 
+        /// Only export events strictly after this RFC 3339 timestamp.
+        #[arg(long, value_name = "RFC3339", conflicts_with = "after")]
+        after_timestamp: Option<String>,
+
         /// Only export events after this timestamp (for incremental `OpenCode` export).
         #[arg(long)]
         since: Option<String>,
+
+        /// Only export records whose source starts with this prefix (e.g., `remote.agent`).
+        #[arg(long)]
+        source: Option<String>,
+
+        /// Fail instead of skipping when `events.jsonl` contains malformed lines.
+        #[arg(long, visible_alias = "validate")]
+        strict: bool,
+
+        /// Start the output with an `export_header` record carrying the format version.
+        #[arg(long)]
+        header: bool,
     },
 
     /// Import events from stdin into local `SQLite` database.
     ///
     /// Events are expected as JSONL (one JSON object per line).
     /// Duplicate events (same ID) are silently ignored.
-    Import,
+    Import {
+        /// Fail instead of warning when an event's `machine_id` disagrees with its ID prefix.
+        #[arg(long)]
+        strict: bool,
+    },
 
     /// Recompute direct/delegated time for streams.
     ///
@@ -63,6 +124,50 @@ pub enum Commands {
         /// Recompute all streams, not just those marked as needing recomputation.
         #[arg(long)]
         force: bool,
+
+        /// Log wall-clock time spent in each phase (fetch, allocate, aggregate, update).
+        #[arg(long)]
+        profile: bool,
+    },
+
+    /// Route unassigned events to existing streams by cwd or URL.
+    ///
+    /// With --project, first clears that project's inferred assignments and
+    /// re-routes only its events; other projects and user assignments are
+    /// left as they are. Affected streams are then recomputed.
+    Infer {
+        /// Only re-infer events whose `git_project` matches.
+        #[arg(long, value_name = "NAME")]
+        project: Option<String>,
+    },
+
+    /// Dump the database's events to stdout as JSONL.
+    ///
+    /// Unlike `export`, this reads the local database rather than the source
+    /// logs, so events include their stream assignments.
+    Dump {
+        /// Replace paths, projects, titles and URLs with stable placeholder tokens.
+        #[arg(long)]
+        anonymize: bool,
+    },
+
+    /// Allocate time over an events JSONL file without touching the database.
+    ///
+    /// Reads `tt export` output and prints per-directory stream times, for
+    /// reproducing someone's numbers from their exported log.
+    Replay {
+        /// Path to the events JSONL file.
+        file: PathBuf,
+    },
+
+    /// Delete old data from the local database.
+    ///
+    /// Removes agent sessions that ended before the cutoff. Ongoing sessions
+    /// are kept unless they started before the cutoff.
+    Prune {
+        /// Cutoff time (ISO 8601 or relative, e.g., '90 days ago').
+        #[arg(long)]
+        before: String,
     },
 
     /// Generate a time report.
@@ -86,6 +191,22 @@ pub enum Commands {
         #[arg(long, group = "period")]
         last_day: bool,
 
+        /// Current calendar month.
+        #[arg(long, group = "period")]
+        month: bool,
+
+        /// Previous calendar month.
+        #[arg(long, group = "period")]
+        last_month: bool,
+
+        /// Current calendar quarter (Jan–Mar, Apr–Jun, Jul–Sep, Oct–Dec).
+        #[arg(long, group = "period")]
+        quarter: bool,
+
+        /// Previous calendar quarter.
+        #[arg(long, group = "period")]
+        last_quarter: bool,
+
         /// Number of weekly reports to generate (most recent first).
         #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), group = "period")]
         weeks: Option<u32>,
@@ -101,24 +222,181 @@ pub enum Commands {
         /// Output as JSON.
         #[arg(long)]
         json: bool,
+
+        /// Output newline-delimited JSON: one object per tag entry, then a totals object.
+        #[arg(
+            long,
+            visible_alias = "compact",
+            conflicts_with_all = ["json", "daily", "by_project", "by_category", "agent_leverage"]
+        )]
+        jsonl: bool,
+
+        /// Show parent agent sessions with their subagents nested.
+        #[arg(long, conflicts_with_all = ["json", "jsonl"])]
+        tree: bool,
+
+        /// Add a per-day breakdown (tag × day) of the period.
+        #[arg(long, conflicts_with = "weeks")]
+        daily: bool,
+
+        /// Add a breakdown by each stream's dominant git project.
+        #[arg(long)]
+        by_project: bool,
+
+        /// Add a breakdown by work type (see 'tt streams categorize').
+        #[arg(long)]
+        by_category: bool,
+
+        /// Add delegated ÷ direct time per tag and overall (agent work per hour of attention).
+        #[arg(long)]
+        agent_leverage: bool,
+
+        /// Track a tag against a time budget, e.g. acme-webapp=10 (repeatable).
+        #[arg(long = "budget", value_name = "TAG=HOURS", conflicts_with = "jsonl")]
+        budgets: Vec<Budget>,
+
+        /// Only include streams with this tag (repeatable).
+        #[arg(long = "tag", value_name = "TAG")]
+        include_tags: Vec<String>,
+
+        /// Exclude streams with this tag (repeatable). Wins over --tag.
+        #[arg(long = "exclude-tag", value_name = "TAG")]
+        exclude_tags: Vec<String>,
+
+        /// Only count direct (human attention) time.
+        #[arg(long, conflicts_with = "only_delegated")]
+        only_direct: bool,
+
+        /// Only count delegated (agent) time.
+        #[arg(long)]
+        only_delegated: bool,
+
+        /// End agent sessions still open at the period end at their last tool use
+        /// ("committed so far") instead of running them to the period end.
+        /// Sessions known to have ended within the period keep their end time.
+        #[arg(long)]
+        no_carry_open: bool,
+
+        /// Drop time falling on Saturday and Sunday (local time).
+        #[arg(long)]
+        exclude_weekends: bool,
+
+        /// Fit tag and stream names to this many columns (default: $COLUMNS, else 80).
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(20..))]
+        width: Option<u16>,
+
+        /// Never truncate tag and stream names.
+        #[arg(long, conflicts_with = "width")]
+        no_truncate: bool,
+
+        /// Show only the N highest-time tags and streams; the rest roll into an "other" row.
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..), conflicts_with_all = ["json", "jsonl"])]
+        top: Option<u16>,
+
+        /// Log wall-clock time spent in each phase (fetch, allocate, aggregate).
+        #[arg(long)]
+        profile: bool,
+    },
+
+    /// Output per-day direct/delegated totals as JSON, for heatmap visualizations.
+    ///
+    /// Every day in the range gets a row, including days with no activity.
+    Heatmap {
+        /// Start date (YYYY-MM-DD, local time).
+        #[arg(long)]
+        start: String,
+
+        /// End date (YYYY-MM-DD, local time, exclusive). Defaults to tomorrow.
+        #[arg(long)]
+        end: Option<String>,
+    },
+
+    /// Show this month's time report (same as 'tt report --month').
+    Month {
+        /// Show the previous month instead.
+        #[arg(long)]
+        last: bool,
+    },
+
+    /// Show today's time report.
+    ///
+    /// With --watch, re-renders the report every --interval seconds until Ctrl-C.
+    Today {
+        /// Keep refreshing the report, clearing the screen between renders.
+        #[arg(long)]
+        watch: bool,
+
+        /// Seconds between refreshes in watch mode.
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = crate::commands::today::DEFAULT_INTERVAL_SECS,
+            value_parser = clap::value_parser!(u64).range(1..),
+            requires = "watch"
+        )]
+        interval: u64,
     },
 
     /// Add a tag to a stream.
     ///
     /// Tags are additive—multiple tags per stream are supported.
     /// Use 'tt streams' to see available stream IDs.
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Tag {
         /// Stream ID or name (e.g., 'abc123' or 'time-tracker').
-        stream: String,
+        #[arg(required = true)]
+        stream: Option<String>,
 
         /// Tag to add.
-        tag: String,
+        #[arg(required = true)]
+        tag: Option<String>,
+
+        #[command(subcommand)]
+        action: Option<TagAction>,
     },
 
     /// Manage streams.
     #[command(subcommand)]
     Streams(StreamsAction),
 
+    /// Inspect the configuration.
+    #[command(subcommand)]
+    Config(ConfigAction),
+
+    /// Inspect and correct individual events.
+    #[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
+    Events {
+        /// Print only the number of events in the range, without loading them.
+        #[arg(long)]
+        count_only: bool,
+
+        /// Only count events strictly after this time (ISO 8601 or relative, e.g., '2 hours ago').
+        #[arg(long, requires = "count_only")]
+        after: Option<String>,
+
+        /// Only count events strictly before this time (ISO 8601 or relative).
+        #[arg(long, requires = "count_only")]
+        before: Option<String>,
+
+        #[command(subcommand)]
+        action: Option<EventsAction>,
+    },
+
+    /// Correct agent session assignments.
+    #[command(subcommand)]
+    Session(SessionAction),
+
+    /// Check the database for data-integrity problems.
+    Verify {
+        /// Report events that differ only by ID (same type, timestamp, session and stream).
+        #[arg(long, required_unless_present = "delete_dupes")]
+        dupes: bool,
+
+        /// Delete duplicate events, keeping the lexicographically smallest ID of each group.
+        #[arg(long)]
+        delete_dupes: bool,
+    },
+
     /// Show and inspect markdown-backed todos.
     #[command(subcommand)]
     Todo(TodoAction),
@@ -127,7 +405,7 @@ pub enum Commands {
     #[command(subcommand)]
     Priority(PriorityAction),
 
-    /// Initialize machine identity for multi-machine sync.
+    /// Initialize machine identity and print the tmux hook setup.
     ///
     /// Generates a persistent UUID for this machine, stored in
     /// `~/.local/share/time-tracker/machine.json`. Idempotent — safe to run again.
@@ -135,10 +413,37 @@ pub enum Commands {
         /// Human-friendly label for this machine (defaults to hostname).
         #[arg(long)]
         label: Option<String>,
+
+        /// Append the tmux hooks to `~/.tmux.conf` instead of printing them.
+        #[arg(long)]
+        write: bool,
     },
 
+    /// Print a shell completion script (e.g. `tt completions fish > ~/.config/fish/completions/tt.fish`).
+    Completions {
+        /// Shell to generate the script for.
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
+    /// List stream names, tags or projects for shell completion, one per line.
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// Kind of value to list.
+        #[arg(value_enum)]
+        kind: CompletionKind,
+    },
+
+    /// Show or change this machine's identity.
+    #[command(subcommand)]
+    Machine(MachineAction),
+
     /// List known remote machines and their sync status.
-    Machines,
+    Machines {
+        /// Output as JSON, including each machine's latest event timestamp.
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Sync events from remote machine(s) via SSH.
     ///
@@ -210,7 +515,7 @@ pub enum Commands {
         #[arg(long)]
         summary: bool,
 
-        /// Output as JSON.
+        /// Output as JSON (with --apply, the coverage summary).
         #[arg(long)]
         json: bool,
 
@@ -229,20 +534,104 @@ pub enum Commands {
         /// Minimum gap duration to include (minutes).
         #[arg(long, default_value = "5")]
         gap_threshold: u32,
+
+        /// Include up to N representative events per session, spread across
+        /// the session and favoring distinct files and directories.
+        #[arg(long, value_name = "N")]
+        sample: Option<usize>,
     },
 }
 
+/// Machine subcommand actions.
+#[derive(Debug, Subcommand)]
+pub enum MachineAction {
+    /// Print this machine's ID and label.
+    Show,
+
+    /// Replace this machine's ID with a new UUID.
+    ///
+    /// Use when two machines share an ID (e.g. a cloned VM). Events already
+    /// recorded keep the old ID, so history appears under both IDs.
+    Regenerate {
+        /// Confirm the regeneration.
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Set this machine's ID to a specific UUID, e.g. when migrating machines.
+    Import {
+        /// Machine UUID to adopt.
+        id: String,
+    },
+}
+
+/// Config subcommand actions.
+#[derive(Debug, Subcommand)]
+pub enum ConfigAction {
+    /// Print the config file path (`--config` if given, otherwise the default location).
+    Path,
+
+    /// Print the effective config (defaults, file, and `TT_*` env vars) as TOML.
+    ///
+    /// Secret values such as API keys are redacted.
+    Show,
+}
+
 /// Streams subcommand actions.
 #[derive(Debug, Subcommand)]
 pub enum StreamsAction {
     /// List streams with time totals and tags.
     ///
-    /// Shows streams from the last 7 days, sorted by total time.
+    /// Shows streams from the last 7 days, sorted by total time. With a
+    /// period flag, shows only streams with events in that period and the
+    /// time allocated within it.
     /// Use 'tt tag <id> <tag>' to organize streams into projects.
     List {
         /// Output as JSON.
         #[arg(long)]
         json: bool,
+
+        /// List all streams, most recently updated first, showing this page (1-based).
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        page: Option<u32>,
+
+        /// Streams per page with `--page`.
+        #[arg(long, default_value_t = 20, requires = "page", value_parser = clap::value_parser!(u32).range(1..))]
+        page_size: u32,
+
+        /// Only streams active in the current week, with their time this week.
+        #[arg(long, group = "period", conflicts_with = "page")]
+        week: bool,
+
+        /// Only streams active today, with their time today.
+        #[arg(long, group = "period", conflicts_with = "page")]
+        today: bool,
+
+        /// Only streams active from this date (YYYY-MM-DD, local time), with their time in range.
+        #[arg(long, group = "period", conflicts_with = "page")]
+        start: Option<String>,
+
+        /// End date (YYYY-MM-DD, local time, exclusive) for `--start`. Defaults to tomorrow.
+        #[arg(long, requires = "start")]
+        end: Option<String>,
+
+        /// Order streams by total time, last activity, name, or creation.
+        #[arg(long, value_enum, default_value_t = StreamSort::Time, conflicts_with = "page")]
+        sort: StreamSort,
+
+        /// Reverse the sort order.
+        #[arg(long, conflicts_with = "page")]
+        reverse: bool,
+    },
+
+    /// Show details for one stream: times, tags, events, sessions, and top directories.
+    Show {
+        /// Stream ID or name (e.g., 'abc123' or 'time-tracker').
+        stream: String,
+
+        /// Output as JSON.
+        #[arg(long)]
+        json: bool,
     },
 
     /// Create a new stream (prints ID to stdout).
@@ -251,6 +640,39 @@ pub enum StreamsAction {
         name: String,
     },
 
+    /// Set a stream's work type (e.g., coding, reviewing, meetings).
+    ///
+    /// Stored as a `type:<category>` tag, replacing any previous category.
+    Categorize {
+        /// Stream ID or name (e.g., 'abc123' or 'time-tracker').
+        stream: String,
+
+        /// Work type (e.g., 'coding').
+        category: String,
+    },
+
+    /// Remove tags from a stream: all of them, or only those given with --tag.
+    Untag {
+        /// Stream ID or name (e.g., 'abc123' or 'time-tracker').
+        stream: String,
+
+        /// Tag to remove (repeatable). Without it, every tag is removed.
+        #[arg(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+    },
+
+    /// Replace one tag on a single stream, leaving other streams' tags alone.
+    Retag {
+        /// Stream ID or name (e.g., 'abc123' or 'time-tracker').
+        stream: String,
+
+        /// Tag to replace.
+        old: String,
+
+        /// Replacement tag.
+        new: String,
+    },
+
     /// Link a stream name to a priority slug.
     Link {
         /// Exact stream display name.
@@ -261,6 +683,52 @@ pub enum StreamsAction {
     },
 }
 
+/// Tag subcommand actions.
+#[derive(Debug, Subcommand)]
+pub enum TagAction {
+    /// Merge one tag into another across all streams (e.g., after a rename collision).
+    ///
+    /// Every stream tagged `from` gets `to` instead; streams already tagged
+    /// `to` just lose `from`.
+    Merge {
+        /// Tag to merge away.
+        from: String,
+
+        /// Tag to keep.
+        to: String,
+    },
+}
+
+/// Events subcommand actions.
+#[derive(Debug, Subcommand)]
+pub enum EventsAction {
+    /// Move a single event to another stream as a user assignment.
+    ///
+    /// Both the previous and the new stream are marked for recompute.
+    Reassign {
+        /// Event ID.
+        event: String,
+
+        /// Target stream ID or name (e.g., 'abc123' or 'time-tracker').
+        stream: String,
+    },
+}
+
+/// Session subcommand actions.
+#[derive(Debug, Subcommand)]
+pub enum SessionAction {
+    /// Move all of an agent session's events to a stream as user assignments.
+    ///
+    /// Every stream the session touched, and the target, is marked for recompute.
+    Assign {
+        /// Agent session ID.
+        session: String,
+
+        /// Target stream ID or name (e.g., 'abc123' or 'time-tracker').
+        stream: String,
+    },
+}
+
 /// Todo subcommand actions.
 #[derive(Debug, Subcommand)]
 pub enum TodoAction {
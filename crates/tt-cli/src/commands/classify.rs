@@ -4,14 +4,18 @@
 //! - **Show**: Display unclassified sessions and events for LLM-based classification
 //! - **Apply**: Accept JSON assignments and propagate to events
 
-use std::collections::HashMap;
-use std::io::Read;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Write as _};
+use std::io::{Read, Write};
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use tt_core::AllocationConfig;
 
 use super::util::parse_datetime;
+use crate::output::OutputCtx;
+use crate::summarize::{SummarizeEvent, sample_events};
 
 // ── Show mode ──────────────────────────────────────────────────────────────
 
@@ -33,6 +37,12 @@ struct SessionSummary {
     user_prompt_count: usize,
     stream_id: Option<String>,
     proposed_stream: Option<String>,
+    /// Suggested name for a new stream when no existing one is proposed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name_hint: Option<String>,
+    /// Representative events of the session, with `--sample`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    events: Option<Vec<SummarizeEvent>>,
 }
 
 /// Non-session event cluster for classification output.
@@ -109,12 +119,13 @@ pub fn run_show(
     end: Option<String>,
     gaps: bool,
     gap_threshold: u32,
+    sample: Option<usize>,
 ) -> Result<()> {
     let (start_time, end_time) = resolve_time_range(start, end)?;
 
     // Get sessions in range
     let sessions = db
-        .agent_sessions_in_range(start_time, end_time)
+        .agent_sessions_in_range(start_time, end_time, None)
         .context("failed to query agent sessions")?;
 
     // Build CWD → stream_id mapping from existing classified events for proposals
@@ -138,6 +149,19 @@ pub fn run_show(
         .map(|s| (s.id.clone(), s.name.clone().unwrap_or_default()))
         .collect();
 
+    // With --sample, summarize each session's events for the prompt
+    let mut session_events: HashMap<&str, Vec<SummarizeEvent>> = HashMap::new();
+    if sample.is_some() {
+        for event in &classified_events {
+            if let (Some(session_id), Some(summary)) = (
+                event.session_id.as_deref(),
+                SummarizeEvent::from_stored(event),
+            ) {
+                session_events.entry(session_id).or_default().push(summary);
+            }
+        }
+    }
+
     // Build session summaries — filter out subagents
     let mut session_summaries: Vec<SessionSummary> = sessions
         .iter()
@@ -169,7 +193,17 @@ pub fn run_show(
                 tool_call_count: s.tool_call_count,
                 user_prompt_count: s.user_prompts.len(),
                 stream_id,
+                name_hint: if proposed.is_none() {
+                    stream_name_hint(s)
+                } else {
+                    None
+                },
                 proposed_stream: proposed,
+                events: sample.map(|n| {
+                    session_events
+                        .get(s.session_id.as_str())
+                        .map_or_else(Vec::new, |events| sample_events(events, n))
+                }),
             }
         })
         .collect();
@@ -358,6 +392,23 @@ fn print_table(output: &ClassifyOutput) {
         if let Some(proposed) = &s.proposed_stream {
             println!("    Proposed: {proposed}");
         }
+        if let Some(hint) = &s.name_hint {
+            println!("    Name:    {hint}");
+        }
+        if let Some(events) = &s.events {
+            println!("    Sampled events ({}):", events.len());
+            for event in events {
+                let target = event.file.as_deref().or(event.cwd.as_deref()).unwrap_or("");
+                println!(
+                    "      {}  {:<8} {target}",
+                    event.timestamp.format("%H:%M:%S"),
+                    event
+                        .tool
+                        .clone()
+                        .unwrap_or_else(|| event.event_type.to_string()),
+                );
+            }
+        }
     }
 }
 
@@ -593,12 +644,144 @@ pub struct TimeAssignment {
     pub stream: String,
 }
 
-/// Apply stream assignments from JSON input.
+/// One event newly assigned by `--apply`, with the rule that decided it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssignmentTrace {
+    pub event_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub stream: String,
+    /// Deciding signal, e.g. `session continuity: <id>` or `cwd pattern: <like>`.
+    pub signal: String,
+}
+
+impl fmt::Display for AssignmentTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {}  -> {}  ({})",
+            self.timestamp
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            self.event_id,
+            self.stream,
+            self.signal
+        )
+    }
+}
+
+/// How well an assignment run (`--apply` or `tt infer`) covered the database's events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApplySummary {
+    /// Events in the database.
+    pub events_processed: usize,
+    /// Event assignments written by this run, including re-assignments of
+    /// already inferred events.
+    pub events_assigned: u64,
+    /// Events still without a stream afterwards (no rule matched them).
+    pub events_unassigned: usize,
+    /// Streams created for names that did not exist yet.
+    pub streams_created: usize,
+    /// Existing streams the input referenced.
+    pub streams_reused: usize,
+}
+
+impl ApplySummary {
+    /// Formats the summary for terminal output.
+    pub fn format(&self) -> String {
+        let mut output = String::new();
+        writeln!(output, "Classification summary:").unwrap();
+        for (label, value) in [
+            ("Events processed:", self.events_processed),
+            (
+                "Assigned:",
+                usize::try_from(self.events_assigned).unwrap_or(usize::MAX),
+            ),
+            ("Unassigned:", self.events_unassigned),
+            ("Streams created:", self.streams_created),
+            ("Streams reused:", self.streams_reused),
+        ] {
+            writeln!(output, "  {label:<18}{value:>8}").unwrap();
+        }
+        output
+    }
+}
+
+/// Result of [`run_apply`].
+#[derive(Debug)]
+pub struct ApplyOutcome {
+    /// Newly assigned events with their deciding rule (only with `verbose`).
+    pub trace: Vec<AssignmentTrace>,
+    pub summary: ApplySummary,
+}
+
+/// Returns the stream's current event IDs when tracing, so new assignments can be diffed.
+fn traced_event_ids(
+    db: &tt_db::Database,
+    stream_id: &str,
+    verbose: bool,
+) -> Result<Option<HashSet<String>>> {
+    if !verbose {
+        return Ok(None);
+    }
+    let events = db
+        .get_events_by_stream(stream_id)
+        .context("failed to load stream events")?;
+    Ok(Some(events.into_iter().map(|event| event.id).collect()))
+}
+
+/// Appends a trace entry for each event that joined the stream since `before`.
+fn trace_new_assignments(
+    db: &tt_db::Database,
+    stream_id: &str,
+    stream: &str,
+    before: Option<&HashSet<String>>,
+    signal: &str,
+    trace: &mut Vec<AssignmentTrace>,
+) -> Result<()> {
+    let Some(before) = before else {
+        return Ok(());
+    };
+    let events = db
+        .get_events_by_stream(stream_id)
+        .context("failed to load stream events")?;
+    trace.extend(
+        events
+            .into_iter()
+            .filter(|event| !before.contains(&event.id))
+            .map(|event| AssignmentTrace {
+                event_id: event.id,
+                timestamp: event.timestamp,
+                stream: stream.to_string(),
+                signal: signal.to_string(),
+            }),
+    );
+    Ok(())
+}
+
+/// Apply stream assignments from JSON input, then print an [`ApplySummary`].
+///
+/// With `verbose`, prints and returns one [`AssignmentTrace`] per newly assigned event.
+/// With `ctx.json`, the summary is printed as JSON and progress lines go to stderr,
+/// so stdout stays parseable. With `ctx.quiet`, progress lines, the trace and the
+/// text summary are dropped; a JSON summary is still printed.
 #[expect(
     clippy::too_many_lines,
     reason = "sequential phases of stream creation, assignment, and recompute"
 )]
-pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
+pub fn run_apply(
+    db: &tt_db::Database,
+    config: &AllocationConfig,
+    input_path: &str,
+    verbose: bool,
+    ctx: &OutputCtx,
+) -> Result<ApplyOutcome> {
+    let mut progress: Box<dyn Write> = if ctx.quiet {
+        Box::new(std::io::sink())
+    } else if ctx.json {
+        Box::new(std::io::stderr())
+    } else {
+        Box::new(std::io::stdout())
+    };
+
     let input_str = if input_path == "-" {
         let mut buf = String::new();
         std::io::stdin()
@@ -634,6 +817,12 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
         .chain(input.assign_by_time.iter().map(|a| a.stream.clone()))
         .collect();
 
+    let mut streams_created = 0;
+    let streams_reused = all_stream_names
+        .iter()
+        .filter(|name| stream_name_to_id.contains_key(*name))
+        .collect::<HashSet<_>>()
+        .len();
     for name in &all_stream_names {
         if !stream_name_to_id.contains_key(name) {
             let id = uuid::Uuid::new_v4().to_string();
@@ -651,7 +840,8 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
             db.insert_stream(&stream)
                 .with_context(|| format!("failed to create stream: {name}"))?;
             stream_name_to_id.insert(name.clone(), id.clone());
-            println!("Created stream: {name} ({})", &id[..8]);
+            streams_created += 1;
+            writeln!(progress, "Created stream: {name} ({})", &id[..8])?;
         }
     }
 
@@ -667,11 +857,13 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
 
     // Phase 2: Session assignments
     let mut total_assigned = 0u64;
+    let mut trace = Vec::new();
     for assignment in &input.assign_by_session {
         let stream_id = stream_name_to_id
             .get(&assignment.stream)
             .with_context(|| format!("unknown stream: {}", assignment.stream))?;
 
+        let before = traced_event_ids(db, stream_id, verbose)?;
         let count = db
             .assign_events_by_session_id(&assignment.session_id, stream_id, "inferred")
             .with_context(|| {
@@ -681,6 +873,15 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
                 )
             })?;
 
+        trace_new_assignments(
+            db,
+            stream_id,
+            &assignment.stream,
+            before.as_ref(),
+            &format!("session continuity: {}", assignment.session_id),
+            &mut trace,
+        )?;
+
         if count > 0 {
             tracing::info!(
                 session_id = %assignment.session_id,
@@ -711,6 +912,7 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
             .transpose()
             .context("invalid end time in pattern assignment")?;
 
+        let before = traced_event_ids(db, stream_id, verbose)?;
         let count = db
             .assign_events_by_pattern(&assignment.cwd_like, start, end, stream_id)
             .with_context(|| {
@@ -720,6 +922,15 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
                 )
             })?;
 
+        trace_new_assignments(
+            db,
+            stream_id,
+            &assignment.stream,
+            before.as_ref(),
+            &format!("cwd pattern: {}", assignment.cwd_like),
+            &mut trace,
+        )?;
+
         if count > 0 {
             tracing::info!(
                 cwd_like = %assignment.cwd_like,
@@ -737,6 +948,7 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
             .get(&assignment.stream)
             .with_context(|| format!("unknown stream: {}", assignment.stream))?;
 
+        let before = traced_event_ids(db, stream_id, verbose)?;
         let count = db
             .assign_events_by_ids(&assignment.event_ids, stream_id, "inferred")
             .with_context(|| {
@@ -747,6 +959,15 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
                 )
             })?;
 
+        trace_new_assignments(
+            db,
+            stream_id,
+            &assignment.stream,
+            before.as_ref(),
+            "explicit event id",
+            &mut trace,
+        )?;
+
         if count > 0 {
             tracing::info!(
                 stream = %assignment.stream,
@@ -769,6 +990,7 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
         let end =
             parse_datetime(&assignment.end).context("invalid end time in time-range assignment")?;
 
+        let before = traced_event_ids(db, stream_id, verbose)?;
         let count = db
             .assign_events_by_time_range(start, end, stream_id)
             .with_context(|| {
@@ -778,6 +1000,15 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
                 )
             })?;
 
+        trace_new_assignments(
+            db,
+            stream_id,
+            &assignment.stream,
+            before.as_ref(),
+            &format!("time range: {} to {}", assignment.start, assignment.end),
+            &mut trace,
+        )?;
+
         if count > 0 {
             tracing::info!(
                 start = %assignment.start,
@@ -790,15 +1021,38 @@ pub fn run_apply(db: &tt_db::Database, input_path: &str) -> Result<()> {
         }
     }
 
+    for entry in &trace {
+        writeln!(progress, "{entry}")?;
+    }
+
     // Phase 5: Recompute affected streams
     if total_assigned > 0 {
-        println!("Assigned {total_assigned} events. Recomputing...");
-        super::recompute::run(db, true)?;
+        writeln!(progress, "Assigned {total_assigned} events. Recomputing...")?;
+        super::recompute::run_to(db, true, config, &mut progress)?;
     } else {
-        println!("No events to assign.");
+        writeln!(progress, "No events to assign.")?;
     }
 
-    Ok(())
+    let summary = ApplySummary {
+        events_processed: db
+            .count_events_in_range(None, None)
+            .context("failed to count events")?,
+        events_assigned: total_assigned,
+        events_unassigned: db
+            .get_events_without_stream()
+            .context("failed to query unassigned events")?
+            .len(),
+        streams_created,
+        streams_reused,
+    };
+    if ctx.json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        writeln!(progress)?;
+        write!(progress, "{}", summary.format())?;
+    }
+
+    Ok(ApplyOutcome { trace, summary })
 }
 
 // ── Utilities ──────────────────────────────────────────────────────────────
@@ -820,6 +1074,28 @@ fn resolve_time_range(
     Ok((start_time, end_time))
 }
 
+/// Maximum length of a suggested stream name.
+const NAME_HINT_MAX_LEN: usize = 60;
+
+/// Suggests a stream name for a session.
+///
+/// The session summary (an `OpenCode` title or a Claude summary) is preferred,
+/// falling back to the first line of the starting prompt.
+fn stream_name_hint(session: &tt_core::session::AgentSession) -> Option<String> {
+    session
+        .summary
+        .as_deref()
+        .or_else(|| {
+            session
+                .starting_prompt
+                .as_deref()
+                .and_then(|prompt| prompt.lines().next())
+        })
+        .map(str::trim)
+        .filter(|hint| !hint.is_empty())
+        .map(|hint| truncate(hint, NAME_HINT_MAX_LEN))
+}
+
 fn truncate(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
@@ -861,6 +1137,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: None,
             cwd: Some(cwd.to_string()),
             session_id: session_id.map(String::from),
@@ -957,8 +1234,25 @@ mod tests {
         )
         .unwrap();
 
-        run_apply(&db, input_path.to_str().unwrap()).unwrap();
+        let outcome = run_apply(
+            &db,
+            &AllocationConfig::default(),
+            input_path.to_str().unwrap(),
+            false,
+            &OutputCtx::default(),
+        )
+        .unwrap();
 
+        assert_eq!(
+            outcome.summary,
+            ApplySummary {
+                events_processed: 3,
+                events_assigned: 2,
+                events_unassigned: 1,
+                streams_created: 1,
+                streams_reused: 0,
+            }
+        );
         let stream = db.resolve_stream("proposal").unwrap().unwrap();
         let assigned = db.get_events_by_stream(&stream.id).unwrap();
         let assigned_ids: Vec<_> = assigned.iter().map(|event| event.id.as_str()).collect();
@@ -968,6 +1262,92 @@ mod tests {
         assert_eq!(unassigned[0].id, "w3");
     }
 
+    #[test]
+    fn test_classify_apply_verbose_traces_each_new_assignment() {
+        let db = tt_db::Database::open_in_memory().unwrap();
+        for event in [
+            make_event(
+                "e1",
+                ts(0),
+                tt_core::EventType::AgentToolUse,
+                Some("sess-a"),
+                "/project-x",
+            ),
+            make_event(
+                "e2",
+                ts(5),
+                tt_core::EventType::AgentToolUse,
+                Some("sess-a"),
+                "/project-x",
+            ),
+            make_window_event("w1", ts(7), "firefox", "Docs", "local"),
+            make_window_event("w2", ts(8), "slack", "Team", "local"),
+        ] {
+            db.insert_event(&event).unwrap();
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let input_path = dir.path().join("classify.json");
+        std::fs::write(
+            &input_path,
+            serde_json::to_string(&json!({
+                "assign_by_session": [{"session_id": "sess-a", "stream": "proposal"}],
+                "assign_by_event_ids": [{"event_ids": ["e1", "w1"], "stream": "proposal"}]
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+
+        let trace = run_apply(
+            &db,
+            &AllocationConfig::default(),
+            input_path.to_str().unwrap(),
+            true,
+            &OutputCtx::default(),
+        )
+        .unwrap()
+        .trace;
+
+        // e1 was already in the stream via its session, so only w1 is new for the id rule.
+        let reported: Vec<_> = trace
+            .iter()
+            .map(|entry| (entry.event_id.as_str(), entry.signal.as_str()))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                ("e1", "session continuity: sess-a"),
+                ("e2", "session continuity: sess-a"),
+                ("w1", "explicit event id"),
+            ]
+        );
+        assert!(trace.iter().all(|entry| entry.stream == "proposal"));
+        assert_eq!(
+            trace[2].to_string(),
+            format!(
+                "{}  w1  -> proposal  (explicit event id)",
+                ts(7).to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            )
+        );
+
+        let quiet = OutputCtx {
+            quiet: true,
+            ..OutputCtx::default()
+        };
+        let rerun = run_apply(
+            &db,
+            &AllocationConfig::default(),
+            input_path.to_str().unwrap(),
+            false,
+            &quiet,
+        )
+        .unwrap();
+        assert!(rerun.trace.is_empty());
+        // The second run finds the named stream and nothing new to assign
+        assert_eq!(rerun.summary.streams_reused, 1);
+        assert_eq!(rerun.summary.streams_created, 0);
+    }
+
     #[test]
     #[expect(
         clippy::too_many_lines,
@@ -1183,4 +1563,40 @@ mod tests {
         let e = db.get_events_by_stream("user-stream").unwrap();
         assert_eq!(e.len(), 1);
     }
+
+    #[test]
+    fn test_stream_name_hint_prefers_title_over_prompt() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        let mut session = tt_core::session::AgentSession {
+            session_id: "ses_1".to_string(),
+            source: tt_core::session::SessionSource::OpenCode,
+            parent_session_id: None,
+            session_type: tt_core::session::SessionType::User,
+            project_path: "/home/user/project".to_string(),
+            project_name: "project".to_string(),
+            start_time: start,
+            end_time: None,
+            message_count: 1,
+            summary: Some("Refactor auth module".to_string()),
+            user_prompts: Vec::new(),
+            starting_prompt: Some("please look at auth\nand clean it up".to_string()),
+            assistant_message_count: 0,
+            tool_call_count: 0,
+            user_message_timestamps: Vec::new(),
+            tool_call_timestamps: Vec::new(),
+        };
+        assert_eq!(
+            stream_name_hint(&session).as_deref(),
+            Some("Refactor auth module")
+        );
+
+        session.summary = None;
+        assert_eq!(
+            stream_name_hint(&session).as_deref(),
+            Some("please look at auth")
+        );
+
+        session.starting_prompt = None;
+        assert_eq!(stream_name_hint(&session), None);
+    }
 }
@@ -0,0 +1,481 @@
+//! Shell completion: static scripts and dynamic value lookups.
+//!
+//! `tt completions <shell>` prints a script generated from the clap command
+//! tree. For bash, zsh and fish the script also wraps the generated completion
+//! function: when the word being completed is a stream, tag or project argument,
+//! the wrapper asks `tt __complete <kind>`, which prints real values from the
+//! database, one per line. Which arguments those are is read from the clap tree
+//! by argument name, so new `stream`/`tag`/`project` arguments complete too.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use clap::CommandFactory;
+use clap_complete::Shell;
+use tt_db::Database;
+
+use crate::Cli;
+
+/// Kind of value that `tt __complete` lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CompletionKind {
+    /// Stream names, or IDs for unnamed streams.
+    Streams,
+    /// Tags used on any stream.
+    Tags,
+    /// `git_project` values, most events first.
+    Projects,
+}
+
+impl CompletionKind {
+    /// The `tt __complete` argument for this kind.
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Streams => "streams",
+            Self::Tags => "tags",
+            Self::Projects => "projects",
+        }
+    }
+
+    /// The kind of value an argument takes, judged by its name.
+    fn of_arg(arg: &clap::Arg) -> Option<Self> {
+        match arg.get_id().as_str() {
+            "stream" => Some(Self::Streams),
+            "tag" | "tags" | "include_tags" | "exclude_tags" | "old" | "from" | "to" => {
+                Some(Self::Tags)
+            }
+            "project" => Some(Self::Projects),
+            _ => None,
+        }
+    }
+}
+
+/// Where stream, tag and project values go on a `tt` command line.
+#[derive(Debug, Default)]
+struct DynamicArgs {
+    /// Subcommand paths joined with `/`, e.g. `streams/show`.
+    subcommands: BTreeSet<String>,
+    /// Flags that take a value, so the value isn't counted as a positional.
+    value_flags: BTreeSet<String>,
+    /// Flags whose value is completed dynamically.
+    flags: BTreeMap<String, CompletionKind>,
+    /// `(subcommand path, positional index)` pairs completed dynamically.
+    positionals: BTreeMap<(String, usize), CompletionKind>,
+}
+
+impl DynamicArgs {
+    fn collect(cmd: &clap::Command) -> Self {
+        let mut args = Self::default();
+        args.visit(cmd, "");
+        args
+    }
+
+    fn visit(&mut self, cmd: &clap::Command, path: &str) {
+        for arg in cmd.get_arguments() {
+            if arg.is_positional() || !arg.get_action().takes_values() {
+                continue;
+            }
+            let kind = CompletionKind::of_arg(arg);
+            let names = arg
+                .get_long()
+                .map(|long| format!("--{long}"))
+                .into_iter()
+                .chain(arg.get_short().map(|short| format!("-{short}")));
+            for name in names {
+                if let Some(kind) = kind {
+                    self.flags.entry(name.clone()).or_insert(kind);
+                }
+                self.value_flags.insert(name);
+            }
+        }
+        for (index, arg) in cmd.get_positionals().enumerate() {
+            if let Some(kind) = CompletionKind::of_arg(arg) {
+                self.positionals.insert((path.to_string(), index), kind);
+            }
+        }
+        for sub in cmd.get_subcommands() {
+            let sub_path = if path.is_empty() {
+                sub.get_name().to_string()
+            } else {
+                format!("{path}/{}", sub.get_name())
+            };
+            self.subcommands.insert(sub_path.clone());
+            self.visit(sub, &sub_path);
+        }
+    }
+
+    /// Groups flags and `path:index` positionals by the kind they complete.
+    fn by_kind(&self) -> BTreeMap<&'static str, (Vec<&str>, Vec<String>)> {
+        let mut groups: BTreeMap<&'static str, (Vec<&str>, Vec<String>)> = BTreeMap::new();
+        for (flag, kind) in &self.flags {
+            groups.entry(kind.name()).or_default().0.push(flag);
+        }
+        for ((path, index), kind) in &self.positionals {
+            groups
+                .entry(kind.name())
+                .or_default()
+                .1
+                .push(format!("{path}:{index}"));
+        }
+        groups
+    }
+
+    fn join<'a>(items: impl IntoIterator<Item = &'a String>) -> String {
+        items
+            .into_iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// A POSIX `__tt_complete_kind` function shared by the bash and zsh wrappers.
+    ///
+    /// Given the words after `tt` and before the cursor, it prints the kind of
+    /// value being completed, or fails when the word isn't dynamic.
+    fn posix_function(&self) -> String {
+        let mut flag_cases = String::new();
+        let mut positional_cases = String::new();
+        for (kind, (flags, positionals)) in self.by_kind() {
+            if !flags.is_empty() {
+                writeln!(flag_cases, "        {}) echo {kind} ;;", flags.join("|")).unwrap();
+            }
+            if !positionals.is_empty() {
+                writeln!(
+                    positional_cases,
+                    "                {}) echo {kind} ;;",
+                    positionals.join("|")
+                )
+                .unwrap();
+            }
+        }
+        POSIX_KIND_FUNCTION
+            .replace("@VALUE_FLAGS@", &Self::join(&self.value_flags))
+            .replace("@SUBCOMMANDS@", &Self::join(&self.subcommands))
+            .replace("@FLAG_CASES@", &flag_cases)
+            .replace("@POSITIONAL_CASES@", &positional_cases)
+    }
+
+    /// The fish equivalent of [`Self::posix_function`].
+    fn fish_function(&self) -> String {
+        let mut flag_cases = String::new();
+        let mut positional_cases = String::new();
+        for (kind, (flags, positionals)) in self.by_kind() {
+            if !flags.is_empty() {
+                writeln!(
+                    flag_cases,
+                    "        if contains -- $flag {}\n            echo {kind}\n            return 0\n        end",
+                    flags.join(" ")
+                )
+                .unwrap();
+            }
+            if !positionals.is_empty() {
+                writeln!(
+                    positional_cases,
+                    "        case {}\n            echo {kind}",
+                    positionals.join(" ")
+                )
+                .unwrap();
+            }
+        }
+        FISH_KIND_FUNCTION
+            .replace("@VALUE_FLAGS@", &Self::join(&self.value_flags))
+            .replace("@SUBCOMMANDS@", &Self::join(&self.subcommands))
+            .replace("@FLAG_CASES@", &flag_cases)
+            .replace("@POSITIONAL_CASES@", &positional_cases)
+    }
+}
+
+const POSIX_KIND_FUNCTION: &str = r#"
+__tt_complete_kind() {
+    local sub="" flag="" n=0 word
+    for word in "$@"; do
+        if [ -n "$flag" ]; then
+            flag=""
+            continue
+        fi
+        case "$word" in
+            -*=*) continue ;;
+            -*)
+                case " @VALUE_FLAGS@ " in
+                    *" $word "*) flag="$word" ;;
+                esac
+                continue
+                ;;
+        esac
+        if [ "$n" -eq 0 ]; then
+            case " @SUBCOMMANDS@ " in
+                *" ${sub:+$sub/}$word "*)
+                    sub="${sub:+$sub/}$word"
+                    continue
+                    ;;
+            esac
+        fi
+        n=$((n + 1))
+    done
+    case "$flag" in
+@FLAG_CASES@        "")
+            case "${sub}:${n}" in
+@POSITIONAL_CASES@                *) return 1 ;;
+            esac
+            ;;
+        *) return 1 ;;
+    esac
+}
+"#;
+
+const BASH_WRAPPER: &str = r#"
+_tt_dynamic() {
+    local kind
+    if kind=$(__tt_complete_kind "${COMP_WORDS[@]:1:COMP_CWORD-1}"); then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$(tt __complete "$kind" 2>/dev/null)" -- "${COMP_WORDS[COMP_CWORD]}"))
+        return 0
+    fi
+    _tt "$@"
+}
+
+complete -F _tt_dynamic -o bashdefault -o default tt
+"#;
+
+/// Replaces `_tt` before the generated script dispatches to it, so both an
+/// autoloaded `_tt` file and a sourced script use the dynamic values.
+const ZSH_WRAPPER: &str = r#"
+(( $+functions[_tt_static] )) || functions[_tt_static]=$functions[_tt]
+_tt() {
+    local kind
+    if kind=$(__tt_complete_kind "${(@)words[2,CURRENT-1]}"); then
+        local -a values
+        values=(${(f)"$(tt __complete "$kind" 2>/dev/null)"})
+        compadd -a values
+        return
+    fi
+    _tt_static "$@"
+}
+
+"#;
+
+/// Last block of the clap zsh script, which calls or registers `_tt`.
+const ZSH_DISPATCH: &str = "if [ \"$funcstack[1]\" = \"_tt\" ]; then";
+
+const FISH_KIND_FUNCTION: &str = r#"
+function __tt_complete_kind
+    set -l words (commandline -opc)
+    set -e words[1]
+    set -l sub ''
+    set -l flag ''
+    set -l n 0
+    for word in $words
+        if test -n "$flag"
+            set flag ''
+            continue
+        end
+        if string match -q -- '-*' $word
+            if not string match -q -- '*=*' $word; and contains -- $word @VALUE_FLAGS@
+                set flag $word
+            end
+            continue
+        end
+        set -l next $word
+        if test -n "$sub"
+            set next $sub/$word
+        end
+        if test $n -eq 0; and contains -- $next @SUBCOMMANDS@
+            set sub $next
+            continue
+        end
+        set n (math $n + 1)
+    end
+    if test -n "$flag"
+@FLAG_CASES@        return 1
+    end
+    switch "$sub:$n"
+@POSITIONAL_CASES@        case '*'
+            return 1
+    end
+end
+
+complete -c tt -f -n '__tt_complete_kind >/dev/null' -a '(tt __complete (__tt_complete_kind))'
+"#;
+
+/// Writes the completion script for `shell` to `out`.
+///
+/// Bash, zsh and fish scripts complete stream, tag and project values through
+/// `tt __complete`; other shells get the static script only.
+pub fn generate(shell: Shell, out: &mut impl Write) -> std::io::Result<()> {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "tt", &mut script);
+    let args = DynamicArgs::collect(&Cli::command());
+    match shell {
+        Shell::Bash => {
+            out.write_all(&script)?;
+            write!(out, "{}{BASH_WRAPPER}", args.posix_function())
+        }
+        Shell::Zsh => {
+            let script = String::from_utf8_lossy(&script);
+            let wrapper = format!("{}{ZSH_WRAPPER}", args.posix_function());
+            match script.rfind(ZSH_DISPATCH) {
+                Some(at) => write!(out, "{}{wrapper}{}", &script[..at], &script[at..]),
+                None => writeln!(out, "{script}{wrapper}compdef _tt tt"),
+            }
+        }
+        Shell::Fish => {
+            out.write_all(&script)?;
+            write!(out, "{}", args.fish_function())
+        }
+        _ => out.write_all(&script),
+    }
+}
+
+/// Runs `tt completions`, printing the script to stdout.
+pub fn run(shell: Shell) -> Result<()> {
+    generate(shell, &mut std::io::stdout().lock()).context("failed to write completion script")
+}
+
+/// Returns the completion candidates of one kind, deduplicated.
+pub fn candidates(db: &Database, kind: CompletionKind) -> Result<Vec<String>> {
+    Ok(match kind {
+        CompletionKind::Streams => {
+            let streams = db.get_streams().context("failed to get streams")?;
+            let names: BTreeSet<String> = streams
+                .into_iter()
+                .map(|stream| stream.name.unwrap_or(stream.id))
+                .collect();
+            names.into_iter().collect()
+        }
+        CompletionKind::Tags => {
+            let tags: BTreeSet<String> = db
+                .get_all_tags()
+                .context("failed to get tags")?
+                .into_iter()
+                .flat_map(|(_, tags)| tags)
+                .collect();
+            tags.into_iter().collect()
+        }
+        CompletionKind::Projects => db
+            .get_distinct_projects()
+            .context("failed to get projects")?
+            .into_iter()
+            .map(|(project, _)| project)
+            .collect(),
+    })
+}
+
+/// Runs `tt __complete`, printing one candidate per line.
+pub fn run_complete(db: &Database, kind: CompletionKind) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    for candidate in candidates(db, kind)? {
+        writeln!(stdout, "{candidate}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_support::make_stream;
+
+    #[test]
+    fn test_generates_script_for_each_shell() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut out = Vec::new();
+            generate(shell, &mut out).unwrap();
+            let script = String::from_utf8(out).unwrap();
+            assert!(script.contains("tt"), "{shell} script is empty");
+            assert!(
+                script.contains("report"),
+                "{shell} script lacks subcommands"
+            );
+            assert!(
+                script.contains("__tt_complete_kind") && script.contains("tt __complete"),
+                "{shell} script doesn't call the __complete helper"
+            );
+        }
+    }
+
+    #[test]
+    fn test_zsh_wrapper_replaces_tt_before_dispatch() {
+        let mut out = Vec::new();
+        generate(Shell::Zsh, &mut out).unwrap();
+        let script = String::from_utf8(out).unwrap();
+
+        let wrapper = script.find("functions[_tt_static]=").unwrap();
+        assert!(wrapper < script.rfind(ZSH_DISPATCH).unwrap());
+    }
+
+    #[test]
+    fn test_dynamic_args_found_by_name() {
+        let args = DynamicArgs::collect(&Cli::command());
+
+        assert_eq!(args.flags.get("--tag"), Some(&CompletionKind::Tags));
+        assert_eq!(args.flags.get("--exclude-tag"), Some(&CompletionKind::Tags));
+        assert_eq!(args.flags.get("--project"), Some(&CompletionKind::Projects));
+        assert!(args.value_flags.contains("--config"));
+        assert!(!args.value_flags.contains("--quiet"));
+        for (path, index, kind) in [
+            ("tag", 0, CompletionKind::Streams),
+            ("tag", 1, CompletionKind::Tags),
+            ("tag/merge", 0, CompletionKind::Tags),
+            ("streams/show", 0, CompletionKind::Streams),
+            ("events/reassign", 1, CompletionKind::Streams),
+        ] {
+            assert_eq!(
+                args.positionals.get(&(path.to_string(), index)),
+                Some(&kind),
+                "{path}:{index}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_posix_function_picks_kind_from_words() {
+        let function = DynamicArgs::collect(&Cli::command()).posix_function();
+        let kind = |words: &str| {
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("{function}\n__tt_complete_kind {words}"))
+                .output()
+                .unwrap();
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8(output.stdout).unwrap().trim().to_string())
+        };
+
+        assert_eq!(kind("tag").as_deref(), Some("streams"));
+        assert_eq!(kind("tag my-stream").as_deref(), Some("tags"));
+        assert_eq!(kind("--config tt.toml tag").as_deref(), Some("streams"));
+        assert_eq!(kind("report --tag").as_deref(), Some("tags"));
+        assert_eq!(kind("infer --project").as_deref(), Some("projects"));
+        assert_eq!(kind("streams show").as_deref(), Some("streams"));
+        assert_eq!(kind("events reassign e1").as_deref(), Some("streams"));
+        assert_eq!(kind("report"), None);
+        assert_eq!(kind("report --config"), None);
+    }
+
+    #[test]
+    fn test_candidates_list_stream_names_and_deduplicated_tags() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_stream(&make_stream("s1", Some("time-tracker")))
+            .unwrap();
+        db.insert_stream(&make_stream("s2", None)).unwrap();
+        db.add_tag("s1", "acme").unwrap();
+        db.add_tag("s2", "acme").unwrap();
+        db.add_tag("s2", "urgent").unwrap();
+
+        assert_eq!(
+            candidates(&db, CompletionKind::Streams).unwrap(),
+            vec!["s2", "time-tracker"]
+        );
+        assert_eq!(
+            candidates(&db, CompletionKind::Tags).unwrap(),
+            vec!["acme", "urgent"]
+        );
+        assert!(
+            candidates(&db, CompletionKind::Projects)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
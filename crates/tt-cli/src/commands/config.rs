@@ -0,0 +1,26 @@
+//! Config command: shows where configuration lives and what it resolves to.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::Config;
+
+/// Prints the config file path: the `--config` override, else the default location.
+pub fn path(config_path: Option<&Path>) -> Result<()> {
+    let path = match config_path {
+        Some(path) => path.to_path_buf(),
+        None => Config::default_path().context("could not determine config directory")?,
+    };
+    println!("{}", path.display());
+    Ok(())
+}
+
+/// Prints the effective merged configuration as TOML.
+pub fn show(config: &Config) -> Result<()> {
+    let toml = config
+        .to_redacted_toml()
+        .context("failed to serialize configuration")?;
+    print!("{toml}");
+    Ok(())
+}
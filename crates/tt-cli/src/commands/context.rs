@@ -136,7 +136,7 @@ fn export_agents(
     end: DateTime<Utc>,
 ) -> anyhow::Result<Vec<AgentExport>> {
     Ok(db
-        .agent_sessions_in_range(start, end)?
+        .agent_sessions_in_range(start, end, None)?
         .into_iter()
         .map(|s| AgentExport {
             user_prompt_count: i32::try_from(s.user_prompts.len()).unwrap_or(i32::MAX),
@@ -349,6 +349,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: None,
             cwd: None,
             session_id: None,
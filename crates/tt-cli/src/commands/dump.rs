@@ -0,0 +1,153 @@
+//! Dump command: writes the database's events to stdout as JSONL.
+//!
+//! Unlike `tt export`, which re-reads the source logs, the dump reflects the
+//! imported, post-inference state, so each event carries its `stream_id` and
+//! `assignment_source`. Useful for backups and for inspecting assignments.
+//!
+//! With `--anonymize`, identifying strings are swapped for placeholder tokens so
+//! the dump can be attached to a bug report. The dump holds events only, so
+//! session prompts and summaries never appear in it.
+
+use std::collections::HashMap;
+use std::io::{BufWriter, Write};
+
+use anyhow::{Context, Result};
+use tt_db::{Database, StoredEvent};
+
+use crate::output::OutputCtx;
+
+/// Replaces identifying event fields with stable placeholder tokens.
+///
+/// Each distinct value maps to one token for the whole dump (every occurrence of
+/// a cwd becomes the same `path-N`), so the structure allocation and inference
+/// depend on survives. Timestamps, types, sources, app IDs, session IDs and
+/// stream assignments are kept as-is.
+#[derive(Debug, Default)]
+pub struct Anonymizer {
+    tokens: HashMap<(&'static str, String), String>,
+    counts: HashMap<&'static str, usize>,
+}
+
+impl Anonymizer {
+    /// Anonymizes cwd, git project/workspace, tmux session, window title and URL.
+    pub fn anonymize(&mut self, event: &mut StoredEvent) {
+        for (kind, field) in [
+            ("path", &mut event.cwd),
+            ("project", &mut event.git_project),
+            ("workspace", &mut event.git_workspace),
+            ("tmux-session", &mut event.tmux_session),
+            ("title", &mut event.window_title),
+            ("url", &mut event.url),
+        ] {
+            if let Some(value) = field {
+                *value = self.token(kind, value);
+            }
+        }
+    }
+
+    fn token(&mut self, kind: &'static str, value: &str) -> String {
+        let counts = &mut self.counts;
+        self.tokens
+            .entry((kind, value.to_string()))
+            .or_insert_with(|| {
+                let count = counts.entry(kind).or_default();
+                *count += 1;
+                format!("{kind}-{count}")
+            })
+            .clone()
+    }
+}
+
+/// Writes every stored event to `writer`, anonymized if requested.
+///
+/// Returns the number of events written.
+pub fn dump_to<W: Write>(db: &Database, writer: W, anonymize: bool) -> Result<usize> {
+    let count = if anonymize {
+        let mut anonymizer = Anonymizer::default();
+        db.export_events_jsonl_with(writer, |event| anonymizer.anonymize(event))
+    } else {
+        db.export_events_jsonl(writer)
+    };
+    count.context("failed to dump events")
+}
+
+/// Writes every stored event to stdout and, unless `ctx.quiet`, reports the
+/// count on stderr.
+pub fn run(db: &Database, anonymize: bool, ctx: &OutputCtx) -> Result<()> {
+    let stdout = std::io::stdout();
+    let count = dump_to(db, BufWriter::new(stdout.lock()), anonymize)?;
+    ctx.info_stderr(format_args!("Dumped {count} events"));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::*;
+
+    fn make_event(id: &str, minute: i64, cwd: &str) -> StoredEvent {
+        StoredEvent {
+            id: id.to_string(),
+            timestamp: Utc.with_ymd_and_hms(2025, 1, 29, 9, 0, 0).unwrap()
+                + Duration::minutes(minute),
+            event_type: tt_core::EventType::TmuxPaneFocus,
+            source: "remote.tmux".to_string(),
+            machine_id: None,
+            schema_version: 1,
+            pane_id: Some("%1".to_string()),
+            tmux_session: Some("client-work".to_string()),
+            window_index: None,
+            git_project: Some("acme-secret".to_string()),
+            git_workspace: None,
+            status: None,
+            idle_duration_ms: None,
+            window_app_id: None,
+            window_title: None,
+            url: None,
+            action: None,
+            cwd: Some(cwd.to_string()),
+            session_id: None,
+            stream_id: None,
+            assignment_source: None,
+            data: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_anonymized_dump_hides_paths_but_keeps_structure() {
+        let db = Database::open_in_memory().unwrap();
+        let mut browser = make_event("e3", 2, "/home/alice/acme");
+        browser.event_type = tt_core::EventType::BrowserTab;
+        browser.url = Some("https://acme.example.com/roadmap".to_string());
+        db.insert_events(&[
+            make_event("e1", 0, "/home/alice/acme"),
+            make_event("e2", 1, "/home/alice/acme/api"),
+            browser,
+        ])
+        .unwrap();
+
+        let mut output = Vec::new();
+        assert_eq!(dump_to(&db, &mut output, true).unwrap(), 3);
+        let output = String::from_utf8(output).unwrap();
+
+        for original in ["/home/alice", "acme", "client-work"] {
+            assert!(!output.contains(original), "{original} leaked: {output}");
+        }
+        let events: Vec<StoredEvent> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let originals = db.get_events(None, None).unwrap();
+        assert_eq!(events.len(), originals.len());
+        for (event, original) in events.iter().zip(&originals) {
+            assert_eq!(event.id, original.id);
+            assert_eq!(event.event_type, original.event_type);
+            assert_eq!(event.timestamp, original.timestamp);
+        }
+        // Stable per distinct value
+        let cwds: Vec<_> = events.iter().map(|e| e.cwd.as_deref().unwrap()).collect();
+        assert_eq!(cwds, vec!["path-1", "path-2", "path-1"]);
+        assert_eq!(events[2].url.as_deref(), Some("url-1"));
+    }
+}
@@ -0,0 +1,155 @@
+//! Events command for inspecting and correcting individual events.
+
+use anyhow::{Context, Result, bail};
+use tt_db::Database;
+
+use super::util::parse_datetime;
+use crate::output::OutputCtx;
+
+/// Prints the number of events strictly between `after` and `before`.
+///
+/// Uses `SELECT COUNT(*)` rather than loading the events, so it stays fast on
+/// large databases (e.g. checking a sync brought the expected volume).
+pub fn count(db: &Database, after: Option<&str>, before: Option<&str>) -> Result<()> {
+    let after = after.map(parse_datetime).transpose()?;
+    let before = before.map(parse_datetime).transpose()?;
+    let count = db
+        .count_events_in_range(after, before)
+        .context("failed to count events")?;
+    println!("{count}");
+    Ok(())
+}
+
+/// Reassigns a single event to a stream as a user assignment.
+///
+/// Both the event's previous stream (if any) and the target stream are marked
+/// for recompute so their time totals catch up on the next `tt recompute`.
+pub fn reassign(db: &Database, event_id: &str, stream: &str, ctx: &OutputCtx) -> Result<()> {
+    let Some(event) = db.get_event(event_id).context("failed to query events")? else {
+        bail!("Event '{event_id}' not found.");
+    };
+
+    let Some(target) = db
+        .resolve_stream(stream)
+        .context("failed to query streams")?
+    else {
+        bail!(
+            "Stream '{stream}' not found.\n\nHint: Use 'tt streams' to see available stream IDs."
+        );
+    };
+
+    db.assign_event_to_stream(event_id, &target.id, "user")
+        .context("failed to reassign event")?;
+
+    let mut affected = vec![target.id.as_str()];
+    if let Some(previous) = event.stream_id.as_deref() {
+        if previous != target.id {
+            affected.push(previous);
+        }
+    }
+    db.mark_streams_for_recompute(&affected)
+        .context("failed to mark streams for recompute")?;
+
+    let target_name = target.name.as_deref().unwrap_or("<unnamed>");
+    match event.stream_id.as_deref() {
+        Some(previous) => ctx.info(format_args!(
+            "Reassigned event {event_id} from {previous} to {} ({target_name})",
+            target.id
+        )),
+        None => ctx.info(format_args!(
+            "Assigned event {event_id} to {} ({target_name})",
+            target.id
+        )),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tt_db::{StoredEvent, Stream};
+
+    fn make_stream(id: &str, name: &str) -> Stream {
+        let now = Utc::now();
+        Stream {
+            id: id.to_string(),
+            name: Some(name.to_string()),
+            created_at: now,
+            updated_at: now,
+            time_direct_ms: 0,
+            time_delegated_ms: 0,
+            first_event_at: None,
+            last_event_at: None,
+            needs_recompute: false,
+        }
+    }
+
+    fn make_event(id: &str, stream_id: &str) -> StoredEvent {
+        StoredEvent {
+            id: id.to_string(),
+            timestamp: Utc::now(),
+            event_type: tt_core::EventType::TmuxPaneFocus,
+            source: "remote.tmux".to_string(),
+            machine_id: None,
+            schema_version: 1,
+            pane_id: Some("%1".to_string()),
+            tmux_session: None,
+            window_index: None,
+            git_project: None,
+            git_workspace: None,
+            status: None,
+            idle_duration_ms: None,
+            window_app_id: None,
+            window_title: None,
+            url: None,
+            action: None,
+            cwd: Some("/home/user/project".to_string()),
+            session_id: None,
+            stream_id: Some(stream_id.to_string()),
+            assignment_source: Some("inferred".to_string()),
+            data: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_reassign_marks_old_and_new_streams_for_recompute() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_stream(&make_stream("stream-old", "old")).unwrap();
+        db.insert_stream(&make_stream("stream-new", "new")).unwrap();
+        db.insert_event(&make_event("event-1", "stream-old"))
+            .unwrap();
+
+        reassign(&db, "event-1", "new", &OutputCtx::default()).unwrap();
+
+        let event = db.get_event("event-1").unwrap().unwrap();
+        assert_eq!(event.stream_id.as_deref(), Some("stream-new"));
+        assert_eq!(event.assignment_source.as_deref(), Some("user"));
+
+        let mut flagged: Vec<String> = db
+            .get_streams_needing_recompute()
+            .unwrap()
+            .into_iter()
+            .map(|stream| stream.id)
+            .collect();
+        flagged.sort();
+        assert_eq!(flagged, vec!["stream-new", "stream-old"]);
+    }
+
+    #[test]
+    fn test_reassign_rejects_unknown_event_or_stream() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_stream(&make_stream("stream-old", "old")).unwrap();
+        db.insert_event(&make_event("event-1", "stream-old"))
+            .unwrap();
+
+        let err = reassign(&db, "missing", "old", &OutputCtx::default()).unwrap_err();
+        assert!(err.to_string().contains("Event 'missing' not found"));
+
+        let err = reassign(&db, "event-1", "missing", &OutputCtx::default()).unwrap_err();
+        assert!(err.to_string().contains("Stream 'missing' not found"));
+        let event = db.get_event("event-1").unwrap().unwrap();
+        assert_eq!(event.stream_id.as_deref(), Some("stream-old"));
+    }
+}
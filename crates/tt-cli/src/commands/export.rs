@@ -8,8 +8,9 @@ use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use chrono::{DateTime, SecondsFormat, Utc};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -62,6 +63,35 @@ pub struct AgentToolUseData {
     pub cwd: Option<String>,
 }
 
+/// Version of the export record format, bumped on incompatible changes.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Leading record describing an export stream, emitted with `--header`.
+///
+/// Carries no `timestamp`, so `--after-timestamp` never drops it. Importers
+/// skip it without counting it as malformed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportHeader {
+    /// Always `"export_header"` — distinguishes from event records.
+    #[serde(rename = "type")]
+    pub record_type: String,
+    pub format_version: u32,
+    pub generated_at: String,
+    pub machine_id: String,
+}
+
+impl ExportHeader {
+    /// Creates a header for an export generated at `generated_at` on `machine_id`.
+    pub fn new(machine_id: &str, generated_at: DateTime<Utc>) -> Self {
+        Self {
+            record_type: "export_header".to_string(),
+            format_version: EXPORT_FORMAT_VERSION,
+            generated_at: generated_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            machine_id: machine_id.to_string(),
+        }
+    }
+}
+
 /// Metadata record for agent sessions in the JSONL export stream.
 ///
 /// Emitted alongside events. Importers that don't recognize this record type
@@ -240,6 +270,9 @@ fn default_opencode_db_path() -> PathBuf {
         .join("opencode/opencode.db")
 }
 
+/// Source recorded on every event emitted from Claude and `OpenCode` sessions.
+const AGENT_SOURCE: &str = "remote.agent";
+
 fn parse_after_timestamp(after: Option<&str>) -> Option<DateTime<Utc>> {
     let rest = after?.splitn(4, ':').nth(3)?;
     let timestamp = rest.get(..24)?;
@@ -247,9 +280,34 @@ fn parse_after_timestamp(after: Option<&str>) -> Option<DateTime<Utc>> {
 }
 
 /// Runs the export command, outputting all events to stdout.
-pub fn run(after: Option<&str>, since: Option<&str>) -> Result<()> {
+///
+/// When `source` is provided, only records whose `source` starts with that
+/// prefix are emitted (e.g., `remote.agent` or `remote.tmux`).
+///
+/// When `strict` is set, `events.jsonl` is validated up front and the export
+/// fails without emitting anything if any line is malformed.
+///
+/// When `after_timestamp` is provided, every emitted record (tmux and agent
+/// alike) must be strictly after it; see [`CutoffWriter`].
+///
+/// When `header` is set, an [`ExportHeader`] is written before any record.
+pub fn run(
+    after: Option<&str>,
+    after_timestamp: Option<&str>,
+    since: Option<&str>,
+    source: Option<&str>,
+    strict: bool,
+    header: bool,
+) -> Result<()> {
     let identity = crate::machine::require_machine_identity()?;
     let data_dir = default_data_dir();
+
+    if strict {
+        let events_file = data_dir.join("events.jsonl");
+        if events_file.exists() {
+            validate_tmux_events(&events_file)?;
+        }
+    }
     let state_dir = crate::config::dirs_state_path().unwrap_or_else(|| data_dir.clone());
 
     // Parse since timestamp if provided
@@ -262,17 +320,105 @@ pub fn run(after: Option<&str>, since: Option<&str>) -> Result<()> {
     } else {
         None
     };
-
+    let cutoff = after_timestamp
+        .map(|value| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|ts| ts.with_timezone(&Utc))
+                .map_err(|e| anyhow::anyhow!("invalid --after-timestamp '{value}': {e}"))
+        })
+        .transpose()?;
+
+    let mut stdout = std::io::stdout();
+    if header {
+        let header = ExportHeader::new(&identity.machine_id, Utc::now());
+        writeln!(stdout, "{}", serde_json::to_string(&header)?)?;
+    }
+    let Some(cutoff) = cutoff else {
+        return run_impl(
+            &data_dir,
+            &default_claude_dir(),
+            &state_dir,
+            Some(&default_opencode_db_path()),
+            &identity.machine_id,
+            after,
+            since_dt.as_ref(),
+            source,
+            &mut stdout,
+        );
+    };
+    // Sessions untouched since the cutoff can't contribute any events.
+    let since_dt = since_dt.or(Some(cutoff));
+    let mut output = CutoffWriter::new(&mut stdout, cutoff);
     run_impl(
         &data_dir,
         &default_claude_dir(),
         &state_dir,
         Some(&default_opencode_db_path()),
         &identity.machine_id,
-        after,
+        None,
         since_dt.as_ref(),
-        &mut std::io::stdout(),
-    )
+        source,
+        &mut output,
+    )?;
+    output.flush()?;
+    Ok(())
+}
+
+/// Drops JSONL records whose `timestamp` is at or before a cutoff.
+///
+/// Records without a parseable `timestamp` (e.g. session metadata) pass
+/// through: importing them twice is harmless, losing them is not. Incomplete
+/// lines are buffered until their newline arrives or the writer is flushed.
+struct CutoffWriter<'a> {
+    inner: &'a mut dyn Write,
+    cutoff: DateTime<Utc>,
+    pending: Vec<u8>,
+}
+
+impl<'a> CutoffWriter<'a> {
+    fn new(inner: &'a mut dyn Write, cutoff: DateTime<Utc>) -> Self {
+        Self {
+            inner,
+            cutoff,
+            pending: Vec::new(),
+        }
+    }
+
+    fn keeps(&self, line: &[u8]) -> bool {
+        serde_json::from_slice::<Value>(line)
+            .ok()
+            .and_then(|value| {
+                value
+                    .get("timestamp")?
+                    .as_str()?
+                    .parse::<DateTime<Utc>>()
+                    .ok()
+            })
+            .is_none_or(|timestamp| timestamp > self.cutoff)
+    }
+}
+
+impl Write for CutoffWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        while let Some(newline) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=newline).collect();
+            if self.keeps(&line[..newline]) {
+                self.inner.write_all(&line)?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.pending.is_empty() {
+            let line = std::mem::take(&mut self.pending);
+            if self.keeps(&line) {
+                self.inner.write_all(&line)?;
+            }
+        }
+        self.inner.flush()
+    }
 }
 
 /// Implementation of export that allows injecting paths for testing.
@@ -288,12 +434,20 @@ fn run_impl(
     machine_id: &str,
     after: Option<&str>,
     since: Option<&chrono::DateTime<chrono::Utc>>,
+    source: Option<&str>,
     output: &mut dyn Write,
 ) -> Result<()> {
     // Export tmux events
     let events_file = data_dir.join("events.jsonl");
     if events_file.exists() {
-        export_tmux_events(&events_file, after, output)?;
+        export_tmux_events(&events_file, after, source, output)?;
+    }
+
+    // Agent emitters only produce `remote.agent` records, so they are either
+    // included or excluded as a whole.
+    let include_agent = source.is_none_or(|prefix| AGENT_SOURCE.starts_with(prefix));
+    if !include_agent {
+        return Ok(());
     }
 
     // Export Claude events with incremental parsing
@@ -316,9 +470,13 @@ fn run_impl(
 /// strictly after that timestamp. This uses timestamp comparison rather than
 /// ID matching because the `--after` ID may be from a different event source
 /// (e.g., an agent event) that doesn't exist in events.jsonl.
+///
+/// When `source` is provided, lines whose `source` field doesn't start with
+/// that prefix are skipped. Lines without a parseable source are skipped too.
 fn export_tmux_events(
     events_file: &Path,
     after: Option<&str>,
+    source: Option<&str>,
     output: &mut dyn Write,
 ) -> Result<()> {
     let file = File::open(events_file).context("failed to open events.jsonl")?;
@@ -354,13 +512,28 @@ fn export_tmux_events(
             // (better to duplicate than lose data)
         }
 
+        if let Some(prefix) = source {
+            let matches = serde_json::from_str::<serde_json::Value>(&line)
+                .ok()
+                .and_then(|value| {
+                    value
+                        .get("source")
+                        .and_then(serde_json::Value::as_str)
+                        .map(|s| s.starts_with(prefix))
+                })
+                .unwrap_or(false);
+            if !matches {
+                continue;
+            }
+        }
+
         // Validate it's valid JSON before passing through (use RawValue to avoid parsing overhead)
         match serde_json::from_str::<&serde_json::value::RawValue>(&line) {
             Ok(_) => {
                 writeln!(output, "{line}").context("failed to write event")?;
             }
             Err(e) => {
-                tracing::warn!(line = line_num + 1, error = %e, "malformed JSON, skipping");
+                tracing::warn!(skipped = true, line = line_num + 1, error = %e, "malformed JSON, skipping");
             }
         }
     }
@@ -368,6 +541,36 @@ fn export_tmux_events(
     Ok(())
 }
 
+/// Checks that every non-empty line of events.jsonl is valid JSON.
+///
+/// Returns an error listing the 1-based numbers of all malformed lines.
+fn validate_tmux_events(events_file: &Path) -> Result<()> {
+    let file = File::open(events_file).context("failed to open events.jsonl")?;
+    let reader = BufReader::new(file);
+
+    let mut malformed = Vec::new();
+    for (line_num, line) in reader.lines().enumerate() {
+        let valid = line.is_ok_and(|l| {
+            l.trim().is_empty() || serde_json::from_str::<&serde_json::value::RawValue>(&l).is_ok()
+        });
+        if !valid {
+            malformed.push(line_num + 1);
+        }
+    }
+
+    if malformed.is_empty() {
+        return Ok(());
+    }
+
+    let lines: Vec<String> = malformed.iter().map(ToString::to_string).collect();
+    bail!(
+        "{} malformed line(s) in {}: {}",
+        malformed.len(),
+        events_file.display(),
+        lines.join(", ")
+    );
+}
+
 /// Discovers Claude session log files.
 fn discover_claude_logs(claude_dir: &Path) -> Result<Vec<PathBuf>> {
     let mut logs = Vec::new();
@@ -418,9 +621,13 @@ fn export_claude_events(
     machine_id: &str,
     output: &mut dyn Write,
 ) -> Result<Vec<PathBuf>> {
-    let logs = discover_claude_logs(claude_dir)?;
+    let mut logs = discover_claude_logs(claude_dir)?;
+    // Sort so output order doesn't depend on directory iteration order
+    logs.sort();
     let mut manifest = ClaudeManifest::load(manifest_path);
 
+    let parsed = parse_claude_logs(&logs, &manifest);
+
     // Track seen sessions across ALL files to avoid duplicate session start events.
     // Maps session_id → cwd so cwd is propagated to all events in the session.
     let mut seen_sessions: HashMap<String, Option<String>> = HashMap::new();
@@ -428,16 +635,12 @@ fn export_claude_events(
     let mut processed_files: HashSet<PathBuf> = HashSet::new();
     let mut files_with_new_content = Vec::new();
 
-    for log_path in logs {
-        let start_offset = manifest.sessions.get(&log_path).copied().unwrap_or(0);
-        match export_single_claude_log(
-            &log_path,
-            &mut seen_sessions,
-            machine_id,
-            output,
-            start_offset,
-        ) {
-            Ok(final_offset) => {
+    for (log_path, (start_offset, result)) in logs.into_iter().zip(parsed) {
+        match result {
+            Ok((final_offset, entries)) => {
+                for entry in &entries {
+                    process_claude_entry(entry, &mut seen_sessions, machine_id, output)?;
+                }
                 if final_offset > start_offset {
                     files_with_new_content.push(log_path.clone());
                 }
@@ -468,7 +671,7 @@ fn export_claude_events(
             .and_then(std::ffi::OsStr::to_str)
             .filter(|id| !id.is_empty())
         else {
-            tracing::warn!(path = %file_path.display(), "skipping session metadata for file with invalid session id");
+            tracing::warn!(skipped = true, path = %file_path.display(), "skipping session metadata for file with invalid session id");
             continue;
         };
 
@@ -528,7 +731,7 @@ fn export_opencode_events(
                 session.session_id
             ),
             timestamp: start_ts,
-            source: "remote.agent".to_string(),
+            source: AGENT_SOURCE.to_string(),
             event_type: "agent_session".to_string(),
             data: serde_json::to_value(AgentSessionData {
                 action: "started".to_string(),
@@ -557,7 +760,7 @@ fn export_opencode_events(
             let event = ExportEvent {
                 id,
                 timestamp,
-                source: "remote.agent".to_string(),
+                source: AGENT_SOURCE.to_string(),
                 event_type: "user_message".to_string(),
                 data: serde_json::to_value(UserMessageData {
                     agent: "opencode".to_string(),
@@ -578,7 +781,7 @@ fn export_opencode_events(
                     session.session_id
                 ),
                 timestamp,
-                source: "remote.agent".to_string(),
+                source: AGENT_SOURCE.to_string(),
                 event_type: "agent_tool_use".to_string(),
                 data: serde_json::to_value(AgentToolUseData {
                     agent: "opencode".to_string(),
@@ -599,7 +802,7 @@ fn export_opencode_events(
                     session.session_id
                 ),
                 timestamp: end_ts,
-                source: "remote.agent".to_string(),
+                source: AGENT_SOURCE.to_string(),
                 event_type: "agent_session".to_string(),
                 data: serde_json::to_value(AgentSessionData {
                     action: "ended".to_string(),
@@ -619,18 +822,30 @@ fn export_opencode_events(
     Ok(())
 }
 
+/// A Claude log's starting manifest offset, and its final offset and parsed entries.
+type ParsedClaudeLog = (u64, Result<(u64, Vec<Value>)>);
+
+/// Reads Claude session logs in parallel, one list of parsed entries per file.
+///
+/// Results are returned in the same order as `logs`, each paired with the
+/// manifest offset the read started from. Only reading and JSON parsing run in
+/// parallel; entries are turned into events serially by the caller so session
+/// state is shared across files.
+fn parse_claude_logs(logs: &[PathBuf], manifest: &ClaudeManifest) -> Vec<ParsedClaudeLog> {
+    logs.par_iter()
+        .map(|log_path| {
+            let start_offset = manifest.sessions.get(log_path).copied().unwrap_or(0);
+            (start_offset, read_claude_log(log_path, start_offset))
+        })
+        .collect()
+}
+
 /// Entry types to filter out (not events).
 const FILTERED_TYPES: &[&str] = &["progress", "file-history-snapshot", "summary", "system"];
 
-/// Exports events from a single Claude session log file.
+/// Reads the entries of a single Claude session log file from `start_offset`.
 /// Returns the byte offset after the last successfully parsed line.
-fn export_single_claude_log(
-    log_path: &Path,
-    seen_sessions: &mut HashMap<String, Option<String>>,
-    machine_id: &str,
-    output: &mut dyn Write,
-    start_offset: u64,
-) -> Result<u64> {
+fn read_claude_log(log_path: &Path, start_offset: u64) -> Result<(u64, Vec<Value>)> {
     let file = File::open(log_path).context("failed to open Claude log")?;
     let file_size = file.metadata()?.len();
 
@@ -656,6 +871,7 @@ fn export_single_claude_log(
     let mut line_num = 0;
     // Reuse String buffer across iterations to avoid repeated allocations
     let mut line = String::new();
+    let mut entries = Vec::new();
 
     loop {
         line.clear();
@@ -673,15 +889,28 @@ fn export_single_claude_log(
 
                 let entry: Value = match serde_json::from_str(&line) {
                     Ok(v) => v,
+                    Err(e) if current_position < file_size => {
+                        // More content follows, so this line is complete but corrupt.
+                        // Re-reading it would never help: skip it for good.
+                        tracing::warn!(
+                            skipped = true,
+                            path = %log_path.display(),
+                            line = line_num,
+                            error = %e,
+                            "skipping corrupt line in Claude log"
+                        );
+                        last_good_position = current_position;
+                        continue;
+                    }
                     Err(e) => {
-                        tracing::warn!(line = line_num, error = %e, "malformed JSON in Claude log");
-                        // Don't update last_good_position - this line may be incomplete
-                        // If it's a partial line at EOF, we'll re-read it next time
+                        // Likely a partial line still being written: don't advance
+                        // last_good_position, so it's re-read next time.
+                        tracing::debug!(line = line_num, error = %e, "partial final line in Claude log");
                         continue;
                     }
                 };
 
-                process_claude_entry(&entry, seen_sessions, machine_id, output)?;
+                entries.push(entry);
                 last_good_position = current_position;
             }
             Err(e) => {
@@ -691,7 +920,7 @@ fn export_single_claude_log(
         }
     }
 
-    Ok(last_good_position)
+    Ok((last_good_position, entries))
 }
 
 /// Processes a single Claude log entry and emits events.
@@ -770,7 +999,7 @@ fn emit_session_start(
     let event = ExportEvent {
         id: format!("{machine_id}:remote.agent:agent_session:{timestamp}:{session_id}:started"),
         timestamp: timestamp.to_string(),
-        source: "remote.agent".to_string(),
+        source: AGENT_SOURCE.to_string(),
         event_type: "agent_session".to_string(),
         data: serde_json::to_value(AgentSessionData {
             action: "started".to_string(),
@@ -811,7 +1040,7 @@ fn emit_user_message(
                 .unwrap_or(session_id)
         ),
         timestamp: timestamp.to_string(),
-        source: "remote.agent".to_string(),
+        source: AGENT_SOURCE.to_string(),
         event_type: "user_message".to_string(),
         data: serde_json::to_value(UserMessageData {
             agent: "claude-code".to_string(),
@@ -859,7 +1088,7 @@ fn emit_tool_uses(
         let event = ExportEvent {
             id: format!("{machine_id}:remote.agent:agent_tool_use:{timestamp}:{tool_id}"),
             timestamp: timestamp.to_string(),
-            source: "remote.agent".to_string(),
+            source: AGENT_SOURCE.to_string(),
             event_type: "agent_tool_use".to_string(),
             data: serde_json::to_value(AgentToolUseData {
                 agent: "claude-code".to_string(),
@@ -1061,6 +1290,7 @@ mod tests {
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         );
 
@@ -1085,6 +1315,7 @@ mod tests {
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1093,6 +1324,43 @@ mod tests {
         assert_eq!(output_str.trim(), event);
     }
 
+    #[test]
+    fn test_export_header_is_first_and_skipped_on_import() {
+        let (_temp, data_dir, claude_dir) = setup_test_dirs();
+        let event = r#"{"id":"tmux1","timestamp":"2025-01-29T12:00:00.000Z","source":"remote.tmux","type":"tmux_pane_focus","data":{"pane_id":"%3","cwd":"/home/user"}}"#;
+        fs::write(data_dir.join("events.jsonl"), format!("{event}\n")).unwrap();
+
+        let generated_at = Utc.with_ymd_and_hms(2025, 1, 30, 8, 0, 0).unwrap();
+        let mut output = Cursor::new(Vec::new());
+        let header = ExportHeader::new(TEST_MACHINE_ID, generated_at);
+        writeln!(output, "{}", serde_json::to_string(&header).unwrap()).unwrap();
+        run_impl(
+            &data_dir,
+            &claude_dir,
+            &data_dir,
+            None,
+            TEST_MACHINE_ID,
+            None,
+            None,
+            None,
+            &mut output,
+        )
+        .unwrap();
+
+        let output_str = String::from_utf8(output.into_inner()).unwrap();
+        let first: ExportHeader = serde_json::from_str(output_str.lines().next().unwrap()).unwrap();
+        assert_eq!(first.record_type, "export_header");
+        assert_eq!(first.format_version, EXPORT_FORMAT_VERSION);
+        assert_eq!(first.generated_at, "2025-01-30T08:00:00.000Z");
+        assert_eq!(first.machine_id, TEST_MACHINE_ID);
+
+        let db = tt_db::Database::open_in_memory().unwrap();
+        let result =
+            crate::commands::import::import_from_reader(&db, Cursor::new(output_str)).unwrap();
+        assert_eq!(result.malformed, 0);
+        assert_eq!(result.inserted, 1);
+    }
+
     #[test]
     fn test_malformed_line_skipped() {
         let (_temp, data_dir, claude_dir) = setup_test_dirs();
@@ -1113,6 +1381,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1121,6 +1390,26 @@ not valid json
         assert_eq!(output_str.lines().count(), 2); // Only valid lines
     }
 
+    #[test]
+    fn test_validate_tmux_events_lists_malformed_lines() {
+        let (_temp, data_dir, _claude_dir) = setup_test_dirs();
+        let events_file = data_dir.join("events.jsonl");
+
+        let content = r#"{"id":"1","timestamp":"2025-01-29T12:00:00Z","source":"remote.tmux","type":"test","data":{}}
+not valid json
+
+{"id":"2","timestamp":"2025-01-29T12:01:00Z"
+"#;
+        fs::write(&events_file, content).unwrap();
+
+        let err = validate_tmux_events(&events_file).unwrap_err().to_string();
+        assert!(err.starts_with("2 malformed line(s)"), "{err}");
+        assert!(err.ends_with(": 2, 4"), "{err}");
+
+        fs::write(&events_file, "{\"id\":\"1\"}\n\n").unwrap();
+        assert!(validate_tmux_events(&events_file).is_ok());
+    }
+
     #[test]
     fn test_empty_lines_skipped() {
         let (_temp, data_dir, claude_dir) = setup_test_dirs();
@@ -1140,6 +1429,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1172,6 +1462,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1220,6 +1511,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1264,6 +1556,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1302,6 +1595,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1338,6 +1632,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1377,6 +1672,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1387,6 +1683,36 @@ not valid json
         assert_eq!(output_str.lines().count(), 3);
     }
 
+    #[test]
+    fn test_claude_log_skips_corrupt_middle_line_but_retries_partial_final_line() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let temp = TempDir::new().unwrap();
+        let log_path = temp.path().join("session.jsonl");
+        let valid = |minute: u32| {
+            format!(
+                r#"{{"type":"user","sessionId":"sess123","timestamp":"2025-01-29T12:{minute:02}:00Z","cwd":"/home/user/project","message":{{"content":"hello"}}}}"#
+            )
+        };
+        let complete = format!("{}\n{{\"type\": corrupt\n{}\n", valid(0), valid(1));
+        let partial = r#"{"type":"user","sessionId":"sess1"#;
+        fs::write(&log_path, format!("{complete}{partial}")).unwrap();
+
+        let counter = crate::skip_counter::SkipCounter::default();
+        let subscriber = tracing_subscriber::registry().with(counter.clone());
+        let (offset, entries) = tracing::subscriber::with_default(subscriber, || {
+            read_claude_log(&log_path, 0).unwrap()
+        });
+
+        // The line after the corrupt one is still read...
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1]["timestamp"], "2025-01-29T12:01:00Z");
+        // ...the corrupt line is reported...
+        assert_eq!(counter.count(), 1);
+        // ...and the partial final line will be re-read next time.
+        assert_eq!(offset, complete.len() as u64);
+    }
+
     #[test]
     fn test_filtered_entry_types() {
         let (_temp, data_dir, claude_dir) = setup_test_dirs();
@@ -1411,6 +1737,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1442,6 +1769,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1502,6 +1830,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output1,
         )
         .unwrap();
@@ -1515,6 +1844,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output2,
         )
         .unwrap();
@@ -1550,6 +1880,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1560,6 +1891,76 @@ not valid json
         assert_eq!(output_str.lines().count(), 3);
     }
 
+    #[test]
+    fn test_source_filter_excludes_tmux() {
+        let (_temp, data_dir, claude_dir) = setup_test_dirs();
+
+        let tmux_event = r#"{"id":"tmux1","timestamp":"2025-01-29T11:00:00Z","source":"remote.tmux","type":"tmux_pane_focus","data":{}}"#;
+        fs::write(data_dir.join("events.jsonl"), format!("{tmux_event}\n")).unwrap();
+
+        let project_dir = claude_dir.join("test-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let claude_entry = r#"{"type":"user","sessionId":"sess123","timestamp":"2025-01-29T12:00:00Z","message":{"content":"hi"}}"#;
+        fs::write(
+            project_dir.join("session.jsonl"),
+            format!("{claude_entry}\n"),
+        )
+        .unwrap();
+
+        let mut output = Cursor::new(Vec::new());
+        run_impl(
+            &data_dir,
+            &claude_dir,
+            &data_dir,
+            None,
+            TEST_MACHINE_ID,
+            None,
+            None,
+            Some("remote.agent"),
+            &mut output,
+        )
+        .unwrap();
+
+        let output_str = String::from_utf8(output.into_inner()).unwrap();
+        assert!(!output_str.contains("remote.tmux"));
+        // session start + user message
+        assert_eq!(output_str.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_source_filter_excludes_agent_emitters() {
+        let (_temp, data_dir, claude_dir) = setup_test_dirs();
+
+        let tmux_event = r#"{"id":"tmux1","timestamp":"2025-01-29T11:00:00Z","source":"remote.tmux","type":"tmux_pane_focus","data":{}}"#;
+        fs::write(data_dir.join("events.jsonl"), format!("{tmux_event}\n")).unwrap();
+
+        let project_dir = claude_dir.join("test-project");
+        fs::create_dir_all(&project_dir).unwrap();
+        let claude_entry = r#"{"type":"user","sessionId":"sess123","timestamp":"2025-01-29T12:00:00Z","message":{"content":"hi"}}"#;
+        fs::write(
+            project_dir.join("session.jsonl"),
+            format!("{claude_entry}\n"),
+        )
+        .unwrap();
+
+        let mut output = Cursor::new(Vec::new());
+        run_impl(
+            &data_dir,
+            &claude_dir,
+            &data_dir,
+            None,
+            TEST_MACHINE_ID,
+            None,
+            None,
+            Some("remote.tmux"),
+            &mut output,
+        )
+        .unwrap();
+
+        let output_str = String::from_utf8(output.into_inner()).unwrap();
+        assert_eq!(output_str.trim(), tmux_event);
+    }
+
     #[test]
     fn test_opencode_export_empty_db() {
         let (_temp, data_dir, claude_dir) = setup_test_dirs();
@@ -1574,6 +1975,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1644,6 +2046,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1750,6 +2153,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output1,
         )
         .unwrap();
@@ -1763,6 +2167,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output2,
         )
         .unwrap();
@@ -1820,6 +2225,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1854,6 +2260,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1936,6 +2343,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -1977,6 +2385,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -2023,6 +2432,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output1,
         )
         .unwrap();
@@ -2044,6 +2454,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output2,
         )
         .unwrap();
@@ -2098,6 +2509,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         );
         assert!(
@@ -2132,6 +2544,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output1,
         )
         .unwrap();
@@ -2163,6 +2576,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output2,
         )
         .unwrap();
@@ -2201,6 +2615,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output1,
         )
         .unwrap();
@@ -2224,6 +2639,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output2,
         )
         .unwrap();
@@ -2262,6 +2678,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output1,
         )
         .unwrap();
@@ -2295,6 +2712,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output2,
         )
         .unwrap();
@@ -2313,6 +2731,92 @@ not valid json
         assert_eq!(events[1]["session_id"], "sess1");
     }
 
+    #[test]
+    fn test_parallel_parse_matches_serial() {
+        let (temp, _data_dir, claude_dir) = setup_test_dirs();
+
+        let entry = |kind: &str, id: &str, minute: u32, cwd: Option<&str>| {
+            let cwd = cwd.map_or_else(String::new, |cwd| format!(r#","cwd":"{cwd}""#));
+            match kind {
+                "user" => format!(
+                    r#"{{"type":"user","sessionId":"{id}","timestamp":"2025-01-29T12:{minute:02}:00Z"{cwd},"message":{{"content":"hello"}}}}"#
+                ),
+                _ => format!(
+                    r#"{{"type":"assistant","sessionId":"{id}","timestamp":"2025-01-29T12:{minute:02}:05Z"{cwd},"message":{{"content":[{{"type":"tool_use","name":"Read","input":{{"file_path":"/p/a.rs"}}}}]}}}}"#
+                ),
+            }
+        };
+        for project in 0..4 {
+            let project_dir = claude_dir.join(format!("project-{project}"));
+            fs::create_dir_all(&project_dir).unwrap();
+            for session in 0..4 {
+                let id = format!("sess-{project}-{session}");
+                let cwd = format!("/p/{project}");
+                let content = format!(
+                    "{}\n{}\n",
+                    entry("user", &id, 0, Some(&cwd)),
+                    entry("assistant", &id, 1, None)
+                );
+                fs::write(project_dir.join(format!("{id}.jsonl")), content).unwrap();
+            }
+        }
+        // A resumed session continues in a later file without repeating its cwd.
+        let shared = claude_dir.join("project-0");
+        fs::write(
+            shared.join("a-shared.jsonl"),
+            format!("{}\n", entry("user", "shared", 0, Some("/p/shared"))),
+        )
+        .unwrap();
+        fs::write(
+            shared.join("b-shared.jsonl"),
+            format!("{}\n", entry("assistant", "shared", 5, None)),
+        )
+        .unwrap();
+
+        let mut parallel = Vec::new();
+        let manifest_path = temp.path().join("claude-manifest.json");
+        export_claude_events(&claude_dir, &manifest_path, TEST_MACHINE_ID, &mut parallel).unwrap();
+        let parallel: Vec<Value> = String::from_utf8(parallel)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str::<Value>(l).unwrap())
+            .filter(|e| e["type"] != "session_metadata")
+            .collect();
+
+        // The pre-parallel export: one shared session map, files read in order.
+        let mut logs = discover_claude_logs(&claude_dir).unwrap();
+        logs.sort();
+        let mut seen_sessions = HashMap::new();
+        let mut serial = Vec::new();
+        for log_path in &logs {
+            for line in fs::read_to_string(log_path).unwrap().lines() {
+                let entry: Value = serde_json::from_str(line).unwrap();
+                process_claude_entry(&entry, &mut seen_sessions, TEST_MACHINE_ID, &mut serial)
+                    .unwrap();
+            }
+        }
+        let serial: Vec<Value> = String::from_utf8(serial)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str::<Value>(l).unwrap())
+            .collect();
+
+        assert_eq!(parallel, serial);
+        let shared_events: Vec<&Value> = parallel
+            .iter()
+            .filter(|e| e["session_id"] == "shared")
+            .collect();
+        let starts = shared_events
+            .iter()
+            .filter(|e| e["type"] == "agent_session")
+            .count();
+        assert_eq!(starts, 1, "one session start across both files");
+        assert!(
+            shared_events.iter().all(|e| e["cwd"] == "/p/shared"),
+            "cwd carries into the second file"
+        );
+    }
+
     #[test]
     fn test_offset_at_eof_no_new_content() {
         let (_temp, data_dir, claude_dir) = setup_test_dirs();
@@ -2334,6 +2838,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output1,
         )
         .unwrap();
@@ -2348,6 +2853,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output2,
         )
         .unwrap();
@@ -2376,6 +2882,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         );
 
@@ -2454,6 +2961,7 @@ not valid json
             TEST_MACHINE_ID,
             Some(&after_id),
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -2465,6 +2973,43 @@ not valid json
         assert!(lines[0].contains("00:02:00"));
     }
 
+    #[test]
+    fn test_export_after_timestamp_filters_events() {
+        let (temp, data_dir, _claude_dir) = setup_test_dirs();
+        let events: Vec<String> = ["00:00", "00:01", "00:02"]
+            .iter()
+            .map(|time| {
+                format!(
+                    r#"{{"id":"tmux-{time}","timestamp":"2025-01-01T{time}:00.000Z","source":"remote.tmux","type":"tmux_pane_focus","pane_id":"%1","tmux_session":"main","cwd":"/tmp"}}"#
+                )
+            })
+            .collect();
+        std::fs::write(data_dir.join("events.jsonl"), events.join("\n") + "\n").unwrap();
+
+        let cutoff = Utc.with_ymd_and_hms(2025, 1, 1, 0, 1, 0).unwrap();
+        let mut output = Vec::new();
+        let mut writer = CutoffWriter::new(&mut output, cutoff);
+        run_impl(
+            &data_dir,
+            &temp.path().join(".claude/projects"),
+            &data_dir,
+            None,
+            TEST_MACHINE_ID,
+            None,
+            None,
+            None,
+            &mut writer,
+        )
+        .unwrap();
+        writer.flush().unwrap();
+
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+        // The middle event sits exactly on the cutoff, so only the last remains
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("\"tmux-00:02\""));
+    }
+
     #[test]
     fn test_export_after_cross_source_filters_tmux_by_timestamp() {
         // Bug regression: when --after is an agent event ID (from OpenCode/Claude),
@@ -2497,6 +3042,7 @@ not valid json
             TEST_MACHINE_ID,
             Some(&agent_after_id),
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -2546,6 +3092,7 @@ not valid json
             TEST_MACHINE_ID,
             Some(&missing_after_id),
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -2591,6 +3138,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -2613,6 +3161,56 @@ not valid json
         assert_eq!(metadata["machine_id"], TEST_MACHINE_ID);
     }
 
+    #[test]
+    fn test_opencode_export_carries_session_title_as_summary() {
+        let (_temp, data_dir, claude_dir) = setup_test_dirs();
+        let opencode_db = create_test_opencode_db(&data_dir);
+
+        insert_opencode_session(
+            opencode_db.as_path(),
+            "ses_titled",
+            "/home/user/project-x",
+            1_700_000_000_000,
+            1_700_000_060_000,
+        );
+        Connection::open(opencode_db.as_path())
+            .unwrap()
+            .execute(
+                "UPDATE session SET title = 'Refactor auth module' WHERE id = 'ses_titled'",
+                [],
+            )
+            .unwrap();
+        insert_opencode_message(
+            opencode_db.as_path(),
+            "m1",
+            "ses_titled",
+            "user",
+            1_700_000_010_000,
+        );
+
+        let mut output = Cursor::new(Vec::new());
+        run_impl(
+            &data_dir,
+            &claude_dir,
+            &data_dir,
+            Some(opencode_db.as_path()),
+            TEST_MACHINE_ID,
+            None,
+            None,
+            None,
+            &mut output,
+        )
+        .unwrap();
+
+        let output_str = String::from_utf8(output.into_inner()).unwrap();
+        let metadata_line = output_str
+            .lines()
+            .find(|l| l.contains("\"session_metadata\""))
+            .expect("expected session_metadata record in output");
+        let metadata: Value = serde_json::from_str(metadata_line).unwrap();
+        assert_eq!(metadata["summary"], "Refactor auth module");
+    }
+
     #[test]
     fn test_opencode_export_emits_session_metadata_without_end_time() {
         let (_temp, data_dir, claude_dir) = setup_test_dirs();
@@ -2653,6 +3251,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         )
         .unwrap();
@@ -2767,6 +3366,7 @@ not valid json
             TEST_MACHINE_ID,
             None,
             None,
+            None,
             &mut output,
         );
 
@@ -0,0 +1,171 @@
+//! Heatmap command: per-day time totals for contribution-style visualizations.
+//!
+//! Unlike `tt report`, the output is a plain time series across all streams,
+//! with no tag breakdown. Every day in the range gets a row, including days
+//! with no activity.
+
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use tt_core::AllocationConfig;
+use tt_db::Database;
+
+use super::report::{Period, ReportData, generate_report_data_by_day, get_period_boundaries};
+use crate::output::OutputCtx;
+
+/// Direct and delegated totals for one local calendar day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeatmapDay {
+    /// Local date (`YYYY-MM-DD`).
+    pub date: String,
+    pub direct_ms: i64,
+    pub delegated_ms: i64,
+}
+
+impl HeatmapDay {
+    fn from_report(data: &ReportData) -> Self {
+        let direct_ms =
+            data.streams.iter().map(|s| s.time_direct_ms).sum::<i64>() + data.unassigned_direct_ms;
+        let delegated_ms = data
+            .streams
+            .iter()
+            .map(|s| s.time_delegated_ms)
+            .sum::<i64>()
+            + data.unassigned_delegated_ms;
+        Self {
+            date: data
+                .period_start
+                .with_timezone(&Local)
+                .date_naive()
+                .format("%Y-%m-%d")
+                .to_string(),
+            direct_ms,
+            delegated_ms,
+        }
+    }
+}
+
+/// Computes per-day totals for the local days in `start..end` (local midnights,
+/// end exclusive).
+pub fn generate_heatmap(
+    db: &Database,
+    config: &AllocationConfig,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    generated_at: DateTime<Utc>,
+) -> Result<Vec<HeatmapDay>> {
+    let timezone = iana_time_zone::get_timezone().unwrap_or_else(|_| "Etc/UTC".to_string());
+    let days = generate_report_data_by_day(db, start, end, generated_at, &timezone, config)?;
+    Ok(days.iter().map(HeatmapDay::from_report).collect())
+}
+
+/// Prints per-day totals for `period` as a JSON array, with a day count on stderr unless
+/// `ctx.quiet`.
+pub fn run(
+    db: &Database,
+    config: &AllocationConfig,
+    period: Period,
+    ctx: &OutputCtx,
+) -> Result<()> {
+    let (start, end) = get_period_boundaries(period, Local::now().date_naive());
+    let days = generate_heatmap(db, config, start, end, Utc::now())?;
+    println!("{}", serde_json::to_string_pretty(&days)?);
+    let active = days
+        .iter()
+        .filter(|d| d.direct_ms > 0 || d.delegated_ms > 0)
+        .count();
+    ctx.info_stderr(format_args!("{} days, {active} with activity", days.len()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::commands::report::local_midnight_to_utc;
+
+    fn make_focus(id: &str, timestamp: DateTime<Utc>, stream_id: &str) -> tt_db::StoredEvent {
+        tt_db::StoredEvent {
+            id: id.to_string(),
+            timestamp,
+            event_type: tt_core::EventType::TmuxPaneFocus,
+            source: "remote.tmux".to_string(),
+            machine_id: None,
+            schema_version: 1,
+            pane_id: Some("%1".to_string()),
+            tmux_session: None,
+            window_index: None,
+            git_project: None,
+            git_workspace: None,
+            status: None,
+            idle_duration_ms: None,
+            window_app_id: None,
+            window_title: None,
+            url: None,
+            action: None,
+            cwd: None,
+            session_id: None,
+            stream_id: Some(stream_id.to_string()),
+            assignment_source: Some("inferred".to_string()),
+            data: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_heatmap_has_a_row_per_day_with_zeros_for_empty_days() {
+        let db = Database::open_in_memory().unwrap();
+        let monday = NaiveDate::from_ymd_opt(2025, 1, 27).unwrap();
+        let wednesday = NaiveDate::from_ymd_opt(2025, 1, 29).unwrap();
+        let friday = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+        let at = |date: NaiveDate, hour: i64, minute: i64| {
+            local_midnight_to_utc(date)
+                + chrono::Duration::hours(hour)
+                + chrono::Duration::minutes(minute)
+        };
+        let now = at(monday, 0, 0);
+        db.insert_stream(&tt_db::Stream {
+            id: "stream-a".to_string(),
+            name: Some("a".to_string()),
+            created_at: now,
+            updated_at: now,
+            time_direct_ms: 0,
+            time_delegated_ms: 0,
+            first_event_at: None,
+            last_event_at: None,
+            needs_recompute: false,
+        })
+        .unwrap();
+        db.insert_events(&[
+            make_focus("mon-1", at(monday, 9, 0), "stream-a"),
+            make_focus("mon-2", at(monday, 9, 3), "stream-a"),
+            make_focus("wed-1", at(wednesday, 14, 0), "stream-a"),
+        ])
+        .unwrap();
+
+        let days = generate_heatmap(
+            &db,
+            &AllocationConfig::default(),
+            at(monday, 0, 0),
+            at(friday, 0, 0),
+            at(friday, 0, 0),
+        )
+        .unwrap();
+
+        let row = |date: &str, direct_ms| HeatmapDay {
+            date: date.to_string(),
+            direct_ms,
+            delegated_ms: 0,
+        };
+        // Focus runs until the next event, then one attention window (5 min) past the last.
+        assert_eq!(
+            days,
+            vec![
+                row("2025-01-27", 480_000),
+                row("2025-01-28", 0),
+                row("2025-01-29", 300_000),
+                row("2025-01-30", 0),
+            ]
+        );
+    }
+}
@@ -3,13 +3,15 @@
 //! This module reads JSONL events from stdin and inserts them into the local
 //! `SQLite` database. Duplicate events (same ID) are silently ignored.
 
+use std::collections::HashMap;
+use std::fmt::Write;
 use std::io::{BufRead, BufReader, Read};
 
-use anyhow::{Context, Result};
-use serde_json::json;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
 use tt_db::{Database, StoredEvent};
 
-use crate::machine::extract_machine_id;
+use crate::output::OutputCtx;
 
 /// Batch size for database inserts.
 const BATCH_SIZE: usize = 1000;
@@ -29,6 +31,121 @@ pub struct ImportResult {
     pub sessions_imported: usize,
     /// Machine ID extracted from events or session metadata.
     pub machine_id: Option<String>,
+    /// Number of events whose `machine_id` disagrees with their ID prefix.
+    pub machine_id_mismatches: usize,
+}
+
+/// Database state captured before an import, so the summary can report deltas.
+#[derive(Debug)]
+pub struct ImportBaseline {
+    sessions: usize,
+    events_by_source: HashMap<String, usize>,
+}
+
+impl ImportBaseline {
+    /// Captures session and per-source event counts.
+    pub fn capture(db: &Database) -> Result<Self> {
+        Ok(Self {
+            sessions: db
+                .count_agent_sessions()
+                .context("failed to count agent sessions")?,
+            events_by_source: db
+                .count_events_by_source()
+                .context("failed to count events by source")?
+                .into_iter()
+                .collect(),
+        })
+    }
+}
+
+/// What an import changed in the database.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Number of events inserted.
+    pub inserted: usize,
+    /// Number of events skipped as already present.
+    pub duplicates: usize,
+    /// Number of agent sessions that did not exist before.
+    pub sessions_new: usize,
+    /// Number of existing agent sessions that were overwritten.
+    pub sessions_updated: usize,
+    /// Earliest and latest event timestamps after the import.
+    pub event_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    /// Sources that gained events, with how many, ordered by source.
+    pub sources_gained: Vec<(String, usize)>,
+}
+
+impl ImportSummary {
+    /// Diffs the database against `baseline` after an import produced `result`.
+    pub fn compute(
+        db: &Database,
+        baseline: &ImportBaseline,
+        result: &ImportResult,
+    ) -> Result<Self> {
+        let sessions_after = db
+            .count_agent_sessions()
+            .context("failed to count agent sessions")?;
+        let sessions_new = sessions_after
+            .saturating_sub(baseline.sessions)
+            .min(result.sessions_imported);
+        let sources_gained = db
+            .count_events_by_source()
+            .context("failed to count events by source")?
+            .into_iter()
+            .filter_map(|(source, count)| {
+                let before = baseline.events_by_source.get(&source).copied().unwrap_or(0);
+                (count > before).then(|| (source, count - before))
+            })
+            .collect();
+
+        Ok(Self {
+            inserted: result.inserted,
+            duplicates: result.duplicates,
+            sessions_new,
+            sessions_updated: result.sessions_imported - sessions_new,
+            event_range: db
+                .event_time_range()
+                .context("failed to get event time range")?,
+            sources_gained,
+        })
+    }
+}
+
+/// Formats an import summary as indented lines for terminal output.
+pub fn format_import_summary(summary: &ImportSummary) -> String {
+    let mut output = String::new();
+    writeln!(
+        output,
+        "  Events: {} inserted, {} duplicates",
+        summary.inserted, summary.duplicates
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "  Sessions: {} new, {} updated",
+        summary.sessions_new, summary.sessions_updated
+    )
+    .unwrap();
+    if let Some((earliest, latest)) = summary.event_range {
+        writeln!(
+            output,
+            "  Event range: {} .. {}",
+            earliest.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            latest.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        )
+        .unwrap();
+    }
+    if summary.sources_gained.is_empty() {
+        writeln!(output, "  Sources: no new events").unwrap();
+    } else {
+        let sources: Vec<String> = summary
+            .sources_gained
+            .iter()
+            .map(|(source, count)| format!("{source} (+{count})"))
+            .collect();
+        writeln!(output, "  Sources: {}", sources.join(", ")).unwrap();
+    }
+    output
 }
 
 /// Imports events from a reader into the database.
@@ -37,6 +154,28 @@ pub struct ImportResult {
 /// Malformed lines are skipped with a warning.
 /// Duplicate events (same ID) are silently ignored.
 pub fn import_from_reader<R: Read>(db: &Database, reader: R) -> Result<ImportResult> {
+    import_from_reader_with_validation(db, reader, false)
+}
+
+/// Returns the ID's machine prefix if it disagrees with the event's `machine_id`.
+///
+/// Events without a `machine_id` or without a UUID prefix aren't checked.
+fn machine_id_mismatch(event: &StoredEvent) -> Option<String> {
+    let machine_id = event.machine_id.as_deref()?;
+    tt_db::extract_machine_id(&event.id).filter(|prefix| prefix != machine_id)
+}
+
+/// Like [`import_from_reader`], but with `strict` a mismatch between an
+/// event's `machine_id` and its ID prefix aborts the import.
+///
+/// Without `strict`, mismatched events are imported with a warning and counted
+/// in [`ImportResult::machine_id_mismatches`]. When aborting, batches already
+/// flushed stay imported; re-importing the fixed export skips them as duplicates.
+pub fn import_from_reader_with_validation<R: Read>(
+    db: &Database,
+    reader: R,
+    strict: bool,
+) -> Result<ImportResult> {
     let buf_reader = BufReader::new(reader);
     let mut batch: Vec<StoredEvent> = Vec::with_capacity(BATCH_SIZE);
     let mut result = ImportResult {
@@ -46,6 +185,7 @@ pub fn import_from_reader<R: Read>(db: &Database, reader: R) -> Result<ImportRes
         malformed: 0,
         sessions_imported: 0,
         machine_id: None,
+        machine_id_mismatches: 0,
     };
 
     for (line_num, line_result) in buf_reader.lines().enumerate() {
@@ -56,9 +196,13 @@ pub fn import_from_reader<R: Read>(db: &Database, reader: R) -> Result<ImportRes
             continue;
         }
 
+        if is_export_header(&line) {
+            continue;
+        }
+
         // Check for session metadata records before event parsing.
-        // This must come before rewrite_legacy_session_types to avoid the
-        // legacy rewriter mangling metadata lines.
+        // This must come before event parsing to avoid the legacy type
+        // rewrite mangling metadata lines.
         match parse_metadata_line(&line) {
             MetadataParseResult::Parsed(session, machine_id) => {
                 db.upsert_agent_session(&session, machine_id.as_deref())
@@ -73,33 +217,28 @@ pub fn import_from_reader<R: Read>(db: &Database, reader: R) -> Result<ImportRes
             MetadataParseResult::NotMetadata => {} // fall through to event parsing
         }
 
-        let line = match rewrite_legacy_session_types(&line, line_num) {
-            Ok(rewritten) => rewritten,
-            Err(err) => {
-                tracing::warn!(
-                    line = line_num + 1,
-                    error = %err,
-                    "malformed JSON, skipping line"
-                );
-                result.malformed += 1;
-                continue;
-            }
-        };
-
-        match serde_json::from_str::<StoredEvent>(&line) {
-            Ok(mut event) => {
-                // Clear stream_id and assignment_source during import - events will be
-                // re-assigned to streams after import via the inference algorithm.
-                // The original stream_id would violate foreign key constraints anyway
-                // since the stream doesn't exist in this database.
-                event.stream_id = None;
-                event.assignment_source = None;
-
-                // Extract machine_id from event ID prefix (UUID before first colon-separated source)
-                if event.machine_id.is_none() {
-                    event.machine_id = extract_machine_id(&event.id);
+        // Events are re-assigned to streams after import via the inference
+        // algorithm, so `from_export_line` drops any exported stream_id.
+        match StoredEvent::from_export_line(&line) {
+            Ok(event) => {
+                if let Some(prefix) = machine_id_mismatch(&event) {
+                    let machine_id = event.machine_id.as_deref().unwrap_or_default();
+                    if strict {
+                        bail!(
+                            "line {}: event '{}' has machine_id '{machine_id}' but its ID prefix is '{prefix}'",
+                            line_num + 1,
+                            event.id
+                        );
+                    }
+                    tracing::warn!(
+                        line = line_num + 1,
+                        event_id = %event.id,
+                        machine_id,
+                        id_prefix = %prefix,
+                        "event machine_id disagrees with its ID prefix"
+                    );
+                    result.machine_id_mismatches += 1;
                 }
-
                 if result.machine_id.is_none() {
                     result.machine_id.clone_from(&event.machine_id);
                 }
@@ -115,7 +254,7 @@ pub fn import_from_reader<R: Read>(db: &Database, reader: R) -> Result<ImportRes
                 }
             }
             Err(e) => {
-                tracing::warn!(line = line_num + 1, error = %e, "malformed JSON, skipping line");
+                tracing::warn!(skipped = true, line = line_num + 1, error = %e, "malformed JSON, skipping line");
                 result.malformed += 1;
             }
         }
@@ -133,50 +272,47 @@ pub fn import_from_reader<R: Read>(db: &Database, reader: R) -> Result<ImportRes
     Ok(result)
 }
 
-fn rewrite_legacy_session_types(line: &str, line_num: usize) -> Result<String> {
-    if !line.contains("\"session_start\"") && !line.contains("\"session_end\"") {
-        return Ok(line.to_string());
-    }
-
-    let mut value: serde_json::Value = serde_json::from_str(line)
-        .with_context(|| format!("legacy type rewrite failed on line {}", line_num + 1))?;
-    if let Some(obj) = value.as_object_mut() {
-        let type_str = obj.get("type").and_then(|t| t.as_str()).unwrap_or("");
-        match type_str {
-            "session_start" => {
-                obj.insert("type".into(), json!("agent_session"));
-                obj.insert("action".into(), json!("started"));
-            }
-            "session_end" => {
-                obj.insert("type".into(), json!("agent_session"));
-                obj.insert("action".into(), json!("ended"));
-            }
-            _ => {}
-        }
-    }
-
-    serde_json::to_string(&value).context("failed to serialize legacy rewrite")
-}
-
 /// Runs the import command, reading from stdin.
-pub fn run(db: &Database) -> Result<ImportResult> {
+///
+/// Unless `ctx.quiet`, prints a one-line count followed by an [`ImportSummary`].
+/// Machine ID mismatches are logged as warnings per event; with `strict`, they
+/// abort the import instead. See [`import_from_reader_with_validation`].
+pub fn run(db: &Database, ctx: &OutputCtx, strict: bool) -> Result<ImportResult> {
+    let baseline = if ctx.quiet {
+        None
+    } else {
+        Some(ImportBaseline::capture(db)?)
+    };
     let stdin = std::io::stdin();
-    let result = import_from_reader(db, stdin.lock())?;
+    let result = import_from_reader_with_validation(db, stdin.lock(), strict)?;
 
-    eprintln!(
+    ctx.info_stderr(format_args!(
         "Imported {} new events, {} sessions ({} duplicates, {} malformed lines)",
         result.inserted, result.sessions_imported, result.duplicates, result.malformed
-    );
+    ));
+    if let Some(baseline) = baseline {
+        let summary = ImportSummary::compute(db, &baseline, &result)?;
+        eprint!("{}", format_import_summary(&summary));
+    }
 
     Ok(result)
 }
 
+/// Returns whether a line is the `export_header` record from `tt export --header`.
+///
+/// The header only describes the stream, so importers skip it.
+pub(super) fn is_export_header(line: &str) -> bool {
+    line.contains("\"export_header\"")
+        && serde_json::from_str::<serde_json::Value>(line)
+            .is_ok_and(|value| value.get("type").and_then(|t| t.as_str()) == Some("export_header"))
+}
+
 /// Tri-state result of parsing a metadata line.
 #[allow(
     clippy::large_enum_variant,
     reason = "AgentSession is the primary variant"
 )]
-enum MetadataParseResult {
+pub(super) enum MetadataParseResult {
     /// Successfully parsed into an `AgentSession` + optional `machine_id`
     Parsed(tt_core::session::AgentSession, Option<String>),
     /// Recognized as `session_metadata` but invalid (skip without counting as malformed)
@@ -191,7 +327,7 @@ enum MetadataParseResult {
 /// - `Parsed(session, machine_id)` if the line is valid `session_metadata`
 /// - `RecognizedInvalid` if it's recognized as `session_metadata` but invalid
 /// - `NotMetadata` if it's not a `session_metadata` record at all
-fn parse_metadata_line(line: &str) -> MetadataParseResult {
+pub(super) fn parse_metadata_line(line: &str) -> MetadataParseResult {
     // Fast path: skip lines that can't possibly be session metadata
     if !line.contains("\"session_metadata\"") {
         return MetadataParseResult::NotMetadata;
@@ -211,6 +347,7 @@ fn parse_metadata_line(line: &str) -> MetadataParseResult {
         Ok(e) => e,
         Err(e) => {
             tracing::warn!(
+                skipped = true,
                 session_id = value.get("session_id").and_then(|v| v.as_str()).unwrap_or("unknown"),
                 error = %e,
                 "recognized session_metadata record failed deserialization"
@@ -226,6 +363,7 @@ fn parse_metadata_line(line: &str) -> MetadataParseResult {
         MetadataParseResult::Parsed(session, machine_id)
     } else {
         tracing::warn!(
+            skipped = true,
             session_id = %session_id,
             source = %source,
             "session_metadata record has invalid fields, skipping"
@@ -237,6 +375,7 @@ fn parse_metadata_line(line: &str) -> MetadataParseResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::machine::extract_machine_id;
     use chrono::{TimeZone, Utc};
     use std::io::Cursor;
 
@@ -259,6 +398,39 @@ mod tests {
         assert_eq!(result.malformed, 0);
     }
 
+    #[test]
+    fn test_machine_id_mismatch_warns_or_rejects_under_strict() {
+        let machine_a = "a0000000-0000-4000-8000-000000000000";
+        let machine_b = "b0000000-0000-4000-8000-000000000000";
+        let event = |id: &str, machine_id: &str| {
+            format!(
+                r#"{{"id":"{id}","timestamp":"2025-01-29T12:00:00Z","source":"remote.tmux","type":"tmux_pane_focus","machine_id":"{machine_id}","data":{{}}}}"#
+            )
+        };
+        let input = format!(
+            "{}\n{}\n",
+            event(&format!("{machine_a}:remote.tmux:1"), machine_a),
+            event(&format!("{machine_a}:remote.tmux:2"), machine_b),
+        );
+
+        let db = Database::open_in_memory().unwrap();
+        let result = import_from_reader(&db, Cursor::new(&input)).unwrap();
+        assert_eq!(result.inserted, 2);
+        assert_eq!(result.machine_id_mismatches, 1);
+
+        let db = Database::open_in_memory().unwrap();
+        let err = import_from_reader_with_validation(&db, Cursor::new(&input), true).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("line 2"), "{message}");
+        assert!(
+            message.contains(&format!(
+                "machine_id '{machine_b}' but its ID prefix is '{machine_a}'"
+            )),
+            "{message}"
+        );
+        assert!(db.get_events(None, None).unwrap().is_empty());
+    }
+
     #[test]
     fn test_valid_jsonl_all_inserted() {
         let db = Database::open_in_memory().unwrap();
@@ -578,6 +750,7 @@ mod tests {
                 chrono::DateTime::parse_from_rfc3339("2025-01-30T00:00:00Z")
                     .unwrap()
                     .with_timezone(&Utc),
+                None,
             )
             .unwrap();
         assert_eq!(sessions.len(), 1);
@@ -612,6 +785,7 @@ mod tests {
                 chrono::DateTime::parse_from_rfc3339("2025-01-30T00:00:00Z")
                     .unwrap()
                     .with_timezone(&Utc),
+                None,
             )
             .unwrap();
         assert_eq!(sessions.len(), 1);
@@ -642,11 +816,55 @@ mod tests {
                 chrono::DateTime::parse_from_rfc3339("2025-01-30T00:00:00Z")
                     .unwrap()
                     .with_timezone(&Utc),
+                None,
             )
             .unwrap();
         assert_eq!(sessions.len(), 1);
     }
 
+    #[test]
+    fn test_import_summary_counts_new_and_duplicate_data() {
+        let db = Database::open_in_memory().unwrap();
+        let metadata_line = r#"{"type":"session_metadata","session_id":"ses_old","source":"claude","session_type":"user","project_path":"/home/user/p","project_name":"p","start_time":"2025-01-29T12:00:00.000Z","message_count":5,"assistant_message_count":2,"tool_call_count":1}"#;
+        let first = format!(
+            "{}\n{metadata_line}\n",
+            make_jsonl_event("e1", "2025-01-29T12:00:00Z")
+        );
+        import_from_reader(&db, Cursor::new(first)).unwrap();
+
+        let baseline = ImportBaseline::capture(&db).unwrap();
+        let new_session = metadata_line.replace("ses_old", "ses_new");
+        let agent_event = r#"{"id":"e3","timestamp":"2025-01-29T13:00:00Z","source":"remote.agent","type":"tmux_pane_focus","data":{}}"#;
+        let second = format!(
+            "{}\n{}\n{agent_event}\n{metadata_line}\n{new_session}\n",
+            make_jsonl_event("e1", "2025-01-29T12:00:00Z"),
+            make_jsonl_event("e2", "2025-01-29T11:00:00Z"),
+        );
+        let result = import_from_reader(&db, Cursor::new(second)).unwrap();
+        let summary = ImportSummary::compute(&db, &baseline, &result).unwrap();
+
+        assert_eq!(
+            summary,
+            ImportSummary {
+                inserted: 2,
+                duplicates: 1,
+                sessions_new: 1,
+                sessions_updated: 1,
+                event_range: Some((
+                    Utc.with_ymd_and_hms(2025, 1, 29, 11, 0, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2025, 1, 29, 13, 0, 0).unwrap(),
+                )),
+                sources_gained: vec![
+                    ("remote.agent".to_string(), 1),
+                    ("remote.tmux".to_string(), 1)
+                ],
+            }
+        );
+        let output = format_import_summary(&summary);
+        assert!(output.contains("Sessions: 1 new, 1 updated"));
+        assert!(output.contains("Sources: remote.agent (+1), remote.tmux (+1)"));
+    }
+
     #[test]
     fn test_import_old_format_without_metadata() {
         // Backward compatibility: old-format exports without metadata lines
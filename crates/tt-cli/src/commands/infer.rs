@@ -0,0 +1,287 @@
+//! Infer command: route unassigned events to existing streams.
+//!
+//! Runs the same cwd/URL routing as `tt ingest sessions`. With a project, the
+//! project's `inferred` assignments are cleared first and only its events are
+//! re-routed, so other projects and user assignments stay as they are.
+
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::{Context, Result};
+use tt_db::Database;
+
+use super::classify::{ApplySummary, AssignmentTrace};
+use super::ingest::auto_assign_events;
+use super::recompute;
+use crate::Config;
+use crate::output::OutputCtx;
+use crate::url_project::UrlProjectMap;
+
+/// Outcome of an inference pass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InferResult {
+    /// Inferred assignments cleared before re-routing (project-scoped runs only).
+    pub cleared: u64,
+    /// Newly assigned events with their matching signal (only with `verbose`).
+    pub trace: Vec<AssignmentTrace>,
+    /// Coverage after the run, in the same shape as `tt classify --apply` reports.
+    /// Inference never creates streams.
+    pub summary: ApplySummary,
+}
+
+/// Routes unassigned events to streams, optionally re-inferring one `git_project`.
+///
+/// Streams that lose or gain events are marked for recompute.
+pub fn infer(
+    db: &Database,
+    url_map: &UrlProjectMap,
+    project: Option<&str>,
+    verbose: bool,
+) -> Result<InferResult> {
+    let project_events = |project: &str| {
+        db.get_events_by_git_project(project)
+            .context("failed to get project events")
+    };
+    let mut touched: BTreeSet<String> = BTreeSet::new();
+    let cleared = match project {
+        Some(project) => {
+            touched.extend(
+                project_events(project)?
+                    .into_iter()
+                    .filter(|event| event.assignment_source.as_deref() == Some("inferred"))
+                    .filter_map(|event| event.stream_id),
+            );
+            db.clear_inferred_assignments_for_project(project)
+                .context("failed to clear inferred assignments")?
+        }
+        None => 0,
+    };
+
+    let candidates: Vec<_> = match project {
+        Some(project) => project_events(project)?
+            .into_iter()
+            .filter(|event| event.stream_id.is_none())
+            .collect(),
+        None => db
+            .get_events_without_stream()
+            .context("failed to get unassigned events")?,
+    };
+    let assignments = auto_assign_events(db, url_map, &candidates)?;
+    touched.extend(assignments.iter().map(|a| a.stream_id.clone()));
+
+    let trace = if verbose {
+        let names: HashMap<String, String> = db
+            .get_streams()
+            .context("failed to get streams")?
+            .into_iter()
+            .map(|stream| (stream.id.clone(), stream.name.unwrap_or(stream.id)))
+            .collect();
+        let timestamps: HashMap<&str, _> = candidates
+            .iter()
+            .map(|event| (event.id.as_str(), event.timestamp))
+            .collect();
+        assignments
+            .iter()
+            .map(|a| AssignmentTrace {
+                event_id: a.event_id.clone(),
+                timestamp: timestamps[a.event_id.as_str()],
+                stream: names
+                    .get(&a.stream_id)
+                    .cloned()
+                    .unwrap_or_else(|| a.stream_id.clone()),
+                signal: a.signal.clone(),
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    if !touched.is_empty() {
+        let stream_ids: Vec<&str> = touched.iter().map(String::as_str).collect();
+        db.mark_streams_for_recompute(&stream_ids)
+            .context("failed to mark streams for recompute")?;
+    }
+
+    let summary = ApplySummary {
+        events_processed: db
+            .count_events_in_range(None, None)
+            .context("failed to count events")?,
+        events_assigned: assignments.len() as u64,
+        events_unassigned: db
+            .get_events_without_stream()
+            .context("failed to query unassigned events")?
+            .len(),
+        streams_created: 0,
+        streams_reused: assignments
+            .iter()
+            .map(|a| a.stream_id.as_str())
+            .collect::<BTreeSet<_>>()
+            .len(),
+    };
+
+    Ok(InferResult {
+        cleared,
+        trace,
+        summary,
+    })
+}
+
+/// Runs the infer command, recomputes the streams it touched, then prints an
+/// [`ApplySummary`].
+///
+/// With `verbose`, prints one [`AssignmentTrace`] per newly assigned event, as
+/// `tt classify --apply` does.
+pub fn run(
+    db: &Database,
+    config: &Config,
+    project: Option<&str>,
+    verbose: bool,
+    ctx: &OutputCtx,
+) -> Result<()> {
+    let url_map =
+        UrlProjectMap::new(&config.url_project_map).context("invalid url_project_map in config")?;
+    let result = infer(db, &url_map, project, verbose)?;
+    for entry in &result.trace {
+        ctx.info(format_args!("{entry}"));
+    }
+    if let Some(project) = project {
+        ctx.info(format_args!(
+            "Cleared {} inferred assignments for project '{project}'",
+            result.cleared
+        ));
+    }
+    ctx.info(format_args!(
+        "Assigned {} events to streams (by cwd/url)",
+        result.summary.events_assigned
+    ));
+    recompute::run(db, false, &config.allocation, ctx)?;
+    ctx.info(format_args!("\n{}", result.summary.format().trim_end()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use tt_db::StoredEvent;
+
+    use super::*;
+    use crate::commands::test_support::{make_event, make_stream};
+
+    fn project_event(id: &str, minute: u32, project: &str, cwd: &str) -> StoredEvent {
+        StoredEvent {
+            git_project: Some(project.to_string()),
+            cwd: Some(cwd.to_string()),
+            ..make_event(id, Utc.with_ymd_and_hms(2025, 1, 15, 9, minute, 0).unwrap())
+        }
+    }
+
+    #[test]
+    fn test_project_infer_leaves_other_projects_assignments() {
+        let db = Database::open_in_memory().unwrap();
+        for id in ["acme-api", "acme-misc", "other"] {
+            db.insert_stream(&make_stream(id, Some(id))).unwrap();
+        }
+        db.insert_events(&[
+            project_event("acme-user", 0, "acme", "/home/dev/acme/api"),
+            project_event("acme-wrong", 1, "acme", "/home/dev/acme/api"),
+            project_event("other-1", 2, "other", "/home/dev/other"),
+            project_event("other-2", 3, "other", "/home/dev/other"),
+        ])
+        .unwrap();
+        // The user pinned the api cwd; classification put a later event elsewhere.
+        db.assign_event_to_stream("acme-user", "acme-api", "user")
+            .unwrap();
+        db.assign_event_to_stream("acme-wrong", "acme-misc", "inferred")
+            .unwrap();
+        // Another project's inferred assignments, deliberately not cwd-consistent.
+        db.assign_event_to_stream("other-1", "other", "inferred")
+            .unwrap();
+        db.assign_event_to_stream("other-2", "acme-misc", "inferred")
+            .unwrap();
+
+        let result = infer(&db, &UrlProjectMap::default(), Some("acme"), true).unwrap();
+
+        assert_eq!(
+            result,
+            InferResult {
+                cleared: 1,
+                trace: vec![AssignmentTrace {
+                    event_id: "acme-wrong".to_string(),
+                    timestamp: Utc.with_ymd_and_hms(2025, 1, 15, 9, 1, 0).unwrap(),
+                    stream: "acme-api".to_string(),
+                    signal: "cwd: /home/dev/acme/api".to_string(),
+                }],
+                summary: ApplySummary {
+                    events_processed: 4,
+                    events_assigned: 1,
+                    events_unassigned: 0,
+                    streams_created: 0,
+                    streams_reused: 1,
+                },
+            }
+        );
+        let stream_of = |id: &str| db.get_event(id).unwrap().unwrap().stream_id;
+        assert_eq!(stream_of("acme-user").as_deref(), Some("acme-api"));
+        assert_eq!(stream_of("acme-wrong").as_deref(), Some("acme-api"));
+        assert_eq!(stream_of("other-1").as_deref(), Some("other"));
+        assert_eq!(stream_of("other-2").as_deref(), Some("acme-misc"));
+        let needs_recompute: BTreeSet<String> = db
+            .get_streams()
+            .unwrap()
+            .into_iter()
+            .filter(|s| s.needs_recompute)
+            .map(|s| s.id)
+            .collect();
+        assert_eq!(
+            needs_recompute,
+            BTreeSet::from(["acme-api".to_string(), "acme-misc".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_project_infer_is_repeatable() {
+        let db = Database::open_in_memory().unwrap();
+        for id in ["acme-api", "acme-misc"] {
+            db.insert_stream(&make_stream(id, Some(id))).unwrap();
+        }
+        db.insert_events(&[
+            project_event("acme-user", 0, "acme", "/home/dev/acme/api"),
+            project_event("acme-wrong", 1, "acme", "/home/dev/acme/api"),
+        ])
+        .unwrap();
+        db.assign_event_to_stream("acme-user", "acme-api", "user")
+            .unwrap();
+        db.assign_event_to_stream("acme-wrong", "acme-misc", "inferred")
+            .unwrap();
+
+        let first = infer(&db, &UrlProjectMap::default(), Some("acme"), false).unwrap();
+        let second = infer(&db, &UrlProjectMap::default(), Some("acme"), false).unwrap();
+
+        let summary = |assigned: u64| ApplySummary {
+            events_processed: 2,
+            events_assigned: assigned,
+            events_unassigned: 0,
+            streams_created: 0,
+            streams_reused: usize::try_from(assigned).unwrap(),
+        };
+        assert_eq!(
+            first,
+            InferResult {
+                cleared: 1,
+                trace: Vec::new(),
+                summary: summary(1),
+            }
+        );
+        // The first run's routing is `auto`, not `inferred`, so it is kept as is.
+        assert_eq!(
+            second,
+            InferResult {
+                cleared: 0,
+                trace: Vec::new(),
+                summary: summary(0),
+            }
+        );
+        let event = db.get_event("acme-wrong").unwrap().unwrap();
+        assert_eq!(event.stream_id.as_deref(), Some("acme-api"));
+        assert_eq!(event.assignment_source.as_deref(), Some("auto"));
+    }
+}
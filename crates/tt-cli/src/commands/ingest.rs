@@ -431,11 +431,17 @@ use tt_core::opencode::scan_opencode_sessions;
 use tt_core::session::{AgentSession, scan_claude_sessions};
 use tt_db::StoredEvent;
 
+use crate::Config;
+use crate::output::OutputCtx;
+use crate::url_project::UrlProjectMap;
+
 /// Run the sessions index command.
 ///
 /// Scans Claude Code session directories and the `OpenCode` `SQLite`
 /// database, then upserts discovered sessions into the database.
-pub fn index_sessions(db: &tt_db::Database) -> Result<()> {
+pub fn index_sessions(db: &tt_db::Database, config: &Config, ctx: &OutputCtx) -> Result<()> {
+    let url_map =
+        UrlProjectMap::new(&config.url_project_map).context("invalid url_project_map in config")?;
     let machine_id = crate::machine::load_machine_identity()?.map(|m| m.machine_id);
 
     let mut all_sessions = Vec::new();
@@ -450,25 +456,31 @@ pub fn index_sessions(db: &tt_db::Database) -> Result<()> {
     // Claude Code
     let claude_dir = get_claude_projects_dir();
     if claude_dir.exists() {
-        println!("Scanning Claude Code sessions...");
+        ctx.info(format_args!("Scanning Claude Code sessions..."));
         let claude_sessions =
             scan_claude_sessions(&claude_dir).context("failed to scan Claude Code sessions")?;
-        println!("  Found {} Claude sessions", claude_sessions.len());
+        ctx.info(format_args!(
+            "  Found {} Claude sessions",
+            claude_sessions.len()
+        ));
         all_sessions.extend(claude_sessions);
     }
 
     // OpenCode
     let opencode_db = get_opencode_db_path()?;
     if opencode_db.exists() {
-        println!("Scanning OpenCode sessions...");
+        ctx.info(format_args!("Scanning OpenCode sessions..."));
         let opencode_sessions = scan_opencode_sessions(&opencode_db, None)
             .context("failed to scan OpenCode sessions")?;
-        println!("  Found {} OpenCode sessions", opencode_sessions.len());
+        ctx.info(format_args!(
+            "  Found {} OpenCode sessions",
+            opencode_sessions.len()
+        ));
         all_sessions.extend(opencode_sessions);
     }
 
     if all_sessions.is_empty() {
-        println!("No sessions found.");
+        ctx.info(format_args!("No sessions found."));
         return Ok(());
     }
 
@@ -490,14 +502,16 @@ pub fn index_sessions(db: &tt_db::Database) -> Result<()> {
         .delete_non_user_message_events()
         .context("failed to clean up stale user_message events")?;
     if cleaned > 0 {
-        println!("Cleaned {cleaned} stale user_message events from non-user sessions");
+        ctx.info(format_args!(
+            "Cleaned {cleaned} stale user_message events from non-user sessions"
+        ));
     }
 
-    println!(
+    ctx.info(format_args!(
         "Indexed {} sessions ({} events)",
         all_sessions.len(),
         event_count
-    );
+    ));
 
     let mut projects: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
     for session in &all_sessions {
@@ -507,13 +521,16 @@ pub fn index_sessions(db: &tt_db::Database) -> Result<()> {
     let mut project_list: Vec<_> = projects.into_iter().collect();
     project_list.sort_unstable_by_key(|b| std::cmp::Reverse(b.1));
 
-    println!("\nSessions by project:");
+    ctx.info(format_args!("\nSessions by project:"));
     for (project, count) in &project_list[..project_list.len().min(10)] {
-        println!("  {project}: {count} sessions");
+        ctx.info(format_args!("  {project}: {count} sessions"));
     }
 
     if project_list.len() > 10 {
-        println!("  ... and {} more projects", project_list.len() - 10);
+        ctx.info(format_args!(
+            "  ... and {} more projects",
+            project_list.len() - 10
+        ));
     }
 
     // Drain local events.jsonl (tmux pane focus events) into the DB. The local
@@ -525,16 +542,23 @@ pub fn index_sessions(db: &tt_db::Database) -> Result<()> {
     let drained = import_local_events(db, &default_data_dir())
         .context("failed to drain local events.jsonl into DB")?;
     if drained > 0 {
-        println!("Drained {drained} local events from events.jsonl");
+        ctx.info(format_args!(
+            "Drained {drained} local events from events.jsonl"
+        ));
     }
 
-    // Auto-assign unassigned events to existing streams based on cwd matching.
-    let assigned =
-        auto_assign_events_to_streams(db).context("failed to auto-assign events to streams")?;
+    // Auto-assign unassigned events to existing streams based on cwd (or URL) matching.
+    let assigned = auto_assign_events_to_streams(db, &url_map)
+        .context("failed to auto-assign events to streams")?;
     if assigned > 0 {
-        println!("Auto-assigned {assigned} events to streams (by cwd)");
+        ctx.info(format_args!(
+            "Auto-assigned {assigned} events to streams (by cwd/url)"
+        ));
     }
 
+    db.link_sessions_to_streams()
+        .context("failed to link sessions to streams")?;
+
     Ok(())
 }
 
@@ -564,8 +588,37 @@ fn project_suffix(cwd: &str) -> Option<&str> {
 /// (path after `/home/<user>/`). This handles multi-machine setups where the
 /// same project lives under different home directories.
 ///
+/// `browser_tab` events carry no cwd; their URL is mapped to a project through
+/// `url_map` and assigned to the one stream with that name, if there is one.
+///
 /// Returns the number of newly assigned events.
-fn auto_assign_events_to_streams(db: &tt_db::Database) -> Result<u64> {
+fn auto_assign_events_to_streams(db: &tt_db::Database, url_map: &UrlProjectMap) -> Result<u64> {
+    let unassigned = db
+        .get_events_without_stream()
+        .context("failed to get unassigned events")?;
+    let assignments = auto_assign_events(db, url_map, &unassigned)?;
+    Ok(assignments.len() as u64)
+}
+
+/// One event routed by [`auto_assign_events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoAssignment {
+    pub event_id: String,
+    pub stream_id: String,
+    /// Matching signal, e.g. `cwd: <path>`, `project suffix: <suffix>` or `url project: <name>`.
+    pub signal: String,
+}
+
+/// Routes `events` to existing streams by cwd (or URL), as in
+/// [`auto_assign_events_to_streams`], and records the assignments as `auto`.
+///
+/// Only the given events are considered, so callers can re-infer a subset.
+/// Returns the applied assignments.
+pub(crate) fn auto_assign_events(
+    db: &tt_db::Database,
+    url_map: &UrlProjectMap,
+    events: &[StoredEvent],
+) -> Result<Vec<AutoAssignment>> {
     use std::collections::HashMap;
 
     // Build cwd → set of stream_ids and suffix → set of stream_ids from assigned events.
@@ -594,25 +647,53 @@ fn auto_assign_events_to_streams(db: &tt_db::Database) -> Result<u64> {
         }
     }
 
-    if cwd_to_streams.is_empty() && suffix_to_streams.is_empty() {
-        return Ok(0);
+    let mut name_to_streams: HashMap<&str, Vec<&str>> = HashMap::new();
+    for stream in &streams {
+        if let Some(name) = stream.name.as_deref() {
+            name_to_streams.entry(name).or_default().push(&stream.id);
+        }
     }
 
-    // Find unassigned events whose cwd maps to exactly ONE stream.
-    let unassigned = db
-        .get_events_without_stream()
-        .context("failed to get unassigned events")?;
+    if cwd_to_streams.is_empty() && suffix_to_streams.is_empty() && url_map.is_empty() {
+        return Ok(Vec::new());
+    }
 
-    let assignments: Vec<(String, String)> = unassigned
+    let assign = |event: &StoredEvent, stream_id: &str, signal: String| AutoAssignment {
+        event_id: event.id.clone(),
+        stream_id: stream_id.to_string(),
+        signal,
+    };
+
+    // Find events whose cwd maps to exactly ONE stream.
+    let assignments: Vec<AutoAssignment> = events
         .iter()
         .filter_map(|event| {
+            if event.event_type == tt_core::EventType::BrowserTab {
+                let url = event.url.as_deref()?;
+                let project = url_map.project_for(url)?;
+                return match name_to_streams.get(project).map(Vec::as_slice) {
+                    Some([stream_id]) => {
+                        Some(assign(event, stream_id, format!("url project: {project}")))
+                    }
+                    matches => {
+                        tracing::debug!(
+                            url = %url,
+                            project = %project,
+                            streams = matches.map_or(0, <[&str]>::len),
+                            "skipping URL project without a unique stream"
+                        );
+                        None
+                    }
+                };
+            }
+
             let cwd = event.cwd.as_ref()?;
 
             // Try exact CWD match first
             if let Some(stream_ids) = cwd_to_streams.get(cwd.as_str()) {
                 if stream_ids.len() == 1 {
                     let stream_id = stream_ids.iter().next()?;
-                    return Some((event.id.clone(), stream_id.clone()));
+                    return Some(assign(event, stream_id, format!("cwd: {cwd}")));
                 }
                 tracing::debug!(
                     cwd = %cwd,
@@ -627,7 +708,11 @@ fn auto_assign_events_to_streams(db: &tt_db::Database) -> Result<u64> {
             if let Some(stream_ids) = suffix_to_streams.get(suffix) {
                 if stream_ids.len() == 1 {
                     let stream_id = stream_ids.iter().next()?;
-                    return Some((event.id.clone(), stream_id.clone()));
+                    return Some(assign(
+                        event,
+                        stream_id,
+                        format!("project suffix: {suffix}"),
+                    ));
                 }
                 tracing::debug!(
                     cwd = %cwd,
@@ -641,14 +726,15 @@ fn auto_assign_events_to_streams(db: &tt_db::Database) -> Result<u64> {
         })
         .collect();
 
-    if assignments.is_empty() {
-        return Ok(0);
+    if !assignments.is_empty() {
+        let pairs: Vec<(String, String)> = assignments
+            .iter()
+            .map(|a| (a.event_id.clone(), a.stream_id.clone()))
+            .collect();
+        db.assign_events_to_stream(&pairs, "auto")
+            .context("failed to assign events to streams")?;
     }
-
-    let count = db
-        .assign_events_to_stream(&assignments, "auto")
-        .context("failed to assign events to streams")?;
-    Ok(count)
+    Ok(assignments)
 }
 
 /// Create events from an agent session.
@@ -675,6 +761,7 @@ fn create_session_events(session: &AgentSession, machine_id: Option<&str>) -> Ve
         idle_duration_ms: None,
         window_app_id: None,
         window_title: None,
+        url: None,
         action: None,
         cwd: Some(session.project_path.clone()),
         session_id: Some(session.session_id.clone()),
@@ -1759,3 +1846,49 @@ fn test_import_local_events_is_idempotent() {
     assert_eq!(first, 1);
     assert_eq!(second, 0, "re-import should not duplicate events");
 }
+
+#[test]
+fn test_auto_assign_maps_browser_tab_url_to_named_stream() {
+    use crate::url_project::UrlProjectRule;
+
+    let db = tt_db::Database::open_in_memory().unwrap();
+    let now = Utc::now();
+    for (id, name) in [("s-webapp", "acme-webapp"), ("s-other", "other")] {
+        db.insert_stream(&tt_db::Stream {
+            id: id.to_string(),
+            name: Some(name.to_string()),
+            created_at: now,
+            updated_at: now,
+            time_direct_ms: 0,
+            time_delegated_ms: 0,
+            first_event_at: None,
+            last_event_at: None,
+            needs_recompute: false,
+        })
+        .unwrap();
+    }
+
+    let lines = [
+        r#"{"id":"tab-1","source":"local.browser","type":"browser_tab","timestamp":"2026-05-17T00:00:00.000Z","url":"https://github.com/acme/webapp/pull/7"}"#,
+        r#"{"id":"tab-2","source":"local.browser","type":"browser_tab","timestamp":"2026-05-17T00:01:00.000Z","url":"https://news.example.com/"}"#,
+    ];
+    import::import_from_reader(&db, lines.join("\n").as_bytes()).unwrap();
+
+    let url_map = UrlProjectMap::new(&[UrlProjectRule {
+        domain: None,
+        regex: Some(r"github\.com/acme/webapp".to_string()),
+        project: "acme-webapp".to_string(),
+    }])
+    .unwrap();
+
+    let assigned = auto_assign_events_to_streams(&db, &url_map).unwrap();
+
+    assert_eq!(assigned, 1);
+    let tab = db.get_event("tab-1").unwrap().unwrap();
+    assert_eq!(
+        tab.url.as_deref(),
+        Some("https://github.com/acme/webapp/pull/7")
+    );
+    assert_eq!(tab.stream_id.as_deref(), Some("s-webapp"));
+    assert_eq!(db.get_event("tab-2").unwrap().unwrap().stream_id, None);
+}
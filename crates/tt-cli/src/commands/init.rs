@@ -1,16 +1,132 @@
-//! Init command for establishing machine identity.
+//! Init command for establishing machine identity and wiring up tmux hooks.
 
-use anyhow::Result;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
 
 use crate::machine;
+use crate::output::OutputCtx;
+
+/// tmux hook configuration recording pane focus and scroll events.
+pub const TMUX_HOOK_SNIPPET: &str = include_str!("../../../../config/tmux-hook.conf");
+
+/// First line of the block appended to `~/.tmux.conf`; marks the hooks as installed.
+const BEGIN_MARKER: &str = "# >>> time-tracker hooks >>>";
+const END_MARKER: &str = "# <<< time-tracker hooks <<<";
+
+/// Returns the path to the user's `~/.tmux.conf`.
+fn tmux_conf_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("could not determine home directory")?;
+    Ok(home.join(".tmux.conf"))
+}
+
+/// Appends the hook block to a tmux config file, creating it if needed.
+///
+/// Returns `false` without writing if the block is already present.
+fn append_tmux_hooks(path: &Path) -> Result<bool> {
+    let existing = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+    };
+    if existing.contains(BEGIN_MARKER) {
+        return Ok(false);
+    }
+
+    let separator = if existing.is_empty() || existing.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    write!(
+        file,
+        "{separator}\n{BEGIN_MARKER}\n{TMUX_HOOK_SNIPPET}{END_MARKER}\n"
+    )
+    .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(true)
+}
 
 /// Runs the init command.
-pub fn run(label: Option<&str>) -> Result<()> {
+///
+/// Creates the machine identity if missing, then either prints the tmux hook
+/// snippet with install instructions or, with `write`, appends it to `~/.tmux.conf`.
+/// Nothing is printed when `ctx.quiet`.
+pub fn run(label: Option<&str>, write: bool, ctx: &OutputCtx) -> Result<()> {
+    let existed = machine::load_machine_identity()?.is_some();
     let identity = machine::init_machine(label)?;
 
-    println!("Machine ID: {}", identity.machine_id);
-    println!("Label:      {}", identity.label);
-    println!("Saved to:   {}", machine::machine_json_path()?.display());
+    if !existed {
+        ctx.info(format_args!("Created machine identity."));
+    }
+    ctx.info(format_args!("Machine ID: {}", identity.machine_id));
+    ctx.info(format_args!("Label:      {}", identity.label));
+    ctx.info(format_args!(
+        "Saved to:   {}",
+        machine::machine_json_path()?.display()
+    ));
+    ctx.info(format_args!(""));
+
+    let tmux_conf = tmux_conf_path()?;
+    if write {
+        if append_tmux_hooks(&tmux_conf)? {
+            ctx.info(format_args!("Added tmux hooks to {}", tmux_conf.display()));
+        } else {
+            ctx.info(format_args!(
+                "tmux hooks already present in {}",
+                tmux_conf.display()
+            ));
+        }
+    } else {
+        ctx.info(format_args!(
+            "Add the following to {} (or rerun with --write):",
+            tmux_conf.display()
+        ));
+        ctx.info(format_args!(""));
+        ctx.info(format_args!("{TMUX_HOOK_SNIPPET}"));
+    }
+    ctx.info(format_args!(
+        "Then reload tmux with `tmux source-file ~/.tmux.conf`,"
+    ));
+    ctx.info(format_args!(
+        "and detach and reattach so focus events take effect."
+    ));
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::literal_string_with_formatting_args)] // tmux formats, not Rust ones
+    fn test_snippet_calls_tt_ingest_pane_focus() {
+        assert!(TMUX_HOOK_SNIPPET.contains("set -g focus-events on"));
+        assert!(TMUX_HOOK_SNIPPET.contains(
+            "set-hook -ga pane-focus-in 'run-shell -b \"mkdir -p $HOME/.local/state/time-tracker \
+             && tt ingest pane-focus --pane #{q:pane_id} --cwd #{q:pane_current_path} \
+             --session #{q:session_name} --window #{q:window_index}"
+        ));
+    }
+
+    #[test]
+    fn test_append_tmux_hooks_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".tmux.conf");
+        std::fs::write(&path, "set -g mouse on").unwrap();
+
+        assert!(append_tmux_hooks(&path).unwrap());
+        assert!(!append_tmux_hooks(&path).unwrap());
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("set -g mouse on\n\n# >>> time-tracker hooks >>>\n"));
+        assert_eq!(content.matches(BEGIN_MARKER).count(), 1);
+        assert!(content.ends_with("# <<< time-tracker hooks <<<\n"));
+    }
+}
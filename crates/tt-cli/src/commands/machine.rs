@@ -0,0 +1,63 @@
+//! Machine command for inspecting and changing this machine's identity.
+
+use anyhow::{Result, bail};
+
+use crate::machine::{self, MachineIdentity};
+use crate::output::OutputCtx;
+
+fn format_identity(identity: &MachineIdentity) -> Result<String> {
+    Ok(format!(
+        "Machine ID: {}\nLabel:      {}\nSaved to:   {}",
+        identity.machine_id,
+        identity.label,
+        machine::machine_json_path()?.display()
+    ))
+}
+
+/// Prints the current machine identity.
+pub fn show() -> Result<()> {
+    println!(
+        "{}",
+        format_identity(&machine::require_machine_identity()?)?
+    );
+    Ok(())
+}
+
+/// Replaces the machine UUID. Refuses to run unless `confirmed`.
+///
+/// The warning about the old ID always prints; the new identity is a
+/// confirmation and follows `ctx.quiet`.
+pub fn regenerate(confirmed: bool, ctx: &OutputCtx) -> Result<()> {
+    let current = machine::require_machine_identity()?;
+    if !confirmed {
+        bail!(
+            "Refusing to regenerate machine ID {} without --yes.\n\n\
+             Events already recorded keep the old ID, so this machine's history will \
+             appear under two IDs. Only do this if another machine shares this ID \
+             (e.g. a cloned VM or devpod).",
+            current.machine_id
+        );
+    }
+
+    let identity = machine::regenerate_machine()?;
+    eprintln!(
+        "WARNING: machine ID changed from {} to {}. Events recorded before now keep the old ID.",
+        current.machine_id, identity.machine_id
+    );
+    ctx.info(format_args!("{}", format_identity(&identity)?));
+    Ok(())
+}
+
+/// Sets the machine UUID to `machine_id`, confirming as [`regenerate`] does.
+pub fn import(machine_id: &str, ctx: &OutputCtx) -> Result<()> {
+    let previous = machine::load_machine_identity()?;
+    let identity = machine::import_machine_id(machine_id)?;
+    if let Some(previous) = previous.filter(|p| p.machine_id != identity.machine_id) {
+        eprintln!(
+            "WARNING: machine ID changed from {} to {}. Events recorded before now keep the old ID.",
+            previous.machine_id, identity.machine_id
+        );
+    }
+    ctx.info(format_args!("{}", format_identity(&identity)?));
+    Ok(())
+}
@@ -1,14 +1,60 @@
 //! Machines command for listing known remotes.
 
 use anyhow::Result;
+use chrono::SecondsFormat;
+use serde::Serialize;
 use tt_db::Database;
 
-/// Runs the machines command.
-pub fn run(db: &Database) -> Result<()> {
+use crate::output::OutputCtx;
+
+/// A known machine as emitted by `tt machines --json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MachineEntry {
+    pub machine_id: String,
+    pub label: String,
+    /// When this machine was last synced (RFC 3339), if ever.
+    pub last_sync_at: Option<String>,
+    pub last_event_id: Option<String>,
+    /// Timestamp of the machine's most recent stored event (RFC 3339).
+    pub last_event_at: Option<String>,
+}
+
+/// Lists known machines with the timestamp of each one's latest event.
+pub fn get_machine_entries(db: &Database) -> Result<Vec<MachineEntry>> {
+    let latest = db.get_last_event_per_machine()?;
+    Ok(db
+        .list_machines()?
+        .into_iter()
+        .map(|machine| {
+            let last_event_at = latest
+                .iter()
+                .find(|(machine_id, _)| *machine_id == machine.machine_id)
+                .map(|(_, timestamp)| timestamp.to_rfc3339_opts(SecondsFormat::Millis, true));
+            MachineEntry {
+                machine_id: machine.machine_id,
+                label: machine.label,
+                last_sync_at: machine.last_sync_at,
+                last_event_id: machine.last_event_id,
+                last_event_at,
+            }
+        })
+        .collect())
+}
+
+/// Runs the machines command, printing JSON when `ctx.json`.
+pub fn run(db: &Database, ctx: &OutputCtx) -> Result<()> {
+    if ctx.json {
+        let entries = get_machine_entries(db)?;
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
     let machines = db.list_machines()?;
 
     if machines.is_empty() {
-        println!("No machines registered yet. Run 'tt sync <remote>' to import from a remote.");
+        ctx.info(format_args!(
+            "No machines registered yet. Run 'tt sync <remote>' to import from a remote."
+        ));
         return Ok(());
     }
 
@@ -26,6 +72,8 @@ pub fn run(db: &Database) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
+    use chrono::{TimeZone, Utc};
+
     use super::*;
 
     fn format_machines_output(db: &Database) -> Result<String> {
@@ -77,4 +125,73 @@ mod tests {
             .to_string();
         insta::assert_snapshot!(output);
     }
+
+    #[test]
+    fn test_machines_json_shape_and_rfc3339_timestamps() {
+        let db = Database::open_in_memory().unwrap();
+        let machine_id = "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee";
+        db.upsert_machine(machine_id, "devbox", Some("last-event-1"))
+            .unwrap();
+        db.upsert_machine("11111111-2222-3333-4444-555555555555", "gpu-server", None)
+            .unwrap();
+        let event = tt_db::StoredEvent {
+            id: format!("{machine_id}:e1"),
+            timestamp: Utc.with_ymd_and_hms(2025, 1, 15, 10, 30, 0).unwrap(),
+            event_type: tt_core::EventType::TmuxPaneFocus,
+            source: "remote.tmux".to_string(),
+            machine_id: Some(machine_id.to_string()),
+            schema_version: 1,
+            pane_id: Some("%1".to_string()),
+            tmux_session: None,
+            window_index: None,
+            git_project: None,
+            git_workspace: None,
+            status: None,
+            idle_duration_ms: None,
+            window_app_id: None,
+            window_title: None,
+            url: None,
+            action: None,
+            cwd: None,
+            session_id: None,
+            stream_id: None,
+            assignment_source: None,
+            data: serde_json::json!({}),
+        };
+        db.insert_event(&event).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::to_value(get_machine_entries(&db).unwrap()).unwrap();
+        let machines = json.as_array().unwrap();
+        assert_eq!(machines.len(), 2);
+        for machine in machines {
+            let mut keys: Vec<&str> = machine
+                .as_object()
+                .unwrap()
+                .keys()
+                .map(String::as_str)
+                .collect();
+            keys.sort_unstable();
+            assert_eq!(
+                keys,
+                [
+                    "label",
+                    "last_event_at",
+                    "last_event_id",
+                    "last_sync_at",
+                    "machine_id"
+                ]
+            );
+            let last_sync = machine["last_sync_at"].as_str().unwrap();
+            assert!(chrono::DateTime::parse_from_rfc3339(last_sync).is_ok());
+        }
+
+        let devbox = &machines[0];
+        assert_eq!(devbox["label"], "devbox");
+        assert_eq!(devbox["last_event_id"], "last-event-1");
+        assert_eq!(devbox["last_event_at"], "2025-01-15T10:30:00.000Z");
+        let gpu = &machines[1];
+        assert!(gpu["last_event_id"].is_null());
+        assert!(gpu["last_event_at"].is_null());
+    }
 }
@@ -1,19 +1,34 @@
 //! CLI subcommand implementations.
 
 pub mod classify;
+pub mod completions;
+pub mod config;
 pub mod context;
+pub mod dump;
+pub mod events;
 pub mod export;
+pub mod heatmap;
 pub mod import;
+pub mod infer;
 pub mod ingest;
 pub mod init;
+pub mod machine;
 pub mod machines;
 pub mod priority;
+pub mod prune;
 pub mod recompute;
+pub mod replay;
 pub mod report;
+pub mod session;
 pub mod status;
 pub mod streams;
 pub mod sync;
 pub mod tag;
+pub mod today;
 pub mod todo;
+pub mod verify;
 
 pub mod util;
+
+#[cfg(test)]
+mod test_support;
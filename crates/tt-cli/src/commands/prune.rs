@@ -0,0 +1,68 @@
+//! Prune command for removing old data from the local database.
+
+use anyhow::{Context, Result};
+use chrono::SecondsFormat;
+use tt_db::Database;
+
+use super::util::parse_datetime;
+use crate::output::OutputCtx;
+
+/// Runs the prune command.
+///
+/// Deletes agent sessions that finished before `before` (ISO 8601 or relative).
+pub fn run(db: &Database, before: &str, ctx: &OutputCtx) -> Result<()> {
+    let cutoff = parse_datetime(before)?;
+    let deleted = db
+        .delete_sessions_before(cutoff)
+        .context("failed to delete old agent sessions")?;
+
+    ctx.info(format_args!(
+        "Pruned {deleted} agent session(s) before {}",
+        cutoff.to_rfc3339_opts(SecondsFormat::Secs, true)
+    ));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use tt_core::session::{AgentSession, SessionSource, SessionType};
+
+    #[test]
+    fn test_prune_deletes_sessions_before_cutoff() {
+        let db = Database::open_in_memory().unwrap();
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 9, 0, 0).unwrap();
+        let session = AgentSession {
+            session_id: "old-session".to_string(),
+            source: SessionSource::Claude,
+            parent_session_id: None,
+            session_type: SessionType::User,
+            project_path: "/home/user/project".to_string(),
+            project_name: "project".to_string(),
+            start_time: start,
+            end_time: Some(start + chrono::Duration::hours(1)),
+            message_count: 1,
+            summary: None,
+            user_prompts: vec![],
+            starting_prompt: None,
+            assistant_message_count: 0,
+            tool_call_count: 0,
+            user_message_timestamps: Vec::new(),
+            tool_call_timestamps: Vec::new(),
+        };
+        db.upsert_agent_session(&session, None).unwrap();
+
+        run(&db, "2025-06-01T00:00:00Z", &OutputCtx::default()).unwrap();
+
+        let remaining = db.agent_sessions_in_range(start, Utc::now(), None).unwrap();
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn test_prune_rejects_invalid_cutoff() {
+        let db = Database::open_in_memory().unwrap();
+        assert!(run(&db, "last tuesday", &OutputCtx::default()).is_err());
+    }
+}
@@ -4,19 +4,50 @@
 //! focus events and agent activity.
 
 use std::collections::HashMap;
+use std::io::Write;
+use std::time::Instant;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use tt_core::{AllocationConfig, SessionType, allocate_time};
 use tt_db::Database;
 
+use super::util::log_phase;
+use crate::output::OutputCtx;
+
 /// Run time recomputation for streams.
 ///
 /// # Arguments
 ///
 /// * `db` - Database connection
 /// * `force` - If true, recompute all streams; otherwise only those needing recomputation
-pub fn run(db: &Database, force: bool) -> Result<()> {
+/// * `config` - Allocation parameters (the config file's `[allocation]` section)
+/// * `ctx` - Output settings; progress is dropped when quiet
+pub fn run(db: &Database, force: bool, config: &AllocationConfig, ctx: &OutputCtx) -> Result<()> {
+    if ctx.quiet {
+        run_to(db, force, config, &mut std::io::sink())
+    } else {
+        run_to(db, force, config, &mut std::io::stdout().lock())
+    }
+}
+
+/// Like [`run`], but writes progress to `out` (e.g. stderr when stdout carries JSON).
+#[allow(clippy::too_many_lines)]
+pub fn run_to(
+    db: &Database,
+    force: bool,
+    config: &AllocationConfig,
+    out: &mut impl Write,
+) -> Result<()> {
+    // Refresh session → stream links so per-stream session lookups reflect the
+    // latest assignments, even when no stream times need recomputing.
+    let linked = db
+        .link_sessions_to_streams()
+        .context("failed to link sessions to streams")?;
+    tracing::debug!(linked, "linked agent sessions to streams");
+
+    let fetch_start = Instant::now();
+
     // Get the list of streams to recompute
     let streams = if force {
         db.get_streams().context("failed to get streams")?
@@ -26,18 +57,18 @@ pub fn run(db: &Database, force: bool) -> Result<()> {
     };
 
     if streams.is_empty() {
-        println!("No streams to recompute.");
+        writeln!(out, "No streams to recompute.")?;
         return Ok(());
     }
 
-    println!("Recomputing {} stream(s)...", streams.len());
+    writeln!(out, "Recomputing {} stream(s)...", streams.len())?;
 
     // Get all events - we need all events to build the focus/agent timelines correctly
     // even if we're only updating specific streams
     let events = db.get_events(None, None).context("failed to get events")?;
 
     if events.is_empty() {
-        println!("No events to process.");
+        writeln!(out, "No events to process.")?;
         return Ok(());
     }
 
@@ -75,7 +106,7 @@ pub fn run(db: &Database, force: bool) -> Result<()> {
     let earliest = events.first().map_or_else(Utc::now, |e| e.timestamp);
     let latest = events.last().map_or_else(Utc::now, |e| e.timestamp);
     let agent_sessions = db
-        .agent_sessions_in_range(earliest, latest)
+        .agent_sessions_in_range(earliest, latest, None)
         .unwrap_or_default();
     let session_end_times: HashMap<String, DateTime<Utc>> = agent_sessions
         .iter()
@@ -91,10 +122,15 @@ pub fn run(db: &Database, force: bool) -> Result<()> {
         sessions_with_type = session_types.len(),
         "loaded session metadata"
     );
+    log_phase("fetch events", fetch_start);
 
     // Run the allocation algorithm
-    let config = AllocationConfig::default();
-    let result = allocate_time(&events, &config, None, &session_end_times, &session_types);
+    let allocate_start = Instant::now();
+    config
+        .validate()
+        .map_err(|e| anyhow::anyhow!("invalid allocation config: {e}"))?;
+    let result = allocate_time(&events, config, None, &session_end_times, &session_types);
+    log_phase("allocate", allocate_start);
 
     tracing::debug!(
         stream_count = result.stream_times.len(),
@@ -103,6 +139,7 @@ pub fn run(db: &Database, force: bool) -> Result<()> {
     );
 
     // Filter results to only streams we want to update
+    let aggregate_start = Instant::now();
     let times_to_update: Vec<_> = if force {
         // Update all streams that have time computed
         result.stream_times
@@ -116,31 +153,35 @@ pub fn run(db: &Database, force: bool) -> Result<()> {
             .filter(|t| stream_ids_to_update.contains(t.stream_id.as_str()))
             .collect()
     };
+    log_phase("aggregate", aggregate_start);
 
     if times_to_update.is_empty() {
-        println!("No time data computed for the selected streams.");
+        writeln!(out, "No time data computed for the selected streams.")?;
         return Ok(());
     }
 
     // Update the database
+    let update_start = Instant::now();
     let updated = db
         .update_stream_times(&times_to_update)
         .context("failed to update stream times")?;
+    log_phase("update", update_start);
 
-    println!("Updated {updated} stream(s).");
+    writeln!(out, "Updated {updated} stream(s).")?;
 
     // Print summary
     for time in &times_to_update {
         let direct_mins = time.time_direct_ms / 60_000;
         let delegated_mins = time.time_delegated_ms / 60_000;
-        println!(
+        writeln!(
+            out,
             "  {}: direct {}m, delegated {}m",
             time.stream_id, direct_mins, delegated_mins
-        );
+        )?;
     }
 
     let total_mins = result.total_tracked_ms / 60_000;
-    println!("\nTotal tracked: {total_mins}m");
+    writeln!(out, "\nTotal tracked: {total_mins}m")?;
 
     Ok(())
 }
@@ -174,6 +215,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: None,
             cwd: Some(cwd.to_string()),
             session_id: None,
@@ -206,6 +248,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: Some(action.to_string()),
             cwd: Some("/project".to_string()),
             session_id: Some(session_id.to_string()),
@@ -237,6 +280,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: None,
             cwd: Some("/project".to_string()),
             session_id: Some(session_id.to_string()),
@@ -285,7 +329,13 @@ mod tests {
         }
 
         // Run recompute
-        run(&db, false).unwrap();
+        run(
+            &db,
+            false,
+            &AllocationConfig::default(),
+            &OutputCtx::default(),
+        )
+        .unwrap();
 
         // Verify stream was updated
         let updated_stream = db.get_stream("stream-1").unwrap().unwrap();
@@ -324,7 +374,13 @@ mod tests {
         db.insert_stream(&stream).unwrap();
 
         // Run recompute (not forced)
-        run(&db, false).unwrap();
+        run(
+            &db,
+            false,
+            &AllocationConfig::default(),
+            &OutputCtx::default(),
+        )
+        .unwrap();
 
         // Stream should not be modified
         let unchanged_stream = db.get_stream("stream-1").unwrap().unwrap();
@@ -366,11 +422,64 @@ mod tests {
         }
 
         // Run recompute with force
-        run(&db, true).unwrap();
+        run(
+            &db,
+            true,
+            &AllocationConfig::default(),
+            &OutputCtx::default(),
+        )
+        .unwrap();
 
         // Stream should be updated even though needs_recompute was false
         let updated_stream = db.get_stream("stream-1").unwrap().unwrap();
         assert!(updated_stream.time_direct_ms > 0);
         assert_eq!(updated_stream.time_delegated_ms, 25 * 60 * 1000);
     }
+
+    #[test]
+    fn test_recompute_uses_allocation_section_from_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "database_path = \"/tmp/tt-allocation.db\"\n[allocation]\nattention_window_ms = 60000\n",
+        )
+        .unwrap();
+        let config = crate::Config::load_from(Some(&config_path)).unwrap();
+        assert_eq!(config.allocation.attention_window_ms, 60_000);
+        assert_eq!(
+            config.allocation.agent_timeout_ms,
+            AllocationConfig::default().agent_timeout_ms,
+            "unset fields keep their defaults"
+        );
+
+        let db = Database::open_in_memory().unwrap();
+        let now = Utc::now();
+        db.insert_stream(&tt_db::Stream {
+            id: "stream-1".to_string(),
+            name: Some("test-project".to_string()),
+            created_at: now,
+            updated_at: now,
+            time_direct_ms: 0,
+            time_delegated_ms: 0,
+            first_event_at: Some(ts(0)),
+            last_event_at: Some(ts(10)),
+            needs_recompute: true,
+        })
+        .unwrap();
+        for event in [
+            make_focus_event("e1", ts(0), "stream-1", "/project"),
+            make_focus_event("e2", ts(10), "stream-1", "/project"),
+        ] {
+            db.insert_event(&event).unwrap();
+            db.assign_event_to_stream(&event.id, "stream-1", "inferred")
+                .unwrap();
+        }
+
+        run(&db, false, &config.allocation, &OutputCtx::default()).unwrap();
+
+        // One 1-minute attention window after each focus, not the default 5 minutes
+        let stream = db.get_stream("stream-1").unwrap().unwrap();
+        assert_eq!(stream.time_direct_ms, 2 * 60_000);
+    }
 }
@@ -0,0 +1,194 @@
+//! Replay command: run allocation over an events JSONL file, bypassing the database.
+//!
+//! Reads `tt export` output (events plus `session_metadata` records), parses
+//! events the same way `tt import` does, and allocates time in memory. This
+//! reproduces someone's reported numbers from their exported log without
+//! importing it.
+//!
+//! Exported events carry no stream assignment, so replay stands in for
+//! inference with its simplest form: one stream per working directory, named by
+//! the directory. Events without a `cwd` stay unassigned.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use tt_core::{AllocationConfig, AllocationResult, SessionType, allocate_time};
+use tt_db::StoredEvent;
+
+use super::import::{MetadataParseResult, is_export_header, parse_metadata_line};
+use super::report::format_duration;
+
+/// Allocation over a replayed file.
+#[derive(Debug)]
+pub struct ReplayResult {
+    /// Number of events replayed.
+    pub events: usize,
+    /// Number of malformed lines skipped.
+    pub malformed: usize,
+    /// Stream times, keyed by working directory, largest total first.
+    pub allocation: AllocationResult,
+}
+
+/// Parses events and session metadata from `reader` and allocates their time.
+pub fn replay_from_reader<R: Read>(reader: R) -> Result<ReplayResult> {
+    let mut events: Vec<StoredEvent> = Vec::new();
+    let mut malformed = 0;
+    let mut session_end_times: HashMap<String, DateTime<Utc>> = HashMap::new();
+    let mut session_types: HashMap<String, SessionType> = HashMap::new();
+
+    for (line_num, line_result) in BufReader::new(reader).lines().enumerate() {
+        let line = line_result.context("failed to read replay input")?;
+        if line.trim().is_empty() || is_export_header(&line) {
+            continue;
+        }
+
+        match parse_metadata_line(&line) {
+            MetadataParseResult::Parsed(session, _machine_id) => {
+                if let Some(end) = session.end_time {
+                    session_end_times.insert(session.session_id.clone(), end);
+                }
+                session_types.insert(session.session_id, session.session_type);
+                continue;
+            }
+            MetadataParseResult::RecognizedInvalid => continue,
+            MetadataParseResult::NotMetadata => {}
+        }
+
+        match StoredEvent::from_export_line(&line) {
+            Ok(mut event) => {
+                event.stream_id.clone_from(&event.cwd);
+                events.push(event);
+            }
+            Err(e) => {
+                tracing::warn!(skipped = true, line = line_num + 1, error = %e, "malformed JSON, skipping line");
+                malformed += 1;
+            }
+        }
+    }
+
+    events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.id.cmp(&b.id)));
+    let config = AllocationConfig::default();
+    config
+        .validate()
+        .map_err(|e| anyhow::anyhow!("invalid allocation config: {e}"))?;
+    let mut allocation = allocate_time(&events, &config, None, &session_end_times, &session_types);
+    allocation.stream_times.sort_by(|a, b| {
+        let total = |t: &tt_core::StreamTime| t.time_direct_ms + t.time_delegated_ms;
+        total(b)
+            .cmp(&total(a))
+            .then_with(|| a.stream_id.cmp(&b.stream_id))
+    });
+
+    Ok(ReplayResult {
+        events: events.len(),
+        malformed,
+        allocation,
+    })
+}
+
+/// Formats replayed stream times as a table.
+pub fn format_replay(result: &ReplayResult) -> String {
+    let mut output = String::new();
+    writeln!(
+        output,
+        "REPLAY ({} events, {} malformed lines)",
+        result.events, result.malformed
+    )
+    .unwrap();
+    writeln!(output).unwrap();
+    writeln!(
+        output,
+        "{:<50} {:>10} {:>10} {:>10}",
+        "Directory", "Direct", "Delegated", "Total"
+    )
+    .unwrap();
+    let allocation = &result.allocation;
+    let rows = allocation
+        .stream_times
+        .iter()
+        .map(|t| (t.stream_id.as_str(), t.time_direct_ms, t.time_delegated_ms))
+        .chain(
+            (allocation.unassigned_direct_ms > 0 || allocation.unassigned_delegated_ms > 0)
+                .then_some((
+                    "(no directory)",
+                    allocation.unassigned_direct_ms,
+                    allocation.unassigned_delegated_ms,
+                )),
+        );
+    for (name, direct_ms, delegated_ms) in rows {
+        writeln!(
+            output,
+            "{name:<50} {:>10} {:>10} {:>10}",
+            format_duration(direct_ms),
+            format_duration(delegated_ms),
+            format_duration(direct_ms + delegated_ms),
+        )
+        .unwrap();
+    }
+    writeln!(output).unwrap();
+    writeln!(
+        output,
+        "Tracked (wall clock): {}",
+        format_duration(allocation.total_tracked_ms)
+    )
+    .unwrap();
+    output
+}
+
+/// Runs `tt replay <file>`.
+pub fn run(path: &Path) -> Result<()> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let result = replay_from_reader(file)?;
+    print!("{}", format_replay(&result));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use super::*;
+
+    #[test]
+    fn test_replay_fixture_totals_per_directory() {
+        let mut fixture = tempfile::NamedTempFile::new().unwrap();
+        // Focus in /a, then /b while an agent session runs there, then /a again.
+        // The session's metadata ends it at 11:10, eight minutes after its first tool use.
+        let lines = [
+            r#"{"id":"f1","timestamp":"2025-01-29T10:00:00Z","source":"remote.tmux","type":"tmux_pane_focus","cwd":"/a","data":{}}"#,
+            r#"{"id":"f2","timestamp":"2025-01-29T10:03:00Z","source":"remote.tmux","type":"tmux_pane_focus","cwd":"/a","data":{}}"#,
+            r#"{"id":"f3","timestamp":"2025-01-29T11:00:00Z","source":"remote.tmux","type":"tmux_pane_focus","cwd":"/b","data":{}}"#,
+            r#"{"id":"s1","timestamp":"2025-01-29T11:00:00Z","source":"remote.agent","type":"agent_session","action":"started","session_id":"sess-1","cwd":"/b","data":{}}"#,
+            r#"{"id":"t1","timestamp":"2025-01-29T11:02:00Z","source":"remote.agent","type":"agent_tool_use","session_id":"sess-1","cwd":"/b","data":{"tool":"Edit"}}"#,
+            r#"{"id":"f4","timestamp":"2025-01-29T11:30:00Z","source":"remote.tmux","type":"tmux_pane_focus","cwd":"/a","data":{}}"#,
+            r#"{"type":"session_metadata","session_id":"sess-1","source":"claude","parent_session_id":null,"session_type":"user","project_path":"/b","project_name":"b","start_time":"2025-01-29T11:00:00Z","end_time":"2025-01-29T11:10:00Z","message_count":1,"summary":null,"user_prompts":[],"starting_prompt":null,"assistant_message_count":0,"tool_call_count":0}"#,
+            "not json",
+        ];
+        writeln!(fixture, "{}", lines.join("\n")).unwrap();
+
+        let result = replay_from_reader(std::fs::File::open(fixture.path()).unwrap()).unwrap();
+
+        assert_eq!(result.events, 6);
+        assert_eq!(result.malformed, 1);
+        let times: Vec<_> = result
+            .allocation
+            .stream_times
+            .iter()
+            .map(|t| (t.stream_id.as_str(), t.time_direct_ms, t.time_delegated_ms))
+            .collect();
+        // Focus lasts until the next focus event, at most one 5-minute attention window:
+        // /a gets 3 + 5 + 5 minutes, /b 5 minutes plus the agent's 8.
+        assert_eq!(
+            times,
+            vec![("/a", 780_000, 0), ("/b", 300_000, 480_000)],
+            "{}",
+            format_replay(&result)
+        );
+        assert!(format_replay(&result).contains("REPLAY (6 events, 1 malformed lines)"));
+    }
+}
@@ -6,16 +6,21 @@
 //! Time is calculated from events within the period using the allocation algorithm,
 //! not from cumulative stream totals. This ensures accurate per-period reporting.
 
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Write;
+use std::time::Instant;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Datelike, Local, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
 use serde::Serialize;
 use tt_core::session::AgentSession;
-use tt_core::{AllocationConfig, EventType, SessionType, allocate_time};
+use tt_core::{ActivityInterval, AllocationConfig, EventType, SessionType, allocate_time};
 use tt_db::{Database, StoredEvent};
 
+use super::streams::category_of;
+use super::util::log_phase;
+use crate::output::OutputCtx;
+
 /// Report period type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Period {
@@ -23,6 +28,14 @@ pub enum Period {
     LastWeek,
     Day,
     LastDay,
+    /// Calendar month containing the reference date.
+    Month,
+    /// Calendar month before the one containing the reference date.
+    LastMonth,
+    /// Calendar quarter (Jan–Mar, Apr–Jun, Jul–Sep, Oct–Dec) containing the reference date.
+    Quarter,
+    /// Calendar quarter before the one containing the reference date.
+    LastQuarter,
     Custom(DateTime<Utc>, DateTime<Utc>),
 }
 
@@ -32,6 +45,8 @@ pub enum Period {
 pub enum PeriodType {
     Week,
     Day,
+    Month,
+    Quarter,
 }
 
 /// Computed time for a stream within the report period.
@@ -43,6 +58,23 @@ pub struct ReportStreamTime {
     pub time_delegated_ms: i64,
 }
 
+impl ReportStreamTime {
+    const fn total_ms(&self) -> i64 {
+        self.time_direct_ms + self.time_delegated_ms
+    }
+}
+
+/// Orders streams by total time descending, then by name and ID.
+///
+/// Allocation returns streams in hash order, so every rendering sorts with
+/// this to keep output stable across runs.
+fn compare_streams(a: &ReportStreamTime, b: &ReportStreamTime) -> std::cmp::Ordering {
+    b.total_ms()
+        .cmp(&a.total_ms())
+        .then_with(|| a.name.cmp(&b.name))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
 /// Computed report data.
 #[derive(Debug)]
 pub struct ReportData {
@@ -61,6 +93,269 @@ pub struct ReportData {
     pub unassigned_direct_ms: i64,
     /// Delegated (agent) time on activity not assigned to any stream.
     pub unassigned_delegated_ms: i64,
+    /// Wall-clock time with any activity: the union of all intervals, so overlapping
+    /// streams count once. Computed before tag filters are applied.
+    pub total_tracked_ms: i64,
+    /// Spans behind the stream and unassigned times, clipped to the period. Tag and
+    /// dimension filters drop the spans they exclude.
+    pub activity: Vec<ActivityInterval>,
+}
+
+/// Output options for `tt report`.
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ReportOptions {
+    /// Output as JSON.
+    pub json: bool,
+    /// Number of weekly reports to generate (most recent first).
+    pub weeks: Option<u32>,
+    /// Show parent sessions with their subagents nested (human-readable only).
+    pub tree: bool,
+    /// Append a per-day breakdown of the period (days nested under the period in JSON).
+    pub daily: bool,
+    /// Only include streams carrying at least one of these tags.
+    pub include_tags: Vec<String>,
+    /// Drop streams carrying any of these tags; wins over `include_tags`.
+    pub exclude_tags: Vec<String>,
+    /// Which time dimension to report.
+    pub dimension: TimeDimension,
+    /// Append a breakdown by each stream's dominant `git_project`.
+    pub by_project: bool,
+    /// Append a breakdown by work type (`type:` tags).
+    pub by_category: bool,
+    /// Drop time falling on Saturday and Sunday (local time).
+    pub exclude_weekends: bool,
+    /// Output one JSON object per line instead of a nested document.
+    pub jsonl: bool,
+    /// Append delegated ÷ direct time per tag and overall.
+    pub agent_leverage: bool,
+    /// Width tag and stream names are truncated to fit.
+    pub width: TableWidth,
+    /// Show only the N highest-time tags and streams, plus an "other" row.
+    pub top: Option<usize>,
+    /// Allocation parameters, from the config file's `[allocation]` section.
+    pub allocation: AllocationConfig,
+    /// Per-tag time budgets to report progress against.
+    pub budgets: Vec<Budget>,
+}
+
+/// A time budget for one tag, parsed from `--budget <tag>=<hours>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Budget {
+    pub tag: String,
+    pub budget_ms: i64,
+}
+
+impl std::str::FromStr for Budget {
+    type Err = String;
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (tag, hours) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected <tag>=<hours>, got '{s}'"))?;
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err(format!("missing tag in '{s}'"));
+        }
+        let hours: f64 = hours
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid hours '{hours}', expected a number"))?;
+        if !hours.is_finite() || hours <= 0.0 {
+            return Err(format!("budget hours must be positive, got '{hours}'"));
+        }
+        Ok(Self {
+            tag: tag.to_string(),
+            budget_ms: (hours * 3_600_000.0).round() as i64,
+        })
+    }
+}
+
+/// Which of direct and delegated time a report counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeDimension {
+    /// Direct plus delegated time.
+    #[default]
+    Both,
+    /// Human attention only (`--only-direct`).
+    Direct,
+    /// Agent time only (`--only-delegated`).
+    Delegated,
+}
+
+impl TimeDimension {
+    /// Zeroes the excluded dimension after allocation and drops streams left with no time.
+    ///
+    /// Totals, tag aggregates and stream ordering are all derived from the remaining
+    /// per-stream times, so they follow the chosen dimension.
+    pub fn apply(self, data: &mut ReportData) {
+        match self {
+            Self::Both => return,
+            Self::Direct => {
+                for stream in &mut data.streams {
+                    stream.time_delegated_ms = 0;
+                }
+                data.unassigned_delegated_ms = 0;
+                data.activity.retain(|span| !span.delegated);
+            }
+            Self::Delegated => {
+                for stream in &mut data.streams {
+                    stream.time_direct_ms = 0;
+                }
+                data.unassigned_direct_ms = 0;
+                data.activity.retain(|span| span.delegated);
+            }
+        }
+        data.streams
+            .retain(|stream| stream.time_direct_ms > 0 || stream.time_delegated_ms > 0);
+    }
+}
+
+/// Stream-level tag filter applied to report data before tag aggregation.
+#[derive(Debug, Default)]
+pub struct TagFilter {
+    /// Streams to keep; `None` keeps every stream not excluded.
+    included: Option<HashSet<String>>,
+    excluded: HashSet<String>,
+}
+
+impl TagFilter {
+    /// Resolves include/exclude tags to stream IDs.
+    ///
+    /// An empty `include` list means no include filter. Exclusions win on conflict.
+    pub fn load(db: &Database, include: &[String], exclude: &[String]) -> Result<Self> {
+        let stream_ids = |tags: &[String]| -> Result<HashSet<String>> {
+            let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+            Ok(db
+                .get_stream_ids_with_any_tag(&tags)
+                .context("failed to get streams by tag")?
+                .into_iter()
+                .collect())
+        };
+        let included = if include.is_empty() {
+            None
+        } else {
+            Some(stream_ids(include)?)
+        };
+        Ok(Self {
+            included,
+            excluded: stream_ids(exclude)?,
+        })
+    }
+
+    /// Returns true if the filter keeps every stream.
+    pub fn is_empty(&self) -> bool {
+        self.included.is_none() && self.excluded.is_empty()
+    }
+
+    /// Returns true if the stream survives the filter.
+    pub fn keeps(&self, stream_id: &str) -> bool {
+        !self.excluded.contains(stream_id)
+            && self
+                .included
+                .as_ref()
+                .is_none_or(|included| included.contains(stream_id))
+    }
+
+    /// Removes filtered-out streams from report data.
+    ///
+    /// An include filter also drops unassigned time, which carries no tags.
+    pub fn apply(&self, data: &mut ReportData) {
+        if self.is_empty() {
+            return;
+        }
+        data.streams.retain(|stream| self.keeps(&stream.id));
+        if self.included.is_some() {
+            data.unassigned_direct_ms = 0;
+            data.unassigned_delegated_ms = 0;
+        }
+        data.activity.retain(|span| {
+            span.stream_id
+                .as_deref()
+                .map_or_else(|| self.included.is_none(), |id| self.keeps(id))
+        });
+    }
+}
+
+/// Width the human-readable report's tag and stream names are fitted to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TableWidth {
+    /// The terminal width from `COLUMNS`, falling back to [`DEFAULT_TABLE_WIDTH`].
+    #[default]
+    Auto,
+    /// A fixed width in columns (`--width`).
+    Fixed(usize),
+    /// Never truncate (`--no-truncate`).
+    Unlimited,
+}
+
+/// Width assumed when the terminal width is unknown.
+pub const DEFAULT_TABLE_WIDTH: usize = 80;
+
+impl TableWidth {
+    /// Resolves the width against the environment; `None` means no truncation.
+    pub fn columns(self) -> Option<usize> {
+        match self {
+            Self::Fixed(width) => Some(width),
+            Self::Unlimited => None,
+            Self::Auto => Some(
+                std::env::var("COLUMNS")
+                    .ok()
+                    .and_then(|value| value.trim().parse().ok())
+                    .filter(|&width| width > 0)
+                    .unwrap_or(DEFAULT_TABLE_WIDTH),
+            ),
+        }
+    }
+}
+
+/// Truncates `text` to at most `max` characters, marking the cut with an ellipsis.
+pub fn truncate_with_ellipsis(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// ANSI styling for the human-readable report. A disabled palette is a no-op.
+#[derive(Debug, Clone, Copy)]
+struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    const TAG: &str = "\x1b[1;35m";
+    const DIRECT: &str = "\x1b[32m";
+    const DELEGATED: &str = "\x1b[36m";
+    const OVER_BUDGET: &str = "\x1b[1;31m";
+    const RESET: &str = "\x1b[0m";
+
+    fn paint(self, code: &str, text: &str) -> String {
+        if self.enabled {
+            format!("{code}{text}{}", Self::RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn tag(self, text: &str) -> String {
+        self.paint(Self::TAG, text)
+    }
+
+    fn direct(self, text: &str) -> String {
+        self.paint(Self::DIRECT, text)
+    }
+
+    fn delegated(self, text: &str) -> String {
+        self.paint(Self::DELEGATED, text)
+    }
+
+    fn over_budget(self, text: &str) -> String {
+        self.paint(Self::OVER_BUDGET, text)
+    }
 }
 
 const DEFAULT_WEEK_START_DAY: &str = "monday";
@@ -126,6 +421,66 @@ fn last_day_boundaries(today: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
     (start, end)
 }
 
+/// Returns the first day of the month containing `date`.
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap()
+}
+
+/// Returns the first day of the month after the one containing `date`.
+///
+/// December rolls over to January of the next year.
+fn first_of_next_month(date: NaiveDate) -> NaiveDate {
+    if date.month() == 12 {
+        NaiveDate::from_ymd_opt(date.year() + 1, 1, 1).unwrap()
+    } else {
+        NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1).unwrap()
+    }
+}
+
+/// Calculates month boundaries (the 1st 00:00 to next month's 1st 00:00 local time).
+fn month_boundaries(today: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = local_midnight_to_utc(first_of_month(today));
+    let end = local_midnight_to_utc(first_of_next_month(today));
+    (start, end)
+}
+
+/// Calculates last month boundaries (previous month's 1st 00:00 to this month's 1st 00:00).
+fn last_month_boundaries(today: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let this_month = first_of_month(today);
+    let last_month = first_of_month(this_month - chrono::Duration::days(1));
+
+    let start = local_midnight_to_utc(last_month);
+    let end = local_midnight_to_utc(this_month);
+    (start, end)
+}
+
+/// Returns the first day of the quarter containing `date`.
+fn first_of_quarter(date: NaiveDate) -> NaiveDate {
+    let first_month = (date.month0() / 3) * 3 + 1;
+    NaiveDate::from_ymd_opt(date.year(), first_month, 1).unwrap()
+}
+
+/// Calculates quarter boundaries (the quarter's first day 00:00 to the next quarter's).
+fn quarter_boundaries(today: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let first = first_of_quarter(today);
+    // The quarter's third month ends where the next quarter begins
+    let next = first_of_next_month(first_of_next_month(first_of_next_month(first)));
+
+    let start = local_midnight_to_utc(first);
+    let end = local_midnight_to_utc(next);
+    (start, end)
+}
+
+/// Calculates last quarter boundaries (previous quarter's first day to this quarter's).
+fn last_quarter_boundaries(today: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let this_quarter = first_of_quarter(today);
+    let last_quarter = first_of_quarter(this_quarter - chrono::Duration::days(1));
+
+    let start = local_midnight_to_utc(last_quarter);
+    let end = local_midnight_to_utc(this_quarter);
+    (start, end)
+}
+
 /// Get boundaries for a given period, using the provided date as reference.
 pub fn get_period_boundaries(period: Period, today: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
     match period {
@@ -133,6 +488,10 @@ pub fn get_period_boundaries(period: Period, today: NaiveDate) -> (DateTime<Utc>
         Period::LastWeek => last_week_boundaries(today),
         Period::Day => day_boundaries(today),
         Period::LastDay => last_day_boundaries(today),
+        Period::Month => month_boundaries(today),
+        Period::LastMonth => last_month_boundaries(today),
+        Period::Quarter => quarter_boundaries(today),
+        Period::LastQuarter => last_quarter_boundaries(today),
         Period::Custom(start, end) => (start, end),
     }
 }
@@ -185,6 +544,22 @@ pub fn progress_bar(value: i64, max: i64) -> String {
     format!("{}{}", "█".repeat(filled), "░".repeat(empty))
 }
 
+/// Returns `part_ms` as a percentage of `total_ms`, rounded to one decimal.
+///
+/// Returns 0 when `total_ms` is not positive.
+#[allow(clippy::cast_precision_loss)]
+pub fn percent_of(part_ms: i64, total_ms: i64) -> f64 {
+    if total_ms <= 0 {
+        return 0.0;
+    }
+    (part_ms as f64 * 1000.0 / total_ms as f64).round() / 10.0
+}
+
+/// Formats a percentage for the report's `%` column (e.g. ` 45%`).
+fn format_percent(percent: f64) -> String {
+    format!("{percent:>3.0}%")
+}
+
 // ========== Agent Session Summary ==========
 
 const STARTING_PROMPT_MAX_CHARS: usize = 100;
@@ -264,6 +639,147 @@ fn build_agent_session_summary(
     }
 }
 
+// ========== Session Tree ==========
+
+/// A session with its subagents nested beneath it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionTreeNode {
+    pub session_id: String,
+    pub source: String,
+    pub session_type: String,
+    pub tool_call_count: i32,
+    /// Session duration clamped to the report period. Agent sessions run
+    /// without human attention, so this is the session's delegated time.
+    pub delegated_ms: i64,
+    pub starting_prompt: String,
+    pub children: Vec<Self>,
+}
+
+/// Builds the session tree for top-level sessions in the report.
+///
+/// Subagents are looked up via `subagents_of`, recursively; sessions already
+/// placed in the tree are not revisited.
+fn build_session_tree(
+    sessions: &[AgentSession],
+    period_start: DateTime<Utc>,
+    period_end: DateTime<Utc>,
+    subagents_of: &mut dyn FnMut(&str) -> Result<Vec<AgentSession>>,
+) -> Result<Vec<SessionTreeNode>> {
+    fn build_node(
+        session: &AgentSession,
+        period_start: DateTime<Utc>,
+        period_end: DateTime<Utc>,
+        subagents_of: &mut dyn FnMut(&str) -> Result<Vec<AgentSession>>,
+        visited: &mut BTreeSet<String>,
+    ) -> Result<SessionTreeNode> {
+        let mut children = Vec::new();
+        for child in subagents_of(&session.session_id)? {
+            if visited.insert(child.session_id.clone()) {
+                children.push(build_node(
+                    &child,
+                    period_start,
+                    period_end,
+                    subagents_of,
+                    visited,
+                )?);
+            }
+        }
+
+        Ok(SessionTreeNode {
+            session_id: session.session_id.clone(),
+            source: session.source.as_str().to_string(),
+            session_type: session.session_type.as_str().to_string(),
+            tool_call_count: session.tool_call_count,
+            delegated_ms: session_duration_ms(session, period_start, period_end),
+            starting_prompt: session
+                .starting_prompt
+                .as_deref()
+                .map(truncate_starting_prompt)
+                .unwrap_or_default(),
+            children,
+        })
+    }
+
+    let mut visited: BTreeSet<String> = sessions
+        .iter()
+        .filter(|session| session.parent_session_id.is_none())
+        .map(|session| session.session_id.clone())
+        .collect();
+
+    sessions
+        .iter()
+        .filter(|session| session.parent_session_id.is_none())
+        .map(|session| {
+            build_node(
+                session,
+                period_start,
+                period_end,
+                subagents_of,
+                &mut visited,
+            )
+        })
+        .collect()
+}
+
+/// Loads the session tree for a report, fetching subagents from the database.
+pub fn load_session_tree(db: &Database, data: &ReportData) -> Result<Vec<SessionTreeNode>> {
+    build_session_tree(
+        &data.agent_sessions,
+        data.period_start,
+        data.period_end,
+        &mut |parent_id| {
+            db.get_subagents(parent_id)
+                .with_context(|| format!("failed to get subagents for session {parent_id}"))
+        },
+    )
+}
+
+fn write_session_tree_node(output: &mut String, node: &SessionTreeNode, depth: usize) {
+    let id_short: String = node.session_id.chars().take(6).collect();
+    let indent = if depth == 0 {
+        "  ".to_string()
+    } else {
+        format!("{}└─ ", "   ".repeat(depth))
+    };
+    let prompt = if node.starting_prompt.is_empty() {
+        "(no prompt)"
+    } else {
+        node.starting_prompt.as_str()
+    };
+    writeln!(
+        output,
+        "{indent}{id_short}  {}/{}  {:>6}  {} tools  {prompt}",
+        node.source,
+        node.session_type,
+        format_duration(node.delegated_ms),
+        node.tool_call_count
+    )
+    .unwrap();
+    for child in &node.children {
+        write_session_tree_node(output, child, depth + 1);
+    }
+}
+
+/// Formats the session tree, listing only sessions that spawned subagents.
+pub fn format_session_tree(nodes: &[SessionTreeNode]) -> String {
+    let mut output = String::new();
+    writeln!(output).unwrap();
+    writeln!(output, "SESSION TREE").unwrap();
+    writeln!(output, "────────────").unwrap();
+
+    let with_subagents: Vec<_> = nodes.iter().filter(|n| !n.children.is_empty()).collect();
+    if with_subagents.is_empty() {
+        writeln!(output, "No subagent sessions recorded.").unwrap();
+        return output;
+    }
+
+    for node in with_subagents {
+        write_session_tree_node(&mut output, node, 0);
+    }
+
+    output
+}
+
 // ========== Report Generation ==========
 
 /// Generates report data from the database.
@@ -274,28 +790,42 @@ pub fn generate_report_data(
     db: &Database,
     period: Period,
     generated_at: DateTime<Utc>,
+    config: &AllocationConfig,
 ) -> Result<ReportData> {
     let today = generated_at.with_timezone(&Local).date_naive();
     let timezone = iana_time_zone::get_timezone().unwrap_or_else(|_| "Etc/UTC".to_string());
-    generate_report_data_for_date(db, period, generated_at, today, timezone)
+    generate_report_data_for_date(db, period, generated_at, today, timezone, config)
 }
 
 /// Generates report data from the database for a specific reference date.
+#[allow(clippy::too_many_lines)]
 pub fn generate_report_data_for_date(
     db: &Database,
     period: Period,
     generated_at: DateTime<Utc>,
     reference_date: NaiveDate,
     timezone: String,
+    config: &AllocationConfig,
 ) -> Result<ReportData> {
     let (period_start, period_end) = get_period_boundaries(period, reference_date);
 
     let period_type = match period {
         Period::Week | Period::LastWeek => PeriodType::Week,
+        Period::Month | Period::LastMonth => PeriodType::Month,
+        Period::Quarter | Period::LastQuarter => PeriodType::Quarter,
         Period::Day | Period::LastDay | Period::Custom(_, _) => PeriodType::Day,
     };
 
-    let mut events = get_report_period_events(db, period_start, period_end)?;
+    // Fetch from a lookback before the period so focus and agent activity already
+    // running at period start carry into it. The result is clipped to the period
+    // after allocation, so consecutive periods sum to the allocation over both.
+    config
+        .validate()
+        .map_err(|e| anyhow::anyhow!("invalid allocation config: {e}"))?;
+    let lookback_start = period_start - carry_over_lookback(config);
+
+    let fetch_start = Instant::now();
+    let mut events = get_report_period_events(db, lookback_start, period_end)?;
 
     let session_ids_with_starts: BTreeSet<&str> = events
         .iter()
@@ -323,19 +853,22 @@ pub fn generate_report_data_for_date(
         events = start_events;
     }
 
-    // Fetch agent sessions overlapping the period — needed both for session_type
-    // filtering during allocation and for the report's session summary at the end.
+    // Fetch agent sessions overlapping the period for the report's session summary, and
+    // those overlapping the lookback too for session_type filtering during allocation.
     let agent_sessions = db
-        .agent_sessions_in_range(period_start, period_end)
+        .agent_sessions_in_range(period_start, period_end, None)
         .context("failed to get agent sessions in period")?;
-    let session_types: HashMap<String, SessionType> = agent_sessions
+    let allocation_sessions = db
+        .agent_sessions_in_range(lookback_start, period_end, None)
+        .context("failed to get agent sessions in period")?;
+    let session_types: HashMap<String, SessionType> = allocation_sessions
         .iter()
         .map(|session| (session.session_id.clone(), session.session_type))
         .collect();
 
     // Known session end times so delegated time uses the real end instead of the timeout
     // heuristic (mirrors recompute). Without this, report delegated time can be inaccurate.
-    let session_end_times: HashMap<String, DateTime<Utc>> = agent_sessions
+    let session_end_times: HashMap<String, DateTime<Utc>> = allocation_sessions
         .iter()
         .filter_map(|session| {
             session
@@ -343,18 +876,22 @@ pub fn generate_report_data_for_date(
                 .map(|end| (session.session_id.clone(), end))
         })
         .collect();
+    log_phase("fetch events", fetch_start);
 
     // Calculate time from events using the allocation algorithm
-    let config = AllocationConfig::default();
-    let result = allocate_time(
+    let allocate_start = Instant::now();
+    let mut result = allocate_time(
         &events,
-        &config,
+        config,
         Some(period_end),
         &session_end_times,
         &session_types,
     );
+    result.clip_to(period_start, period_end);
+    log_phase("allocate", allocate_start);
 
     // Get stream metadata (names) for display
+    let aggregate_start = Instant::now();
     let all_streams = db.get_streams().context("failed to get streams")?;
     let stream_names: HashMap<String, Option<String>> =
         all_streams.into_iter().map(|s| (s.id, s.name)).collect();
@@ -366,7 +903,7 @@ pub fn generate_report_data_for_date(
         .collect();
 
     // Convert allocation results to report format, excluding zero-time streams
-    let streams: Vec<ReportStreamTime> = result
+    let mut streams: Vec<ReportStreamTime> = result
         .stream_times
         .into_iter()
         .filter(|t| t.time_direct_ms > 0 || t.time_delegated_ms > 0)
@@ -377,6 +914,8 @@ pub fn generate_report_data_for_date(
             time_delegated_ms: t.time_delegated_ms,
         })
         .collect();
+    streams.sort_by(compare_streams);
+    log_phase("aggregate", aggregate_start);
 
     Ok(ReportData {
         generated_at,
@@ -389,9 +928,148 @@ pub fn generate_report_data_for_date(
         agent_sessions,
         unassigned_direct_ms: result.unassigned_direct_ms,
         unassigned_delegated_ms: result.unassigned_delegated_ms,
+        total_tracked_ms: result.total_tracked_ms,
+        activity: result.activity,
     })
 }
 
+/// How far before a period to look for activity that is still running at its start.
+///
+/// Focus lasts at most one attention window past its last event, and an agent session
+/// with no tool use within its timeout has ended.
+fn carry_over_lookback(config: &AllocationConfig) -> chrono::Duration {
+    let longest_timeout_ms = config
+        .agent_timeout_overrides_ms
+        .values()
+        .copied()
+        .fold(config.agent_timeout_ms, i64::max);
+    chrono::Duration::milliseconds(config.attention_window_ms.max(longest_timeout_ms))
+}
+
+/// Splits a report's period into local calendar days and generates a report for each.
+///
+/// The first and last days are clipped to the period, so a custom range starting
+/// mid-day still yields days that exactly cover it.
+pub fn generate_daily_report_data(
+    db: &Database,
+    data: &ReportData,
+    config: &AllocationConfig,
+) -> Result<Vec<ReportData>> {
+    generate_report_data_by_day(
+        db,
+        data.period_start,
+        data.period_end,
+        data.generated_at,
+        &data.timezone,
+        config,
+    )
+}
+
+/// Generates one report per local calendar day in `period_start..period_end`.
+///
+/// The first and last days are clipped to the range.
+pub fn generate_report_data_by_day(
+    db: &Database,
+    period_start: DateTime<Utc>,
+    period_end: DateTime<Utc>,
+    generated_at: DateTime<Utc>,
+    timezone: &str,
+    config: &AllocationConfig,
+) -> Result<Vec<ReportData>> {
+    let mut days = Vec::new();
+    let mut date = period_start.with_timezone(&Local).date_naive();
+    loop {
+        let day_start = local_midnight_to_utc(date).max(period_start);
+        if day_start >= period_end {
+            break;
+        }
+        let next_date = date + chrono::Duration::days(1);
+        let day_end = local_midnight_to_utc(next_date).min(period_end);
+        days.push(generate_report_data_for_date(
+            db,
+            Period::Custom(day_start, day_end),
+            generated_at,
+            date,
+            timezone.to_string(),
+            config,
+        )?);
+        date = next_date;
+    }
+    Ok(days)
+}
+
+/// Returns whether a day report covers a Saturday or Sunday in local time.
+fn is_weekend_day(day: &ReportData) -> bool {
+    matches!(
+        day.period_start.with_timezone(&Local).weekday(),
+        chrono::Weekday::Sat | chrono::Weekday::Sun
+    )
+}
+
+/// Rebuilds a report from its weekday (Monday to Friday, local time) days only.
+///
+/// Days are allocated separately and clipped at local midnight, so activity
+/// running from Friday night into Saturday keeps only its Friday portion.
+/// The period bounds stay those of `data`.
+pub fn exclude_weekends(
+    db: &Database,
+    data: &ReportData,
+    config: &AllocationConfig,
+) -> Result<ReportData> {
+    let mut days = generate_daily_report_data(db, data, config)?;
+    days.retain(|day| !is_weekend_day(day));
+    Ok(merge_days(data, &days))
+}
+
+/// Sums day reports into one report with the period bounds of `data`.
+fn merge_days(data: &ReportData, days: &[ReportData]) -> ReportData {
+    let mut streams: Vec<ReportStreamTime> = Vec::new();
+    let mut agent_sessions: Vec<AgentSession> = Vec::new();
+    let mut unassigned_direct_ms = 0;
+    let mut unassigned_delegated_ms = 0;
+    let mut total_tracked_ms = 0;
+    let mut activity = Vec::new();
+    for day in days {
+        for stream in &day.streams {
+            match streams.iter_mut().find(|s| s.id == stream.id) {
+                Some(existing) => {
+                    existing.time_direct_ms += stream.time_direct_ms;
+                    existing.time_delegated_ms += stream.time_delegated_ms;
+                }
+                None => streams.push(stream.clone()),
+            }
+        }
+        for session in &day.agent_sessions {
+            if !agent_sessions
+                .iter()
+                .any(|s| s.session_id == session.session_id)
+            {
+                agent_sessions.push(session.clone());
+            }
+        }
+        unassigned_direct_ms += day.unassigned_direct_ms;
+        unassigned_delegated_ms += day.unassigned_delegated_ms;
+        total_tracked_ms += day.total_tracked_ms;
+        activity.extend(day.activity.iter().cloned());
+    }
+    streams.sort_by(compare_streams);
+
+    ReportData {
+        generated_at: data.generated_at,
+        period_start: data.period_start,
+        period_end: data.period_end,
+        period_type: data.period_type,
+        timezone: data.timezone.clone(),
+        streams,
+        tags_by_stream: data.tags_by_stream.clone(),
+        agent_sessions,
+        unassigned_direct_ms,
+        unassigned_delegated_ms,
+        total_tracked_ms,
+        activity,
+    }
+}
+
 fn get_report_period_events(
     db: &Database,
     period_start: DateTime<Utc>,
@@ -420,6 +1098,14 @@ fn format_period_description(report_data: &ReportData) -> String {
             // "Wednesday, Jan 29, 2025"
             format!("{}", start_date.format("%A, %b %-d, %Y"))
         }
+        PeriodType::Month => {
+            // "January 2025"
+            format!("{}", start_date.format("%B %Y"))
+        }
+        PeriodType::Quarter => {
+            // "Q1 2025"
+            format!("Q{} {}", start_date.month0() / 3 + 1, start_date.year())
+        }
     }
 }
 
@@ -462,7 +1148,7 @@ fn write_agent_session_summary(output: &mut String, summary: &JsonAgentSessionSu
     }
 
     for session in &summary.top_sessions {
-        let id_short = &session.session_id[..6.min(session.session_id.len())];
+        let id_short: String = session.session_id.chars().take(6).collect();
         let duration = format_duration(session.duration_ms);
         let prompt = if session.starting_prompt.is_empty() {
             "(no prompt)"
@@ -478,9 +1164,65 @@ fn write_agent_session_summary(output: &mut String, summary: &JsonAgentSessionSu
     }
 }
 
-/// Formats the human-readable report output.
-#[allow(clippy::too_many_lines)]
+/// Formats the human-readable report output without color.
 pub fn format_report(data: &ReportData) -> String {
+    format_report_styled(data, false)
+}
+
+/// Formats the human-readable report output, optionally with ANSI color.
+///
+/// Tags and direct/delegated durations are colored; layout is identical either way.
+pub fn format_report_styled(data: &ReportData, color: bool) -> String {
+    format_report_fitted(data, color, TableLayout::default())
+}
+
+/// Row layout for the human-readable report tables.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableLayout {
+    /// Columns to fit tag and stream names to; `None` keeps them whole.
+    pub width: Option<usize>,
+    /// Show only this many tags and streams, rolling the rest into an "other" row.
+    pub top: Option<usize>,
+}
+
+/// Splits `entries` (sorted by time) into the first `top` and the rest.
+fn split_top<T>(entries: &[T], top: Option<usize>) -> (&[T], &[T]) {
+    entries.split_at(top.map_or(entries.len(), |top| top.min(entries.len())))
+}
+
+/// Label for the row standing in for `count` entries cut by `--top`.
+fn other_label(count: usize) -> String {
+    if count == 1 {
+        "other (1 entry)".to_string()
+    } else {
+        format!("other ({count} entries)")
+    }
+}
+
+/// Minimum characters kept for a tag or stream name however narrow the width.
+const MIN_NAME_WIDTH: usize = 8;
+/// Columns after the tag: duration, percent and bar.
+const TAG_ROW_SUFFIX_WIDTH: usize = 24;
+/// Columns around a session name: indent and short ID before, "(10h 30m)" after.
+const SESSION_ROW_FIXED_WIDTH: usize = 21;
+
+/// Name column width for a row whose other columns take `fixed` of `width`.
+fn name_column_width(width: Option<usize>, fixed: usize, natural: usize) -> usize {
+    width.map_or(natural, |width| {
+        width.saturating_sub(fixed).clamp(MIN_NAME_WIDTH, natural)
+    })
+}
+
+/// Formats the human-readable report laid out by `layout`.
+///
+/// Names too long for their column are truncated with an ellipsis. With
+/// `layout.top`, tags and streams past the first N collapse into one
+/// "other (M entries)" row carrying their combined time, so totals still add up.
+/// The default layout is how [`format_report_styled`] renders.
+#[allow(clippy::too_many_lines)]
+pub fn format_report_fitted(data: &ReportData, color: bool, layout: TableLayout) -> String {
+    let TableLayout { width, top } = layout;
+    let palette = Palette { enabled: color };
     let mut output = String::new();
 
     // Header
@@ -498,6 +1240,8 @@ pub fn format_report(data: &ReportData) -> String {
         let period_word = match data.period_type {
             PeriodType::Week => "week",
             PeriodType::Day => "day",
+            PeriodType::Month => "month",
+            PeriodType::Quarter => "quarter",
         };
         writeln!(output).unwrap();
         writeln!(output, "No events recorded this {period_word}.").unwrap();
@@ -518,7 +1262,7 @@ pub fn format_report(data: &ReportData) -> String {
         + data.unassigned_delegated_ms;
     let total_time = total_direct + total_delegated;
 
-    let tag_entries = build_tag_entries(&data.streams, &data.tags_by_stream);
+    let tag_entries = build_tag_entries(&data.streams, &data.tags_by_stream, total_time);
     let mut untagged_direct_ms = 0;
     let mut untagged_delegated_ms = 0;
     for stream in &data.streams {
@@ -533,14 +1277,21 @@ pub fn format_report(data: &ReportData) -> String {
     let untagged_total_ms = untagged_direct_ms + untagged_delegated_ms;
     let unassigned_total_ms = data.unassigned_direct_ms + data.unassigned_delegated_ms;
 
+    let (shown_tags, other_tags) = split_top(&tag_entries, top);
+    let other_tags_ms: i64 = other_tags
+        .iter()
+        .map(|entry| entry.time_direct_ms + entry.time_delegated_ms)
+        .sum();
+
     let max_total = if tag_entries.is_empty() {
         total_time
     } else {
-        let max_tag_total = tag_entries
+        let max_tag_total = shown_tags
             .iter()
             .map(|entry| entry.time_direct_ms + entry.time_delegated_ms)
             .max()
-            .unwrap_or(0);
+            .unwrap_or(0)
+            .max(other_tags_ms);
         std::cmp::max(max_tag_total, untagged_total_ms).max(unassigned_total_ms)
     };
 
@@ -552,18 +1303,26 @@ pub fn format_report(data: &ReportData) -> String {
     if tag_entries.is_empty() {
         writeln!(output, "(no tagged streams)").unwrap();
     } else {
-        let mut sorted_tags = tag_entries;
-        sorted_tags.sort_by(|a, b| {
-            let a_total = a.time_direct_ms + a.time_delegated_ms;
-            let b_total = b.time_direct_ms + b.time_delegated_ms;
-            b_total.cmp(&a_total).then_with(|| a.tag.cmp(&b.tag))
-        });
-
-        for entry in sorted_tags {
+        let tag_width = name_column_width(width, TAG_ROW_SUFFIX_WIDTH, 36);
+        for entry in shown_tags {
             let total_ms = entry.time_direct_ms + entry.time_delegated_ms;
             let duration = format_duration(total_ms);
             let bar = progress_bar(total_ms, max_total);
-            writeln!(output, "{:<36}{:>7}  {}", entry.tag, duration, bar).unwrap();
+            let percent = format_percent(entry.percent);
+            let label = if width.is_some() {
+                truncate_with_ellipsis(&entry.tag, tag_width - 1)
+            } else {
+                entry.tag.clone()
+            };
+            let tag = palette.tag(&format!("{label:<tag_width$}"));
+            writeln!(output, "{tag}{duration:>7} {percent}  {bar}").unwrap();
+        }
+        if !other_tags.is_empty() {
+            let label = other_label(other_tags.len());
+            let duration = format_duration(other_tags_ms);
+            let percent = format_percent(percent_of(other_tags_ms, total_time));
+            let bar = progress_bar(other_tags_ms, max_total);
+            writeln!(output, "{label:<tag_width$}{duration:>7} {percent}  {bar}").unwrap();
         }
     }
 
@@ -571,21 +1330,23 @@ pub fn format_report(data: &ReportData) -> String {
     writeln!(output).unwrap();
     let untagged_total = format_duration(untagged_total_ms);
     let untagged_bar = progress_bar(untagged_total_ms, max_total);
+    let untagged_percent = format_percent(percent_of(untagged_total_ms, total_time));
     writeln!(
         output,
-        "(untagged)                                {untagged_total:>7}  {untagged_bar}"
+        "{:<42}{untagged_total:>7} {untagged_percent}  {untagged_bar}",
+        "(untagged)"
     )
     .unwrap();
     writeln!(
         output,
         "  Direct:    {}",
-        format_duration(untagged_direct_ms)
+        palette.direct(&format_duration(untagged_direct_ms))
     )
     .unwrap();
     writeln!(
         output,
         "  Delegated: {}",
-        format_duration(untagged_delegated_ms)
+        palette.delegated(&format_duration(untagged_delegated_ms))
     )
     .unwrap();
 
@@ -595,22 +1356,23 @@ pub fn format_report(data: &ReportData) -> String {
         writeln!(output).unwrap();
         let unassigned_total = format_duration(unassigned_total_ms);
         let unassigned_bar = progress_bar(unassigned_total_ms, max_total);
+        let unassigned_percent = format_percent(percent_of(unassigned_total_ms, total_time));
         writeln!(
             output,
-            "{:<42}{:>7}  {}",
-            "(unassigned)", unassigned_total, unassigned_bar
+            "{:<42}{:>7} {}  {}",
+            "(unassigned)", unassigned_total, unassigned_percent, unassigned_bar
         )
         .unwrap();
         writeln!(
             output,
             "  Direct:    {}",
-            format_duration(data.unassigned_direct_ms)
+            palette.direct(&format_duration(data.unassigned_direct_ms))
         )
         .unwrap();
         writeln!(
             output,
             "  Delegated: {}",
-            format_duration(data.unassigned_delegated_ms)
+            palette.delegated(&format_duration(data.unassigned_delegated_ms))
         )
         .unwrap();
         writeln!(
@@ -623,22 +1385,36 @@ pub fn format_report(data: &ReportData) -> String {
     // Sessions list
     writeln!(output, "  Sessions:").unwrap();
 
-    // Sort streams by total time descending
+    // Sort streams by total time descending, ties by name
     let mut sorted_streams: Vec<_> = data.streams.iter().collect();
-    sorted_streams.sort_by_key(|s| std::cmp::Reverse(s.time_direct_ms + s.time_delegated_ms));
+    sorted_streams.sort_by(|a, b| compare_streams(a, b));
 
-    let show_count = 5.min(sorted_streams.len());
-    let remaining = sorted_streams.len().saturating_sub(5);
+    let show_count = top.unwrap_or(5).min(sorted_streams.len());
+    let remaining = sorted_streams.len() - show_count;
 
+    let name_width = name_column_width(width, SESSION_ROW_FIXED_WIDTH, 26);
     for stream in sorted_streams.iter().take(show_count) {
         let id_short = &stream.id[..6.min(stream.id.len())];
         let name = stream.name.as_deref().unwrap_or("(unnamed)");
+        let name = if width.is_some() {
+            truncate_with_ellipsis(name, name_width - 1)
+        } else {
+            name.to_string()
+        };
         let stream_total = stream.time_direct_ms + stream.time_delegated_ms;
         let duration = format_duration(stream_total);
-        writeln!(output, "    {id_short}  {name:<26}({duration})").unwrap();
+        writeln!(output, "    {id_short}  {name:<name_width$}({duration})").unwrap();
     }
 
-    if remaining > 0 {
+    if remaining > 0 && top.is_some() {
+        let other_ms: i64 = sorted_streams[show_count..]
+            .iter()
+            .map(|s| s.time_direct_ms + s.time_delegated_ms)
+            .sum();
+        let label = other_label(remaining);
+        let duration = format_duration(other_ms);
+        writeln!(output, "    {:6}  {label:<name_width$}({duration})", "").unwrap();
+    } else if remaining > 0 {
         writeln!(output, "    ... and {remaining} more").unwrap();
     }
 
@@ -672,21 +1448,26 @@ pub fn format_report(data: &ReportData) -> String {
         writeln!(
             output,
             "Direct time:    {} ({direct_pct}%)",
-            format_duration(total_direct)
+            palette.direct(&format_duration(total_direct))
         )
         .unwrap();
         writeln!(
             output,
             "Delegated time: {} ({delegated_pct}%)",
-            format_duration(total_delegated)
+            palette.delegated(&format_duration(total_delegated))
         )
         .unwrap();
     } else {
-        writeln!(output, "Direct time:    {}", format_duration(total_direct)).unwrap();
+        writeln!(
+            output,
+            "Direct time:    {}",
+            palette.direct(&format_duration(total_direct))
+        )
+        .unwrap();
         writeln!(
             output,
             "Delegated time: {}",
-            format_duration(total_delegated)
+            palette.delegated(&format_duration(total_delegated))
         )
         .unwrap();
     }
@@ -694,25 +1475,191 @@ pub fn format_report(data: &ReportData) -> String {
     output
 }
 
-// ========== JSON Output ==========
+const DAILY_LABEL_WIDTH: usize = 20;
+const DAILY_CELL_WIDTH: usize = 9;
 
-/// JSON report structure.
-#[derive(Debug, Serialize)]
-pub struct JsonReport {
-    pub generated_at: String,
-    pub timezone: String,
-    pub week_start_day: String,
-    pub period: JsonPeriod,
-    pub by_tag: Vec<JsonTagEntry>,
-    pub untagged: JsonUntagged,
-    pub agent_sessions: JsonAgentSessionSummary,
-    pub totals: JsonTotals,
+/// How tracked time spreads over the days of a period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DailyRate {
+    /// Days with any tracked (direct or delegated) time.
+    pub active_days: usize,
+    /// Direct time per active day; zero when no day was active.
+    pub avg_direct_ms_per_active_day: i64,
 }
 
-#[derive(Debug, Serialize)]
-pub struct JsonWeeksReport {
-    pub weeks: Vec<JsonReport>,
-}
+impl DailyRate {
+    /// Computes the rate from per-day reports (see [`generate_daily_report_data`]).
+    pub fn from_days(days: &[ReportData]) -> Self {
+        let active: Vec<&ReportData> = days.iter().filter(|d| report_total_ms(d) > 0).collect();
+        let direct_ms: i64 = active.iter().map(|d| report_direct_ms(d)).sum();
+        Self::new(active.len(), direct_ms)
+    }
+
+    /// Computes the rate from a report's own activity spans, without per-day reports.
+    ///
+    /// A local calendar day is active when any remaining span touches it.
+    pub fn from_activity(data: &ReportData) -> Self {
+        let mut active: BTreeSet<NaiveDate> = BTreeSet::new();
+        for span in &data.activity {
+            let mut day = span.start.with_timezone(&Local).date_naive();
+            let last = (span.end - chrono::Duration::milliseconds(1))
+                .with_timezone(&Local)
+                .date_naive();
+            while day <= last {
+                active.insert(day);
+                day += chrono::Duration::days(1);
+            }
+        }
+        Self::new(active.len(), report_direct_ms(data))
+    }
+
+    fn new(active_days: usize, direct_ms: i64) -> Self {
+        Self {
+            active_days,
+            avg_direct_ms_per_active_day: i64::try_from(active_days)
+                .ok()
+                .filter(|&n| n > 0)
+                .map_or(0, |n| direct_ms / n),
+        }
+    }
+}
+
+/// Formats the active-day lines that close the SUMMARY section.
+pub fn format_daily_rate(rate: &DailyRate) -> String {
+    let mut output = String::new();
+    writeln!(output, "Active days:    {}", rate.active_days).unwrap();
+    writeln!(
+        output,
+        "Avg direct/day: {}",
+        format_duration(rate.avg_direct_ms_per_active_day)
+    )
+    .unwrap();
+    output
+}
+
+/// Total direct time in a report, including unassigned activity.
+fn report_direct_ms(data: &ReportData) -> i64 {
+    data.streams.iter().map(|s| s.time_direct_ms).sum::<i64>() + data.unassigned_direct_ms
+}
+
+/// Total direct plus delegated time in a report, including unassigned activity.
+fn report_total_ms(data: &ReportData) -> i64 {
+    data.streams
+        .iter()
+        .map(|s| s.time_direct_ms + s.time_delegated_ms)
+        .sum::<i64>()
+        + data.unassigned_direct_ms
+        + data.unassigned_delegated_ms
+}
+
+/// Formats a tag × day matrix of total time, with untagged and daily total rows.
+///
+/// Multi-tagged streams count toward each of their tags, as in the main report.
+pub fn format_daily_breakdown(days: &[ReportData]) -> String {
+    let day_tags: Vec<HashMap<String, i64>> = days
+        .iter()
+        .map(|day| {
+            build_tag_entries(&day.streams, &day.tags_by_stream, report_total_ms(day))
+                .into_iter()
+                .map(|entry| (entry.tag, entry.time_direct_ms + entry.time_delegated_ms))
+                .collect()
+        })
+        .collect();
+    let tags: BTreeSet<&str> = day_tags
+        .iter()
+        .flat_map(|totals| totals.keys().map(String::as_str))
+        .collect();
+
+    let mut output = String::new();
+    writeln!(output).unwrap();
+    writeln!(output, "DAILY BREAKDOWN").unwrap();
+    writeln!(output, "───────────────").unwrap();
+
+    let mut header = format!("{:<DAILY_LABEL_WIDTH$}", "");
+    for day in days {
+        let date = day.period_start.with_timezone(&Local).date_naive();
+        let label = date.format("%a %d").to_string();
+        write!(header, "{label:>DAILY_CELL_WIDTH$}").unwrap();
+    }
+    write!(header, "{:>DAILY_CELL_WIDTH$}", "Total").unwrap();
+    writeln!(output, "{}", header.trim_end()).unwrap();
+
+    for tag in &tags {
+        let cells: Vec<i64> = day_tags
+            .iter()
+            .map(|totals| totals.get(*tag).copied().unwrap_or(0))
+            .collect();
+        write_daily_row(&mut output, tag, &cells);
+    }
+    let untagged: Vec<i64> = days
+        .iter()
+        .map(|day| {
+            day.streams
+                .iter()
+                .filter(|s| day.tags_by_stream.get(&s.id).is_none_or(Vec::is_empty))
+                .map(|s| s.time_direct_ms + s.time_delegated_ms)
+                .sum()
+        })
+        .collect();
+    write_daily_row(&mut output, "(untagged)", &untagged);
+    let totals: Vec<i64> = days.iter().map(report_total_ms).collect();
+    write_daily_row(&mut output, "Total", &totals);
+
+    output
+}
+
+/// Writes one matrix row: the label, one cell per day, then the row total.
+fn write_daily_row(output: &mut String, label: &str, cells: &[i64]) {
+    write!(output, "{label:<DAILY_LABEL_WIDTH$}").unwrap();
+    let total: i64 = cells.iter().sum();
+    for &ms in cells.iter().chain(std::iter::once(&total)) {
+        let cell = if ms > 0 {
+            format_duration(ms)
+        } else {
+            "-".to_string()
+        };
+        write!(output, "{cell:>DAILY_CELL_WIDTH$}").unwrap();
+    }
+    writeln!(output).unwrap();
+}
+
+// ========== JSON Output ==========
+
+/// JSON report structure.
+#[derive(Debug, Serialize)]
+pub struct JsonReport {
+    pub generated_at: String,
+    pub timezone: String,
+    pub week_start_day: String,
+    pub period: JsonPeriod,
+    pub by_tag: Vec<JsonTagEntry>,
+    pub untagged: JsonUntagged,
+    pub agent_sessions: JsonAgentSessionSummary,
+    pub totals: JsonTotals,
+    /// Per-day reports within the period (`--daily` only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days: Option<Vec<Self>>,
+    /// Time grouped by dominant git project (`--by-project` only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_project: Option<Vec<JsonProjectEntry>>,
+    /// Time grouped by work type (`--by-category` only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_category: Option<Vec<JsonCategoryEntry>>,
+    /// Delegated time per unit of direct time (`--agent-leverage` only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_leverage: Option<JsonAgentLeverage>,
+    /// Progress against per-tag budgets (`--budget` only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budgets: Option<Vec<JsonBudgetEntry>>,
+    /// Active days and average direct time per active day.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<DailyRate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonWeeksReport {
+    pub weeks: Vec<JsonReport>,
+}
 
 #[derive(Debug, Serialize)]
 pub struct JsonPeriod {
@@ -728,6 +1675,54 @@ pub struct JsonTagEntry {
     pub time_direct_ms: i64,
     pub time_delegated_ms: i64,
     pub streams: Vec<String>,
+    /// Share of the report's total (direct + delegated) time, in percent.
+    pub percent: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonProjectEntry {
+    pub project: String,
+    pub time_direct_ms: i64,
+    pub time_delegated_ms: i64,
+    pub streams: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonCategoryEntry {
+    pub category: String,
+    pub time_direct_ms: i64,
+    pub time_delegated_ms: i64,
+    pub streams: Vec<String>,
+}
+
+/// Delegated ÷ direct time, per tag and for the whole report.
+#[derive(Debug, Serialize)]
+pub struct JsonAgentLeverage {
+    /// `None` (JSON `null`) when the report has no direct time.
+    pub overall: Option<f64>,
+    pub by_tag: Vec<JsonLeverageEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonLeverageEntry {
+    pub tag: String,
+    pub time_direct_ms: i64,
+    pub time_delegated_ms: i64,
+    /// `None` (JSON `null`) when the tag has no direct time.
+    pub leverage: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct JsonBudgetEntry {
+    pub tag: String,
+    /// Direct plus delegated time on the tag, attributed as in `by_tag`.
+    pub time_ms: i64,
+    pub budget_ms: i64,
+    /// Budget left; 0 once over budget.
+    pub remaining_ms: i64,
+    /// Time past the budget; 0 while within it.
+    pub overage_ms: i64,
+    pub over_budget: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -735,6 +1730,8 @@ pub struct JsonUntagged {
     pub time_direct_ms: i64,
     pub time_delegated_ms: i64,
     pub streams: Vec<String>,
+    /// Share of the report's total (direct + delegated) time, in percent.
+    pub percent: f64,
 }
 
 #[derive(Debug, Serialize)]
@@ -746,6 +1743,8 @@ pub struct JsonTotals {
     pub unassigned_direct_ms: i64,
     /// Delegated time on activity not assigned to any stream (subset of `time_delegated_ms`).
     pub unassigned_delegated_ms: i64,
+    /// Wall-clock time with any activity; overlaps count once, unlike the sums above.
+    pub total_tracked_ms: i64,
 }
 
 #[derive(Debug, Serialize)]
@@ -779,10 +1778,14 @@ struct TagAggregate {
 /// attributed to EACH tag. This means `sum(by_tag.time_direct_ms)` may exceed
 /// `totals.time_direct_ms` when streams have overlapping tags. This is intentional —
 /// tags represent orthogonal dimensions (e.g., project + activity), so each dimension
-/// should reflect the complete time spent.
+/// should reflect the complete time spent. For the same reason, tag percentages of
+/// `total_ms` may sum past 100.
+///
+/// Entries are sorted by total time descending, then by tag.
 fn build_tag_entries(
     streams: &[ReportStreamTime],
     tags_by_stream: &HashMap<String, Vec<String>>,
+    total_ms: i64,
 ) -> Vec<JsonTagEntry> {
     let mut by_tag: BTreeMap<String, TagAggregate> = BTreeMap::new();
 
@@ -797,23 +1800,344 @@ fn build_tag_entries(
         }
     }
 
-    by_tag
+    let mut entries = by_tag
         .into_iter()
         .map(|(tag, aggregate)| JsonTagEntry {
             tag,
+            percent: percent_of(
+                aggregate.time_direct_ms + aggregate.time_delegated_ms,
+                total_ms,
+            ),
             time_direct_ms: aggregate.time_direct_ms,
             time_delegated_ms: aggregate.time_delegated_ms,
             streams: aggregate.streams.into_iter().collect(),
         })
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| {
+        let a_total = a.time_direct_ms + a.time_delegated_ms;
+        let b_total = b.time_direct_ms + b.time_delegated_ms;
+        b_total.cmp(&a_total).then_with(|| a.tag.cmp(&b.tag))
+    });
+    entries
+}
+
+/// Bucket for streams whose events carry no `git_project`.
+const UNKNOWN_PROJECT: &str = "(unknown project)";
+
+/// Bucket for streams without a `type:` category tag.
+const UNCATEGORIZED: &str = "(uncategorized)";
+
+/// Groups stream time by a single key per stream, sorted by total time with
+/// the `fallback` bucket (streams without a key) last.
+fn group_streams<'a>(
+    streams: &'a [ReportStreamTime],
+    key: impl Fn(&ReportStreamTime) -> Option<&'a str>,
+    fallback: &'a str,
+) -> Vec<(&'a str, TagAggregate)> {
+    let mut groups: BTreeMap<&str, TagAggregate> = BTreeMap::new();
+    for stream in streams {
+        let entry = groups.entry(key(stream).unwrap_or(fallback)).or_default();
+        entry.time_direct_ms += stream.time_direct_ms;
+        entry.time_delegated_ms += stream.time_delegated_ms;
+        entry.streams.insert(stream.id.clone());
+    }
+
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by(|(a_name, a), (b_name, b)| {
+        let a_total = a.time_direct_ms + a.time_delegated_ms;
+        let b_total = b.time_direct_ms + b.time_delegated_ms;
+        (*a_name == fallback)
+            .cmp(&(*b_name == fallback))
+            .then_with(|| b_total.cmp(&a_total))
+            .then_with(|| a_name.cmp(b_name))
+    });
+    groups
+}
+
+/// Groups stream time by each stream's dominant git project.
+///
+/// Unlike tags, every stream lands in exactly one project, so the entries sum
+/// to the assigned total. Entries are sorted by total time, with the unknown
+/// bucket last.
+#[allow(clippy::implicit_hasher)]
+pub fn build_project_entries(
+    streams: &[ReportStreamTime],
+    projects: &HashMap<String, String>,
+) -> Vec<JsonProjectEntry> {
+    group_streams(
+        streams,
+        |stream| projects.get(&stream.id).map(String::as_str),
+        UNKNOWN_PROJECT,
+    )
+    .into_iter()
+    .map(|(project, aggregate)| JsonProjectEntry {
+        project: project.to_string(),
+        time_direct_ms: aggregate.time_direct_ms,
+        time_delegated_ms: aggregate.time_delegated_ms,
+        streams: aggregate.streams.into_iter().collect(),
+    })
+    .collect()
+}
+
+/// Groups stream time by work type (the stream's `type:` tag).
+///
+/// Sorted like [`build_project_entries`], with uncategorized streams last.
+#[allow(clippy::implicit_hasher)]
+pub fn build_category_entries(
+    streams: &[ReportStreamTime],
+    tags_by_stream: &HashMap<String, Vec<String>>,
+) -> Vec<JsonCategoryEntry> {
+    group_streams(
+        streams,
+        |stream| {
+            tags_by_stream
+                .get(&stream.id)
+                .and_then(|tags| category_of(tags))
+        },
+        UNCATEGORIZED,
+    )
+    .into_iter()
+    .map(|(category, aggregate)| JsonCategoryEntry {
+        category: category.to_string(),
+        time_direct_ms: aggregate.time_direct_ms,
+        time_delegated_ms: aggregate.time_delegated_ms,
+        streams: aggregate.streams.into_iter().collect(),
+    })
+    .collect()
+}
+
+/// Formats an appended breakdown section of `(label, total_ms)` rows.
+fn format_group_breakdown(title: &str, rows: &[(&str, i64)]) -> String {
+    let mut output = String::new();
+    writeln!(output).unwrap();
+    writeln!(output, "{title}").unwrap();
+    writeln!(output, "{}", "─".repeat(title.chars().count())).unwrap();
+
+    if rows.is_empty() {
+        writeln!(output, "(no streams)").unwrap();
+        return output;
+    }
+    let max_total = rows.iter().map(|(_, ms)| *ms).max().unwrap_or(0);
+    for (label, total_ms) in rows {
+        let duration = format_duration(*total_ms);
+        let bar = progress_bar(*total_ms, max_total);
+        writeln!(output, "{label:<36}{duration:>7}  {bar}").unwrap();
+    }
+    output
+}
+
+/// Formats the `--by-project` section appended to the human-readable report.
+pub fn format_project_breakdown(entries: &[JsonProjectEntry]) -> String {
+    let rows: Vec<(&str, i64)> = entries
+        .iter()
+        .map(|e| (e.project.as_str(), e.time_direct_ms + e.time_delegated_ms))
+        .collect();
+    format_group_breakdown("BY PROJECT", &rows)
+}
+
+/// Formats the `--by-category` section appended to the human-readable report.
+pub fn format_category_breakdown(entries: &[JsonCategoryEntry]) -> String {
+    let rows: Vec<(&str, i64)> = entries
+        .iter()
+        .map(|e| (e.category.as_str(), e.time_direct_ms + e.time_delegated_ms))
+        .collect();
+    format_group_breakdown("BY CATEGORY", &rows)
+}
+
+/// Returns delegated ÷ direct time rounded to two decimals, or `None` when
+/// there is no direct time to divide by.
+#[allow(clippy::cast_precision_loss)]
+fn leverage_ratio(direct_ms: i64, delegated_ms: i64) -> Option<f64> {
+    (direct_ms > 0).then(|| (delegated_ms as f64 * 100.0 / direct_ms as f64).round() / 100.0)
+}
+
+/// Sort key for leverage: delegated time with no direct time ranks as
+/// infinite, and entries with no time at all rank last.
+const fn leverage_rank(entry: &JsonLeverageEntry) -> f64 {
+    match entry.leverage {
+        Some(ratio) => ratio,
+        None if entry.time_delegated_ms > 0 => f64::INFINITY,
+        None => f64::NEG_INFINITY,
+    }
+}
+
+/// Computes agent leverage (delegated ÷ direct time) per tag and overall.
+///
+/// Per-tag time uses the same multi-tag attribution as `by_tag`. The overall
+/// ratio includes untagged and unassigned time. Tags are sorted by leverage
+/// descending, then by tag.
+pub fn build_agent_leverage(data: &ReportData) -> JsonAgentLeverage {
+    let total_direct =
+        data.streams.iter().map(|s| s.time_direct_ms).sum::<i64>() + data.unassigned_direct_ms;
+    let total_delegated = data
+        .streams
+        .iter()
+        .map(|s| s.time_delegated_ms)
+        .sum::<i64>()
+        + data.unassigned_delegated_ms;
+
+    let mut by_tag: Vec<JsonLeverageEntry> = build_tag_entries(
+        &data.streams,
+        &data.tags_by_stream,
+        total_direct + total_delegated,
+    )
+    .into_iter()
+    .map(|entry| JsonLeverageEntry {
+        leverage: leverage_ratio(entry.time_direct_ms, entry.time_delegated_ms),
+        tag: entry.tag,
+        time_direct_ms: entry.time_direct_ms,
+        time_delegated_ms: entry.time_delegated_ms,
+    })
+    .collect();
+    by_tag.sort_by(|a, b| {
+        leverage_rank(b)
+            .total_cmp(&leverage_rank(a))
+            .then_with(|| a.tag.cmp(&b.tag))
+    });
+
+    JsonAgentLeverage {
+        overall: leverage_ratio(total_direct, total_delegated),
+        by_tag,
+    }
+}
+
+/// Compares each budgeted tag's time with its budget, in `budgets` order.
+///
+/// Tag time is direct plus delegated, with the same multi-tag attribution as
+/// `by_tag`; a tag with no time in the period counts as zero.
+pub fn build_budget_entries(data: &ReportData, budgets: &[Budget]) -> Vec<JsonBudgetEntry> {
+    let tag_entries = build_tag_entries(&data.streams, &data.tags_by_stream, 0);
+    budgets
+        .iter()
+        .map(|budget| {
+            let time_ms = tag_entries
+                .iter()
+                .find(|entry| entry.tag == budget.tag)
+                .map_or(0, |entry| entry.time_direct_ms + entry.time_delegated_ms);
+            JsonBudgetEntry {
+                tag: budget.tag.clone(),
+                time_ms,
+                budget_ms: budget.budget_ms,
+                remaining_ms: (budget.budget_ms - time_ms).max(0),
+                overage_ms: (time_ms - budget.budget_ms).max(0),
+                over_budget: time_ms > budget.budget_ms,
+            }
+        })
         .collect()
 }
 
+/// Formats the `--budget` section appended to the human-readable report.
+///
+/// Over-budget tags are marked `OVER` (red when colored).
+pub fn format_budgets(entries: &[JsonBudgetEntry], color: bool) -> String {
+    let palette = Palette { enabled: color };
+    let title = "BUDGETS";
+    let mut output = String::new();
+    writeln!(output).unwrap();
+    writeln!(output, "{title}").unwrap();
+    writeln!(output, "{}", "─".repeat(title.chars().count())).unwrap();
+
+    for entry in entries {
+        let progress = format!(
+            "{} / {}",
+            format_duration(entry.time_ms),
+            format_duration(entry.budget_ms)
+        );
+        let status = if entry.over_budget {
+            palette.over_budget(&format!("{} over  OVER", format_duration(entry.overage_ms)))
+        } else {
+            format!("{} left", format_duration(entry.remaining_ms))
+        };
+        let tag = palette.tag(&format!("{:<28}", entry.tag));
+        writeln!(output, "{tag}{progress:>17}  {status}").unwrap();
+    }
+    output
+}
+
+/// Formats a leverage ratio for display: `2.50x`, `∞` without direct time,
+/// or `-` when there is no time at all.
+fn format_leverage(leverage: Option<f64>, delegated_ms: i64) -> String {
+    match leverage {
+        Some(ratio) => format!("{ratio:.2}x"),
+        None if delegated_ms > 0 => "∞".to_string(),
+        None => "-".to_string(),
+    }
+}
+
+/// Formats the `--agent-leverage` section appended to the human-readable report.
+pub fn format_agent_leverage(leverage: &JsonAgentLeverage, data: &ReportData) -> String {
+    let title = "AGENT LEVERAGE (delegated ÷ direct)";
+    let mut output = String::new();
+    writeln!(output).unwrap();
+    writeln!(output, "{title}").unwrap();
+    writeln!(output, "{}", "─".repeat(title.chars().count())).unwrap();
+
+    for entry in &leverage.by_tag {
+        writeln!(
+            output,
+            "{:<36}{:>7}  {} direct, {} delegated",
+            entry.tag,
+            format_leverage(entry.leverage, entry.time_delegated_ms),
+            format_duration(entry.time_direct_ms),
+            format_duration(entry.time_delegated_ms)
+        )
+        .unwrap();
+    }
+    let total_delegated = data
+        .streams
+        .iter()
+        .map(|s| s.time_delegated_ms)
+        .sum::<i64>()
+        + data.unassigned_delegated_ms;
+    writeln!(
+        output,
+        "{:<36}{:>7}",
+        "Overall",
+        format_leverage(leverage.overall, total_delegated)
+    )
+    .unwrap();
+    output
+}
+
 /// Formats report data as JSON.
 pub fn format_report_json(data: &ReportData) -> Result<String> {
     let report = build_json_report(data);
     Ok(serde_json::to_string_pretty(&report)?)
 }
 
+/// One line of `--jsonl` output, labelled by a `kind` field.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum JsonlRecord<'a> {
+    Tag(&'a JsonTagEntry),
+    Untagged(&'a JsonUntagged),
+    Totals {
+        period: &'a JsonPeriod,
+        #[serde(flatten)]
+        totals: &'a JsonTotals,
+    },
+}
+
+/// Formats report data as newline-delimited JSON for line-by-line processing.
+///
+/// Emits one object per tag, one for untagged streams, then a final totals
+/// object carrying the period. Every line is a complete JSON document.
+pub fn format_report_jsonl(data: &ReportData) -> Result<String> {
+    let report = build_json_report(data);
+    let records = report.by_tag.iter().map(JsonlRecord::Tag).chain([
+        JsonlRecord::Untagged(&report.untagged),
+        JsonlRecord::Totals {
+            period: &report.period,
+            totals: &report.totals,
+        },
+    ]);
+    let mut output = String::new();
+    for record in records {
+        writeln!(output, "{}", serde_json::to_string(&record)?).unwrap();
+    }
+    Ok(output)
+}
+
 fn build_json_report(data: &ReportData) -> JsonReport {
     let local_start = data.period_start.with_timezone(&Local);
     let local_end = data.period_end.with_timezone(&Local);
@@ -835,11 +2159,17 @@ fn build_json_report(data: &ReportData) -> JsonReport {
     let agent_sessions =
         build_agent_session_summary(&data.agent_sessions, data.period_start, data.period_end);
 
-    let by_tag = build_tag_entries(&data.streams, &data.tags_by_stream);
+    let by_tag = build_tag_entries(
+        &data.streams,
+        &data.tags_by_stream,
+        total_direct + total_delegated,
+    );
     let mut untagged_direct_ms = 0;
     let mut untagged_delegated_ms = 0;
     let mut untagged_streams = Vec::new();
-    for stream in &data.streams {
+    let mut sorted_streams: Vec<_> = data.streams.iter().collect();
+    sorted_streams.sort_by(|a, b| compare_streams(a, b));
+    for stream in sorted_streams {
         match data.tags_by_stream.get(&stream.id) {
             Some(tags) if !tags.is_empty() => {}
             _ => {
@@ -861,6 +2191,10 @@ fn build_json_report(data: &ReportData) -> JsonReport {
         },
         by_tag,
         untagged: JsonUntagged {
+            percent: percent_of(
+                untagged_direct_ms + untagged_delegated_ms,
+                total_direct + total_delegated,
+            ),
             time_direct_ms: untagged_direct_ms,
             time_delegated_ms: untagged_delegated_ms,
             streams: untagged_streams,
@@ -872,51 +2206,188 @@ fn build_json_report(data: &ReportData) -> JsonReport {
             stream_count: data.streams.len(),
             unassigned_direct_ms: data.unassigned_direct_ms,
             unassigned_delegated_ms: data.unassigned_delegated_ms,
+            total_tracked_ms: data.total_tracked_ms,
         },
+        days: None,
+        by_project: None,
+        by_category: None,
+        agent_leverage: None,
+        budgets: None,
+        rate: None,
     }
 }
 
 // ========== Public Interface ==========
 
-/// Runs the report command.
-pub fn run(db: &Database, period: Period, json: bool, weeks: Option<u32>) -> Result<()> {
+/// Runs the report command; human-readable output is colored per `ctx`.
+pub fn run(db: &Database, period: Period, options: &ReportOptions, ctx: &OutputCtx) -> Result<()> {
     let generated_at = Utc::now();
-    run_with_weeks(db, period, json, weeks, generated_at)
+    run_with_weeks(db, period, options, *ctx, generated_at)
 }
 
+#[allow(clippy::too_many_lines)]
 fn run_with_weeks(
     db: &Database,
     period: Period,
-    json: bool,
-    weeks: Option<u32>,
+    options: &ReportOptions,
+    ctx: OutputCtx,
     generated_at: DateTime<Utc>,
 ) -> Result<()> {
-    if let Some(weeks) = weeks {
-        let reports = generate_weekly_reports(db, weeks, generated_at)?;
-        if json {
+    let json = options.json;
+    let color = ctx.with_json(json || options.jsonl).color;
+    let config = &options.allocation;
+    let layout = TableLayout {
+        width: options.width.columns(),
+        top: options.top,
+    };
+    let tag_filter = TagFilter::load(db, &options.include_tags, &options.exclude_tags)?;
+    let filter = |data: &mut ReportData| {
+        tag_filter.apply(data);
+        options.dimension.apply(data);
+    };
+    let projects = if options.by_project {
+        db.dominant_git_projects()
+            .context("failed to load stream projects")?
+    } else {
+        HashMap::new()
+    };
+    let build_json = |data: &ReportData| {
+        let mut report = build_json_report(data);
+        if options.by_project {
+            report.by_project = Some(build_project_entries(&data.streams, &projects));
+        }
+        if options.by_category {
+            report.by_category = Some(build_category_entries(&data.streams, &data.tags_by_stream));
+        }
+        if options.agent_leverage {
+            report.agent_leverage = Some(build_agent_leverage(data));
+        }
+        if !options.budgets.is_empty() {
+            report.budgets = Some(build_budget_entries(data, &options.budgets));
+        }
+        report
+    };
+    let breakdowns = |data: &ReportData| {
+        let mut output = String::new();
+        if options.by_project {
+            output.push_str(&format_project_breakdown(&build_project_entries(
+                &data.streams,
+                &projects,
+            )));
+        }
+        if options.by_category {
+            output.push_str(&format_category_breakdown(&build_category_entries(
+                &data.streams,
+                &data.tags_by_stream,
+            )));
+        }
+        if options.agent_leverage {
+            output.push_str(&format_agent_leverage(&build_agent_leverage(data), data));
+        }
+        if !options.budgets.is_empty() {
+            output.push_str(&format_budgets(
+                &build_budget_entries(data, &options.budgets),
+                color,
+            ));
+        }
+        output
+    };
+    let generate = |data: ReportData| -> Result<ReportData> {
+        let mut data = if options.exclude_weekends {
+            exclude_weekends(db, &data, config)?
+        } else {
+            data
+        };
+        filter(&mut data);
+        Ok(data)
+    };
+    if let Some(weeks) = options.weeks {
+        let reports = generate_weekly_reports(db, weeks, generated_at, config)?
+            .into_iter()
+            .map(generate)
+            .collect::<Result<Vec<_>>>()?;
+        let rates: Vec<DailyRate> = reports.iter().map(DailyRate::from_activity).collect();
+        if options.jsonl {
+            for data in &reports {
+                print!("{}", format_report_jsonl(data)?);
+            }
+        } else if json {
             let weeks_report = JsonWeeksReport {
-                weeks: reports.iter().map(build_json_report).collect(),
+                weeks: reports
+                    .iter()
+                    .zip(&rates)
+                    .map(|(data, rate)| JsonReport {
+                        rate: Some(*rate),
+                        ..build_json(data)
+                    })
+                    .collect(),
             };
             println!("{}", serde_json::to_string_pretty(&weeks_report)?);
         } else {
             let separator = "\n\n────────────────────────\n\n";
             let output = reports
                 .iter()
-                .map(format_report)
-                .collect::<Vec<_>>()
+                .zip(&rates)
+                .map(|(data, rate)| {
+                    let mut output = format_report_fitted(data, color, layout);
+                    output.push_str(&format_daily_rate(rate));
+                    output.push_str(&breakdowns(data));
+                    if options.tree {
+                        output.push_str(&format_session_tree(&load_session_tree(db, data)?));
+                    }
+                    Ok(output)
+                })
+                .collect::<Result<Vec<_>>>()?
                 .join(separator);
             print!("{output}");
         }
         return Ok(());
     }
 
-    let data = generate_report_data(db, period, generated_at)?;
+    let data = generate_report_data(db, period, generated_at, config)?;
+    // Per-day reports cost one allocation per day; build them only for --daily,
+    // and reuse them for --exclude-weekends.
+    let (data, days, rate) = if options.daily {
+        let mut days = generate_daily_report_data(db, &data, config)?;
+        if options.exclude_weekends {
+            days.retain(|day| !is_weekend_day(day));
+        }
+        let mut data = if options.exclude_weekends {
+            merge_days(&data, &days)
+        } else {
+            data
+        };
+        filter(&mut data);
+        for day in &mut days {
+            filter(day);
+        }
+        let rate = DailyRate::from_days(&days);
+        (data, days, rate)
+    } else {
+        let data = generate(data)?;
+        let rate = DailyRate::from_activity(&data);
+        (data, Vec::new(), rate)
+    };
 
-    if json {
-        let output = format_report_json(&data)?;
-        println!("{output}");
+    if options.jsonl {
+        print!("{}", format_report_jsonl(&data)?);
+    } else if json {
+        let mut report = build_json(&data);
+        report.rate = Some(rate);
+        if options.daily {
+            report.days = Some(days.iter().map(build_json).collect());
+        }
+        println!("{}", serde_json::to_string_pretty(&report)?);
     } else {
-        let output = format_report(&data);
+        let mut output = format_report_fitted(&data, color, layout);
+        output.push_str(&format_daily_rate(&rate));
+        if options.daily {
+            output.push_str(&format_daily_breakdown(&days));
+        }
+        output.push_str(&breakdowns(&data));
+        if options.tree {
+            output.push_str(&format_session_tree(&load_session_tree(db, &data)?));
+        }
         print!("{output}");
     }
 
@@ -927,6 +2398,7 @@ fn generate_weekly_reports(
     db: &Database,
     weeks: u32,
     generated_at: DateTime<Utc>,
+    config: &AllocationConfig,
 ) -> Result<Vec<ReportData>> {
     let today = Local::now().date_naive();
     let timezone = iana_time_zone::get_timezone().unwrap_or_else(|_| "Etc/UTC".to_string());
@@ -939,6 +2411,7 @@ fn generate_weekly_reports(
             generated_at,
             reference_date,
             timezone.clone(),
+            config,
         )?;
         reports.push(data);
     }
@@ -953,6 +2426,8 @@ mod tests {
     use serde_json::{Value, json};
     use tt_core::session::{SessionSource, SessionType};
 
+    use crate::output::ColorChoice;
+
     // ========== Period Date Calculation Tests ==========
 
     #[test]
@@ -996,6 +2471,85 @@ mod tests {
         assert_eq!(end_local, NaiveDate::from_ymd_opt(2025, 2, 3).unwrap());
     }
 
+    #[test]
+    fn test_month_boundaries_for_mid_month_date() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let local_dates = |(start, end): (DateTime<Utc>, DateTime<Utc>)| {
+            (
+                start.with_timezone(&Local).date_naive(),
+                end.with_timezone(&Local).date_naive(),
+            )
+        };
+
+        assert_eq!(
+            local_dates(month_boundaries(date(2, 14))),
+            (date(2, 1), date(3, 1))
+        );
+        // 2024 is a leap year: last month of Mar 15 is all 29 days of February
+        assert_eq!(
+            local_dates(last_month_boundaries(date(3, 15))),
+            (date(2, 1), date(3, 1))
+        );
+        assert_eq!(
+            local_dates(month_boundaries(date(4, 30))),
+            (date(4, 1), date(5, 1))
+        );
+    }
+
+    #[test]
+    fn test_month_boundaries_roll_over_the_year() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let local_dates = |(start, end): (DateTime<Utc>, DateTime<Utc>)| {
+            (
+                start.with_timezone(&Local).date_naive(),
+                end.with_timezone(&Local).date_naive(),
+            )
+        };
+
+        assert_eq!(
+            local_dates(month_boundaries(date(2024, 12, 31))),
+            (date(2024, 12, 1), date(2025, 1, 1))
+        );
+        assert_eq!(
+            local_dates(last_month_boundaries(date(2025, 1, 10))),
+            (date(2024, 12, 1), date(2025, 1, 1))
+        );
+    }
+
+    #[test]
+    fn test_quarter_boundaries_for_q2_date() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let (start, end) = quarter_boundaries(date(5, 14));
+
+        assert_eq!(start.with_timezone(&Local).date_naive(), date(4, 1));
+        // Half-open: the quarter runs through June 30
+        assert_eq!(end.with_timezone(&Local).date_naive(), date(7, 1));
+    }
+
+    #[test]
+    fn test_last_quarter_of_q1_is_prior_years_q4() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let local_dates = |(start, end): (DateTime<Utc>, DateTime<Utc>)| {
+            (
+                start.with_timezone(&Local).date_naive(),
+                end.with_timezone(&Local).date_naive(),
+            )
+        };
+
+        assert_eq!(
+            local_dates(quarter_boundaries(date(2025, 1, 1))),
+            (date(2025, 1, 1), date(2025, 4, 1))
+        );
+        assert_eq!(
+            local_dates(last_quarter_boundaries(date(2025, 2, 10))),
+            (date(2024, 10, 1), date(2025, 1, 1))
+        );
+        assert_eq!(
+            local_dates(quarter_boundaries(date(2024, 12, 31))),
+            (date(2024, 10, 1), date(2025, 1, 1))
+        );
+    }
+
     #[test]
     fn test_last_week_boundaries_for_known_date() {
         // Jan 29, 2025 is a Wednesday
@@ -1169,6 +2723,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: action.map(ToString::to_string),
             cwd: Some("/home/sami/time-tracker/default".to_string()),
             session_id: Some(session_id.to_string()),
@@ -1226,21 +2781,625 @@ mod tests {
         );
         db.insert_events(&[inside, boundary]).unwrap();
 
-        // When: report code fetches events for the half-open period [start, end).
-        let events = get_report_period_events(&db, start, end).unwrap();
+        // When: report code fetches events for the half-open period [start, end).
+        let events = get_report_period_events(&db, start, end).unwrap();
+
+        // Then: the end-boundary event is excluded from the earlier period.
+        let ids = events
+            .iter()
+            .map(|event| event.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["inside"]);
+        let next_period_ids = get_report_period_events(&db, end, end + chrono::Duration::days(7))
+            .unwrap()
+            .iter()
+            .map(|event| event.id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(next_period_ids, vec!["boundary"]);
+    }
+
+    fn tag_filter_fixture() -> (tt_db::Database, ReportData) {
+        let db = tt_db::Database::open_in_memory().unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 1, 29, 16, 0, 0).unwrap();
+        for id in ["work", "learning", "both", "untagged"] {
+            db.insert_stream(&tt_db::Stream {
+                id: id.to_string(),
+                name: Some(id.to_string()),
+                created_at: now,
+                updated_at: now,
+                time_direct_ms: 0,
+                time_delegated_ms: 0,
+                first_event_at: None,
+                last_event_at: None,
+                needs_recompute: false,
+            })
+            .unwrap();
+        }
+        db.add_tag("work", "acme").unwrap();
+        db.add_tag("learning", "personal-learning").unwrap();
+        db.add_tag("both", "acme").unwrap();
+        db.add_tag("both", "personal-learning").unwrap();
+
+        let data = ReportData {
+            generated_at: now,
+            period_start: Utc.with_ymd_and_hms(2025, 1, 27, 0, 0, 0).unwrap(),
+            period_end: Utc.with_ymd_and_hms(2025, 2, 3, 0, 0, 0).unwrap(),
+            period_type: PeriodType::Week,
+            timezone: "Etc/UTC".to_string(),
+            streams: ["work", "learning", "both", "untagged"]
+                .into_iter()
+                .map(|id| make_test_stream(id, id, 60_000, 0))
+                .collect(),
+            tags_by_stream: db.get_all_tags().unwrap().into_iter().collect(),
+            agent_sessions: vec![],
+            unassigned_direct_ms: 5_000,
+            unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
+        };
+        (db, data)
+    }
+
+    fn stream_ids(data: &ReportData) -> Vec<&str> {
+        data.streams.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tag_filter_exclude_only_keeps_untagged() {
+        let (db, mut data) = tag_filter_fixture();
+        let filter = TagFilter::load(&db, &[], &["personal-learning".to_string()]).unwrap();
+
+        filter.apply(&mut data);
+
+        // Streams with no tags are never excluded, and unassigned time stays.
+        assert_eq!(stream_ids(&data), vec!["work", "untagged"]);
+        assert_eq!(data.unassigned_direct_ms, 5_000);
+    }
+
+    #[test]
+    fn test_tag_filter_exclude_wins_over_include() {
+        let (db, mut data) = tag_filter_fixture();
+        let filter = TagFilter::load(
+            &db,
+            &["acme".to_string()],
+            &["personal-learning".to_string()],
+        )
+        .unwrap();
+
+        filter.apply(&mut data);
+
+        // "both" carries an included and an excluded tag: exclusion wins.
+        assert_eq!(stream_ids(&data), vec!["work"]);
+        assert_eq!(data.unassigned_direct_ms, 0);
+    }
+
+    #[test]
+    fn test_project_entries_group_by_dominant_project() {
+        let streams = vec![
+            make_test_stream("a", "api", 600_000, 0),
+            make_test_stream("b", "api-agent", 0, 300_000),
+            make_test_stream("c", "site", 1_800_000, 0),
+            make_test_stream("d", "scratch", 3_600_000, 0),
+        ];
+        let projects: HashMap<String, String> = [("a", "alpha"), ("b", "alpha"), ("c", "beta")]
+            .into_iter()
+            .map(|(stream, project)| (stream.to_string(), project.to_string()))
+            .collect();
+
+        let entries = build_project_entries(&streams, &projects);
+
+        // Sorted by total, with the project-less bucket last even though it is largest.
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|e| {
+                (
+                    e.project.as_str(),
+                    e.time_direct_ms,
+                    e.time_delegated_ms,
+                    e.streams.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("beta", 1_800_000, 0, vec!["c".to_string()]),
+                (
+                    "alpha",
+                    600_000,
+                    300_000,
+                    vec!["a".to_string(), "b".to_string()]
+                ),
+                ("(unknown project)", 3_600_000, 0, vec!["d".to_string()]),
+            ]
+        );
+
+        let output = format_project_breakdown(&entries);
+        assert!(output.contains("BY PROJECT"));
+        assert!(output.contains("alpha"));
+        assert!(output.contains("(unknown project)"));
+
+        let mut report = build_json_report(&dimension_fixture());
+        assert!(
+            serde_json::to_value(&report)
+                .unwrap()
+                .get("by_project")
+                .is_none()
+        );
+        report.by_project = Some(entries);
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["by_project"][1]["project"], "alpha");
+        assert_eq!(value["by_project"][1]["time_delegated_ms"], 300_000);
+    }
+
+    fn dimension_fixture() -> ReportData {
+        let now = Utc.with_ymd_and_hms(2025, 1, 29, 12, 0, 0).unwrap();
+        ReportData {
+            generated_at: now,
+            period_start: Utc.with_ymd_and_hms(2025, 1, 27, 0, 0, 0).unwrap(),
+            period_end: Utc.with_ymd_and_hms(2025, 2, 3, 0, 0, 0).unwrap(),
+            period_type: PeriodType::Week,
+            timezone: "Etc/UTC".to_string(),
+            streams: vec![
+                make_test_stream("agentic", "agentic", 600_000, 3_600_000),
+                make_test_stream("focused", "focused", 1_800_000, 0),
+                make_test_stream("mixed", "mixed", 900_000, 1_200_000),
+            ],
+            tags_by_stream: HashMap::from([
+                ("agentic".to_string(), vec!["acme".to_string()]),
+                ("focused".to_string(), vec!["acme".to_string()]),
+            ]),
+            agent_sessions: vec![],
+            unassigned_direct_ms: 60_000,
+            unassigned_delegated_ms: 120_000,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_category_entries_group_by_type_tag() {
+        let streams = vec![
+            make_test_stream("a", "auth", 1_800_000, 600_000),
+            make_test_stream("b", "pr-review", 900_000, 0),
+            make_test_stream("c", "billing", 600_000, 0),
+            make_test_stream("d", "standup", 3_600_000, 0),
+        ];
+        let tags = |tags: &[&str]| tags.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let tags_by_stream = HashMap::from([
+            ("a".to_string(), tags(&["acme", "type:coding"])),
+            ("b".to_string(), tags(&["type:reviewing", "acme"])),
+            ("c".to_string(), tags(&["type:coding"])),
+            ("d".to_string(), tags(&["acme"])),
+        ]);
+
+        let entries = build_category_entries(&streams, &tags_by_stream);
+
+        let summary: Vec<_> = entries
+            .iter()
+            .map(|e| (e.category.as_str(), e.time_direct_ms + e.time_delegated_ms))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("coding", 3_000_000),
+                ("reviewing", 900_000),
+                ("(uncategorized)", 3_600_000),
+            ]
+        );
+        assert_eq!(entries[0].streams, vec!["a", "c"]);
+
+        let output = format_category_breakdown(&entries);
+        assert!(output.contains("BY CATEGORY\n───────────\n"), "{output}");
+        assert!(output.contains("reviewing"));
+    }
+
+    #[test]
+    fn test_daily_rate_averages_direct_time_over_active_days() {
+        let day = |direct_ms, delegated_ms, unassigned_direct_ms| ReportData {
+            streams: vec![make_test_stream("s", "s", direct_ms, delegated_ms)],
+            unassigned_direct_ms,
+            unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            ..dimension_fixture()
+        };
+        let days = [
+            day(7_200_000, 0, 0),
+            day(0, 0, 0),
+            // Agent-only days are active but add no direct time
+            day(0, 3_600_000, 0),
+            day(0, 0, 3_600_000),
+            day(0, 0, 0),
+        ];
+
+        let rate = DailyRate::from_days(&days);
+
+        assert_eq!(
+            rate,
+            DailyRate {
+                active_days: 3,
+                avg_direct_ms_per_active_day: 3_600_000,
+            }
+        );
+        let output = format_daily_rate(&rate);
+        assert!(output.contains("Active days:    3"), "{output}");
+        assert!(output.contains("Avg direct/day: 1h"), "{output}");
+        assert_eq!(
+            DailyRate::from_days(&[day(0, 0, 0)]).avg_direct_ms_per_active_day,
+            0
+        );
+    }
+
+    #[test]
+    fn test_daily_rate_from_activity_counts_local_days_touched() {
+        let local = |month, day, hour| {
+            Local
+                .with_ymd_and_hms(2025, month, day, hour, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let span = |stream_id: Option<&str>, start, end, delegated| ActivityInterval {
+            stream_id: stream_id.map(str::to_string),
+            start,
+            end,
+            delegated,
+        };
+        let mut data = ReportData {
+            activity: vec![
+                span(Some("focused"), local(1, 27, 9), local(1, 27, 11), false),
+                span(Some("agentic"), local(1, 28, 9), local(1, 28, 10), true),
+                // Ends exactly at local midnight, so Jan 30 stays inactive
+                span(None, local(1, 29, 22), local(1, 30, 0), false),
+                // Crosses local midnight, so both days count
+                span(Some("mixed"), local(1, 31, 23), local(2, 1, 1), false),
+            ],
+            ..dimension_fixture()
+        };
+
+        assert_eq!(DailyRate::from_activity(&data).active_days, 5);
+
+        TimeDimension::Direct.apply(&mut data);
+        let rate = DailyRate::from_activity(&data);
+        assert_eq!(rate.active_days, 4);
+        // 600_000 + 1_800_000 + 900_000 stream direct + 60_000 unassigned, over 4 days
+        assert_eq!(rate.avg_direct_ms_per_active_day, 840_000);
+    }
+
+    fn session_order(output: &str) -> Vec<&str> {
+        output
+            .lines()
+            .skip_while(|line| !line.contains("Sessions:"))
+            .skip(1)
+            .take_while(|line| line.starts_with("    "))
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .collect()
+    }
+
+    #[test]
+    fn test_only_direct_zeroes_delegated_and_orders_by_direct() {
+        let mut data = dimension_fixture();
+        assert_eq!(
+            session_order(&format_report(&data)),
+            vec!["agentic", "mixed", "focused"]
+        );
+
+        TimeDimension::Direct.apply(&mut data);
+
+        assert!(data.streams.iter().all(|s| s.time_delegated_ms == 0));
+        assert_eq!(data.unassigned_delegated_ms, 0);
+        assert_eq!(
+            session_order(&format_report(&data)),
+            vec!["focused", "mixed", "agentic"]
+        );
+        let json = build_json_report(&data);
+        assert_eq!(json.totals.time_direct_ms, 3_360_000);
+        assert_eq!(json.totals.time_delegated_ms, 0);
+        assert_eq!(json.by_tag[0].time_direct_ms, 2_400_000);
+        assert_eq!(json.by_tag[0].time_delegated_ms, 0);
+    }
+
+    #[test]
+    fn test_only_delegated_drops_streams_without_agent_time() {
+        let mut data = dimension_fixture();
+
+        TimeDimension::Delegated.apply(&mut data);
+
+        assert!(data.streams.iter().all(|s| s.time_direct_ms == 0));
+        assert_eq!(data.unassigned_direct_ms, 0);
+        assert_eq!(
+            session_order(&format_report(&data)),
+            vec!["agentic", "mixed"]
+        );
+        let json = build_json_report(&data);
+        assert_eq!(json.totals.time_direct_ms, 0);
+        assert_eq!(json.totals.time_delegated_ms, 4_920_000);
+        assert_eq!(json.by_tag[0].time_delegated_ms, 3_600_000);
+    }
+
+    #[test]
+    fn test_exclude_weekends_keeps_only_friday_portion_of_spillover() {
+        let db = tt_db::Database::open_in_memory().unwrap();
+        let friday = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+        let saturday = NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+        let sunday = NaiveDate::from_ymd_opt(2025, 2, 2).unwrap();
+        let at = |date: NaiveDate, hour: i64, minute: i64| {
+            local_midnight_to_utc(date)
+                + chrono::Duration::hours(hour)
+                + chrono::Duration::minutes(minute)
+        };
+        db.insert_stream(&tt_db::Stream {
+            id: "stream-a".to_string(),
+            name: Some("a".to_string()),
+            created_at: at(friday, 0, 0),
+            updated_at: at(friday, 0, 0),
+            time_direct_ms: 0,
+            time_delegated_ms: 0,
+            first_event_at: None,
+            last_event_at: None,
+            needs_recompute: false,
+        })
+        .unwrap();
+        let focus = |id: &str, timestamp| {
+            make_agent_event(
+                id,
+                timestamp,
+                tt_core::EventType::TmuxPaneFocus,
+                "session",
+                "stream-a",
+                None,
+            )
+        };
+        // Focus at 23:58 Friday lasts one 5-minute attention window: 2 min Friday, 3 Saturday.
+        db.insert_events(&[
+            focus("fri-night", at(friday, 23, 58)),
+            focus("sat-1", at(saturday, 10, 0)),
+        ])
+        .unwrap();
+
+        let data = generate_report_data_for_date(
+            &db,
+            Period::Custom(local_midnight_to_utc(friday), local_midnight_to_utc(sunday)),
+            at(sunday, 12, 0),
+            friday,
+            "Etc/UTC".to_string(),
+            &AllocationConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(report_total_ms(&data), 600_000);
+
+        let weekdays = exclude_weekends(&db, &data, &AllocationConfig::default()).unwrap();
+        assert_eq!(weekdays.period_start, data.period_start);
+        assert_eq!(weekdays.period_end, data.period_end);
+        assert_eq!(weekdays.streams.len(), 1);
+        assert_eq!(weekdays.streams[0].time_direct_ms, 120_000);
+        assert_eq!(report_total_ms(&weekdays), 120_000);
+    }
+
+    #[test]
+    fn test_report_json_total_tracked_counts_overlap_once() {
+        let db = tt_db::Database::open_in_memory().unwrap();
+        let day = NaiveDate::from_ymd_opt(2025, 1, 29).unwrap();
+        let at = |minute: i64| {
+            local_midnight_to_utc(day)
+                + chrono::Duration::hours(10)
+                + chrono::Duration::minutes(minute)
+        };
+        for id in ["stream-a", "stream-b"] {
+            db.insert_stream(&tt_db::Stream {
+                id: id.to_string(),
+                name: Some(id.to_string()),
+                created_at: at(0),
+                updated_at: at(0),
+                time_direct_ms: 0,
+                time_delegated_ms: 0,
+                first_event_at: None,
+                last_event_at: None,
+                needs_recompute: false,
+            })
+            .unwrap();
+        }
+        // Direct focus on stream-a while an agent works on stream-b.
+        let events = vec![
+            make_agent_event(
+                "focus",
+                at(0),
+                tt_core::EventType::TmuxPaneFocus,
+                "",
+                "stream-a",
+                None,
+            ),
+            make_agent_event(
+                "start",
+                at(0),
+                tt_core::EventType::AgentSession,
+                "sess",
+                "stream-b",
+                Some("started"),
+            ),
+            make_agent_event(
+                "tool",
+                at(2),
+                tt_core::EventType::AgentToolUse,
+                "sess",
+                "stream-b",
+                None,
+            ),
+            make_agent_event(
+                "end",
+                at(4),
+                tt_core::EventType::AgentSession,
+                "sess",
+                "stream-b",
+                Some("ended"),
+            ),
+        ];
+        db.insert_events(&events).unwrap();
+        let period_end = local_midnight_to_utc(day + chrono::Duration::days(1));
+
+        let data = generate_report_data_for_date(
+            &db,
+            Period::Custom(local_midnight_to_utc(day), period_end),
+            period_end,
+            day,
+            "Etc/UTC".to_string(),
+            &AllocationConfig::default(),
+        )
+        .unwrap();
+        let json: Value = serde_json::from_str(&format_report_json(&data).unwrap()).unwrap();
+
+        let expected = allocate_time(
+            &events,
+            &AllocationConfig::default(),
+            Some(period_end),
+            &HashMap::new(),
+            &HashMap::new(),
+        )
+        .total_tracked_ms;
+        assert_eq!(json["totals"]["total_tracked_ms"].as_i64(), Some(expected));
+        assert!(expected > 0);
+        assert!(expected < report_total_ms(&data));
+    }
+
+    #[test]
+    fn test_report_output_is_identical_across_runs() {
+        let db = tt_db::Database::open_in_memory().unwrap();
+        let day = NaiveDate::from_ymd_opt(2025, 1, 29).unwrap();
+        let at = |minute: i64| {
+            local_midnight_to_utc(day)
+                + chrono::Duration::hours(10)
+                + chrono::Duration::minutes(minute)
+        };
+        for id in ["stream-a", "stream-b", "stream-c", "stream-d"] {
+            db.insert_stream(&tt_db::Stream {
+                id: id.to_string(),
+                name: Some(id.to_string()),
+                created_at: at(0),
+                updated_at: at(0),
+                time_direct_ms: 0,
+                time_delegated_ms: 0,
+                first_event_at: None,
+                last_event_at: None,
+                needs_recompute: false,
+            })
+            .unwrap();
+        }
+        db.add_tag("stream-c", "zeta").unwrap();
+        db.add_tag("stream-d", "alpha").unwrap();
+        // Every stream gets one full attention window, so all totals tie.
+        let events: Vec<_> = ["stream-c", "stream-a", "stream-d", "stream-b"]
+            .iter()
+            .zip([0, 10, 20, 30])
+            .map(|(stream, minute)| {
+                make_agent_event(
+                    &format!("focus-{stream}"),
+                    at(minute),
+                    tt_core::EventType::TmuxPaneFocus,
+                    "",
+                    stream,
+                    None,
+                )
+            })
+            .collect();
+        db.insert_events(&events).unwrap();
+        let period_end = local_midnight_to_utc(day + chrono::Duration::days(1));
+        let render = || {
+            let data = generate_report_data_for_date(
+                &db,
+                Period::Custom(local_midnight_to_utc(day), period_end),
+                period_end,
+                day,
+                "Etc/UTC".to_string(),
+                &AllocationConfig::default(),
+            )
+            .unwrap();
+            (format_report(&data), format_report_json(&data).unwrap())
+        };
+
+        let first = render();
+        assert_eq!(first, render());
+        let json: Value = serde_json::from_str(&first.1).unwrap();
+        assert_eq!(json["by_tag"][0]["tag"], "alpha");
+        assert_eq!(json["by_tag"][1]["tag"], "zeta");
+        assert_eq!(json["untagged"]["streams"], json!(["stream-a", "stream-b"]));
+    }
+
+    #[test]
+    fn test_daily_breakdown_matches_weekly_allocation() {
+        let db = tt_db::Database::open_in_memory().unwrap();
+        let wednesday = NaiveDate::from_ymd_opt(2025, 1, 29).unwrap();
+        let thursday = NaiveDate::from_ymd_opt(2025, 1, 30).unwrap();
+        let at = |date: NaiveDate, hour: i64, minute: i64| {
+            local_midnight_to_utc(date)
+                + chrono::Duration::hours(hour)
+                + chrono::Duration::minutes(minute)
+        };
+        for id in ["stream-a", "stream-b"] {
+            db.insert_stream(&tt_db::Stream {
+                id: id.to_string(),
+                name: Some(id.to_string()),
+                created_at: at(wednesday, 0, 0),
+                updated_at: at(wednesday, 0, 0),
+                time_direct_ms: 0,
+                time_delegated_ms: 0,
+                first_event_at: None,
+                last_event_at: None,
+                needs_recompute: false,
+            })
+            .unwrap();
+        }
+        db.add_tag("stream-a", "acme").unwrap();
+        let focus = |id: &str, timestamp, stream_id| {
+            make_agent_event(
+                id,
+                timestamp,
+                tt_core::EventType::TmuxPaneFocus,
+                "session",
+                stream_id,
+                None,
+            )
+        };
+        db.insert_events(&[
+            focus("wed-1", at(wednesday, 10, 0), "stream-a"),
+            focus("wed-2", at(wednesday, 10, 3), "stream-a"),
+            focus("thu-1", at(thursday, 14, 0), "stream-b"),
+        ])
+        .unwrap();
+
+        let generated_at = at(thursday, 23, 0);
+        let week = generate_report_data_for_date(
+            &db,
+            Period::Week,
+            generated_at,
+            thursday,
+            "Etc/UTC".to_string(),
+            &AllocationConfig::default(),
+        )
+        .unwrap();
+        let days = generate_daily_report_data(&db, &week, &AllocationConfig::default()).unwrap();
+
+        // Seven days exactly covering the week.
+        assert_eq!(days.len(), 7);
+        assert_eq!(days[0].period_start, week.period_start);
+        assert_eq!(days[6].period_end, week.period_end);
 
-        // Then: the end-boundary event is excluded from the earlier period.
-        let ids = events
-            .iter()
-            .map(|event| event.id.as_str())
-            .collect::<Vec<_>>();
-        assert_eq!(ids, vec!["inside"]);
-        let next_period_ids = get_report_period_events(&db, end, end + chrono::Duration::days(7))
-            .unwrap()
-            .iter()
-            .map(|event| event.id.clone())
-            .collect::<Vec<_>>();
-        assert_eq!(next_period_ids, vec!["boundary"]);
+        let week_stream_ms = |id: &str| {
+            week.streams
+                .iter()
+                .find(|s| s.id == id)
+                .map_or(0, |s| s.time_direct_ms + s.time_delegated_ms)
+        };
+        let wed = &days[2];
+        let thu = &days[3];
+        assert!(report_total_ms(wed) > 0);
+        assert_eq!(report_total_ms(wed), week_stream_ms("stream-a"));
+        assert_eq!(report_total_ms(thu), week_stream_ms("stream-b"));
+        let daily_sum: i64 = days.iter().map(report_total_ms).sum();
+        assert_eq!(daily_sum, report_total_ms(&week));
+
+        let output = format_daily_breakdown(&days);
+        let acme_row = output.lines().find(|l| l.starts_with("acme")).unwrap();
+        assert!(acme_row.contains(&format_duration(week_stream_ms("stream-a"))));
+        assert!(output.lines().any(|l| l.starts_with("(untagged)")));
     }
 
     fn build_weeks_json(reference_dates: &[NaiveDate]) -> String {
@@ -1255,6 +3414,7 @@ mod tests {
                     generated_at,
                     *date,
                     "Etc/UTC".to_string(),
+                    &AllocationConfig::default(),
                 )
                 .unwrap()
             })
@@ -1300,7 +3460,8 @@ mod tests {
               "untagged": {
                 "time_direct_ms": 0,
                 "time_delegated_ms": 0,
-                "streams": []
+                "streams": [],
+                "percent": 0.0
               },
               "agent_sessions": {
                 "total": 0,
@@ -1313,7 +3474,8 @@ mod tests {
                 "time_delegated_ms": 0,
                 "stream_count": 0,
                 "unassigned_direct_ms": 0,
-                "unassigned_delegated_ms": 0
+                "unassigned_delegated_ms": 0,
+                "total_tracked_ms": 0
               }
             },
             {
@@ -1329,7 +3491,8 @@ mod tests {
               "untagged": {
                 "time_direct_ms": 0,
                 "time_delegated_ms": 0,
-                "streams": []
+                "streams": [],
+                "percent": 0.0
               },
               "agent_sessions": {
                 "total": 0,
@@ -1342,7 +3505,8 @@ mod tests {
                 "time_delegated_ms": 0,
                 "stream_count": 0,
                 "unassigned_direct_ms": 0,
-                "unassigned_delegated_ms": 0
+                "unassigned_delegated_ms": 0,
+                "total_tracked_ms": 0
               }
             }
           ]
@@ -1388,7 +3552,8 @@ mod tests {
               "untagged": {
                 "time_direct_ms": 0,
                 "time_delegated_ms": 0,
-                "streams": []
+                "streams": [],
+                "percent": 0.0
               },
               "agent_sessions": {
                 "total": 0,
@@ -1401,7 +3566,8 @@ mod tests {
                 "time_delegated_ms": 0,
                 "stream_count": 0,
                 "unassigned_direct_ms": 0,
-                "unassigned_delegated_ms": 0
+                "unassigned_delegated_ms": 0,
+                "total_tracked_ms": 0
               }
             },
             {
@@ -1417,7 +3583,8 @@ mod tests {
               "untagged": {
                 "time_direct_ms": 0,
                 "time_delegated_ms": 0,
-                "streams": []
+                "streams": [],
+                "percent": 0.0
               },
               "agent_sessions": {
                 "total": 0,
@@ -1430,7 +3597,8 @@ mod tests {
                 "time_delegated_ms": 0,
                 "stream_count": 0,
                 "unassigned_direct_ms": 0,
-                "unassigned_delegated_ms": 0
+                "unassigned_delegated_ms": 0,
+                "total_tracked_ms": 0
               }
             },
             {
@@ -1446,7 +3614,8 @@ mod tests {
               "untagged": {
                 "time_direct_ms": 0,
                 "time_delegated_ms": 0,
-                "streams": []
+                "streams": [],
+                "percent": 0.0
               },
               "agent_sessions": {
                 "total": 0,
@@ -1459,7 +3628,8 @@ mod tests {
                 "time_delegated_ms": 0,
                 "stream_count": 0,
                 "unassigned_direct_ms": 0,
-                "unassigned_delegated_ms": 0
+                "unassigned_delegated_ms": 0,
+                "total_tracked_ms": 0
               }
             }
           ]
@@ -1480,6 +3650,8 @@ mod tests {
             agent_sessions: vec![],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report(&data);
@@ -1502,6 +3674,8 @@ mod tests {
             agent_sessions: vec![],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report(&data);
@@ -1526,6 +3700,8 @@ mod tests {
             agent_sessions: vec![],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report_json(&data).unwrap();
@@ -1552,6 +3728,8 @@ mod tests {
             agent_sessions: vec![],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report_json(&data).unwrap();
@@ -1582,6 +3760,8 @@ mod tests {
             agent_sessions: vec![],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report_json(&data).unwrap();
@@ -1607,6 +3787,8 @@ mod tests {
             agent_sessions: vec![],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report_json(&data).unwrap();
@@ -1650,6 +3832,8 @@ mod tests {
             ],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report_json(&data).unwrap();
@@ -1725,6 +3909,8 @@ mod tests {
             ],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report_json(&data).unwrap();
@@ -1775,6 +3961,8 @@ mod tests {
             ],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report_json(&data).unwrap();
@@ -1815,12 +4003,151 @@ mod tests {
             agent_sessions: vec![],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report(&data);
         assert_snapshot!(output);
     }
 
+    #[test]
+    fn test_report_color_never_has_no_ansi_codes() {
+        let mut tags_by_stream = HashMap::new();
+        tags_by_stream.insert("abc123def456".to_string(), vec!["acme".to_string()]);
+        let data = ReportData {
+            generated_at: Utc.with_ymd_and_hms(2025, 1, 29, 16, 0, 0).unwrap(),
+            period_start: Utc.with_ymd_and_hms(2025, 1, 29, 8, 0, 0).unwrap(),
+            period_end: Utc.with_ymd_and_hms(2025, 1, 30, 8, 0, 0).unwrap(),
+            period_type: PeriodType::Day,
+            timezone: "Etc/UTC".to_string(),
+            streams: vec![make_test_stream(
+                "abc123def456",
+                "tmux/dev/session-1",
+                3_600_000,
+                4_500_000,
+            )],
+            tags_by_stream,
+            agent_sessions: vec![],
+            unassigned_direct_ms: 0,
+            unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
+        };
+
+        let plain = format_report_styled(&data, ColorChoice::Never.enabled());
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(plain, format_report(&data));
+
+        let colored = format_report_styled(&data, ColorChoice::Always.enabled());
+        assert!(colored.contains("\x1b[1;35macme"));
+        assert!(colored.contains("Direct time:    \x1b[32m1h 0m\x1b[0m"));
+        assert!(colored.contains("Delegated time: \x1b[36m1h 15m\x1b[0m"));
+    }
+
+    #[test]
+    fn test_report_fits_long_names_to_width_with_ellipsis() {
+        let long_name = "tmux/dev/an-extremely-long-session-name-that-wraps";
+        let long_tag = "client:a-very-long-client-name-with-department";
+        let mut tags_by_stream = HashMap::new();
+        tags_by_stream.insert("abc123def456".to_string(), vec![long_tag.to_string()]);
+        let data = ReportData {
+            generated_at: Utc.with_ymd_and_hms(2025, 1, 29, 16, 0, 0).unwrap(),
+            period_start: Utc.with_ymd_and_hms(2025, 1, 29, 8, 0, 0).unwrap(),
+            period_end: Utc.with_ymd_and_hms(2025, 1, 30, 8, 0, 0).unwrap(),
+            period_type: PeriodType::Day,
+            timezone: "Etc/UTC".to_string(),
+            streams: vec![make_test_stream("abc123def456", long_name, 3_600_000, 0)],
+            tags_by_stream,
+            agent_sessions: vec![],
+            unassigned_direct_ms: 0,
+            unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
+        };
+
+        let layout = TableLayout {
+            width: Some(40),
+            ..TableLayout::default()
+        };
+        let fitted = format_report_fitted(&data, false, layout);
+        let session_line = fitted.lines().find(|l| l.contains("abc123")).unwrap();
+        assert_eq!(session_line, "    abc123  tmux/dev/an-extre… (1h 0m)");
+        assert!(session_line.chars().count() <= 40);
+        let tag_line = fitted.lines().find(|l| l.contains("client:")).unwrap();
+        assert!(tag_line.starts_with("client:a-very-… "));
+        assert!(tag_line.chars().count() <= 40);
+
+        let whole = format_report_fitted(&data, false, TableLayout::default());
+        assert!(whole.contains(long_name));
+        assert!(whole.contains(long_tag));
+        assert_eq!(whole, format_report(&data));
+    }
+
+    #[test]
+    fn test_report_top_rolls_the_rest_into_an_other_row() {
+        // Streams s1..s5 with 5h..1h, each tagged with its own tag
+        let hour = 3_600_000;
+        let streams: Vec<ReportStreamTime> = (1..=5)
+            .map(|i| {
+                make_test_stream(
+                    &format!("s{i}-stream"),
+                    &format!("stream-{i}"),
+                    (6 - i) * hour,
+                    0,
+                )
+            })
+            .collect();
+        let tags_by_stream = streams
+            .iter()
+            .map(|s| (s.id.clone(), vec![format!("tag-{}", s.id)]))
+            .collect();
+        let data = ReportData {
+            generated_at: Utc.with_ymd_and_hms(2025, 1, 29, 16, 0, 0).unwrap(),
+            period_start: Utc.with_ymd_and_hms(2025, 1, 27, 8, 0, 0).unwrap(),
+            period_end: Utc.with_ymd_and_hms(2025, 2, 3, 8, 0, 0).unwrap(),
+            period_type: PeriodType::Week,
+            timezone: "Etc/UTC".to_string(),
+            streams,
+            tags_by_stream,
+            agent_sessions: vec![],
+            unassigned_direct_ms: 0,
+            unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
+        };
+        let layout = TableLayout {
+            top: Some(2),
+            ..TableLayout::default()
+        };
+
+        let output = format_report_fitted(&data, false, layout);
+
+        let tag_rows: Vec<&str> = output
+            .lines()
+            .skip_while(|l| *l != "──────")
+            .skip(1)
+            .take_while(|l| !l.is_empty())
+            .collect();
+        assert_eq!(tag_rows.len(), 3, "{output}");
+        assert!(tag_rows[0].starts_with("tag-s1-stream"));
+        assert!(tag_rows[1].starts_with("tag-s2-stream"));
+        // 3h + 2h + 1h omitted
+        assert!(tag_rows[2].starts_with("other (3 entries)"));
+        assert!(tag_rows[2].contains(&format_duration(6 * hour)));
+
+        let session_rows: Vec<&str> = output
+            .lines()
+            .skip_while(|l| *l != "  Sessions:")
+            .skip(1)
+            .take_while(|l| !l.is_empty())
+            .collect();
+        assert_eq!(session_rows.len(), 3, "{output}");
+        assert!(session_rows[2].contains("other (3 entries)"));
+        assert!(session_rows[2].ends_with(&format!("({})", format_duration(6 * hour))));
+        assert!(output.contains("Total tracked:  15h 0m"));
+    }
+
     #[test]
     fn test_report_truncation() {
         // Create 8 streams to test truncation (>5)
@@ -1846,6 +4173,8 @@ mod tests {
             agent_sessions: vec![],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report(&data);
@@ -1856,7 +4185,7 @@ BY TAG
 ──────
 (no tagged streams)
 
-(untagged)                                 8h 53m  ██████████
+(untagged)                                 8h 53m 100%  ██████████
   Direct:    5h 40m
   Delegated: 3h 13m
   Sessions:
@@ -1881,6 +4210,195 @@ Delegated time: 3h 13m (36%)
 "###);
     }
 
+    #[test]
+    fn test_report_jsonl_one_object_per_entry_plus_totals() {
+        let data = ReportData {
+            generated_at: Utc.with_ymd_and_hms(2025, 1, 29, 16, 0, 0).unwrap(),
+            period_start: Utc.with_ymd_and_hms(2025, 1, 27, 8, 0, 0).unwrap(),
+            period_end: Utc.with_ymd_and_hms(2025, 2, 3, 8, 0, 0).unwrap(),
+            period_type: PeriodType::Week,
+            timezone: "Etc/UTC".to_string(),
+            streams: vec![
+                make_test_stream("stream-a", "a", 1_000_000, 0),
+                make_test_stream("stream-b", "b", 600_000, 400_000),
+                make_test_stream("stream-c", "c", 0, 1_000_000),
+            ],
+            tags_by_stream: HashMap::from([
+                ("stream-a".to_string(), vec!["acme".to_string()]),
+                ("stream-b".to_string(), vec!["internal".to_string()]),
+            ]),
+            agent_sessions: vec![],
+            unassigned_direct_ms: 0,
+            unassigned_delegated_ms: 0,
+            total_tracked_ms: 2_500_000,
+            activity: Vec::new(),
+        };
+
+        let output = format_report_jsonl(&data).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        // Two tags plus the untagged entry, then totals
+        assert_eq!(lines.len(), build_json_report(&data).by_tag.len() + 1 + 1);
+        assert_eq!(lines[0]["kind"], "tag");
+        assert_eq!(lines[2]["kind"], "untagged");
+        assert_eq!(lines[3]["kind"], "totals");
+        assert_eq!(lines[3]["total_tracked_ms"], 2_500_000);
+        assert_eq!(lines[3]["period"]["start"], "2025-01-27");
+    }
+
+    #[test]
+    fn test_agent_leverage_ratios_per_tag_and_overall() {
+        let data = ReportData {
+            generated_at: Utc.with_ymd_and_hms(2025, 1, 29, 16, 0, 0).unwrap(),
+            period_start: Utc.with_ymd_and_hms(2025, 1, 27, 8, 0, 0).unwrap(),
+            period_end: Utc.with_ymd_and_hms(2025, 2, 3, 8, 0, 0).unwrap(),
+            period_type: PeriodType::Week,
+            timezone: "Etc/UTC".to_string(),
+            streams: vec![
+                make_test_stream("stream-a", "a", 3_600_000, 9_000_000),
+                make_test_stream("stream-b", "b", 4_000_000, 1_000_000),
+                make_test_stream("stream-c", "c", 0, 600_000),
+                make_test_stream("stream-d", "d", 400_000, 0),
+            ],
+            tags_by_stream: HashMap::from([
+                ("stream-a".to_string(), vec!["acme".to_string()]),
+                ("stream-b".to_string(), vec!["internal".to_string()]),
+                ("stream-c".to_string(), vec!["overnight".to_string()]),
+            ]),
+            agent_sessions: vec![],
+            unassigned_direct_ms: 0,
+            unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
+        };
+
+        let leverage = build_agent_leverage(&data);
+
+        let rows: Vec<(&str, Option<f64>)> = leverage
+            .by_tag
+            .iter()
+            .map(|entry| (entry.tag.as_str(), entry.leverage))
+            .collect();
+        // No direct time ranks as infinite; 9.0M / 3.6M = 2.5; 1M / 4M = 0.25
+        assert_eq!(
+            rows,
+            vec![
+                ("overnight", None),
+                ("acme", Some(2.5)),
+                ("internal", Some(0.25)),
+            ]
+        );
+        // 10.6M delegated / 8M direct, untagged stream-d included
+        assert_eq!(leverage.overall, Some(1.33));
+
+        let output = format_agent_leverage(&leverage, &data);
+        assert!(output.contains("overnight"));
+        assert!(output.contains("∞"));
+        assert!(output.contains("2.50x"));
+        assert!(output.contains("1.33x"));
+    }
+
+    #[test]
+    fn test_budget_reports_overage_for_tag_past_its_budget() {
+        let hour = 3_600_000;
+        let data = ReportData {
+            generated_at: Utc.with_ymd_and_hms(2025, 1, 29, 16, 0, 0).unwrap(),
+            period_start: Utc.with_ymd_and_hms(2025, 1, 27, 8, 0, 0).unwrap(),
+            period_end: Utc.with_ymd_and_hms(2025, 2, 3, 8, 0, 0).unwrap(),
+            period_type: PeriodType::Week,
+            timezone: "Etc/UTC".to_string(),
+            streams: vec![
+                make_test_stream("stream-a", "webapp", 8 * hour, 4 * hour),
+                make_test_stream("stream-b", "docs", 3 * hour, 0),
+            ],
+            tags_by_stream: HashMap::from([
+                ("stream-a".to_string(), vec!["acme-webapp".to_string()]),
+                ("stream-b".to_string(), vec!["internal".to_string()]),
+            ]),
+            agent_sessions: vec![],
+            unassigned_direct_ms: 0,
+            unassigned_delegated_ms: 0,
+            total_tracked_ms: 15 * hour,
+            activity: Vec::new(),
+        };
+        let budgets: Vec<Budget> = ["acme-webapp=10", "internal=4.5"]
+            .iter()
+            .map(|arg| arg.parse().unwrap())
+            .collect();
+
+        let entries = build_budget_entries(&data, &budgets);
+
+        assert_eq!(
+            entries,
+            vec![
+                JsonBudgetEntry {
+                    tag: "acme-webapp".to_string(),
+                    time_ms: 12 * hour,
+                    budget_ms: 10 * hour,
+                    remaining_ms: 0,
+                    overage_ms: 2 * hour,
+                    over_budget: true,
+                },
+                JsonBudgetEntry {
+                    tag: "internal".to_string(),
+                    time_ms: 3 * hour,
+                    budget_ms: 4 * hour + hour / 2,
+                    remaining_ms: hour + hour / 2,
+                    overage_ms: 0,
+                    over_budget: false,
+                },
+            ]
+        );
+        let output = format_budgets(&entries, false);
+        assert!(
+            output.contains("12h 0m / 10h 0m  2h 0m over  OVER"),
+            "{output}"
+        );
+        assert!(output.contains("3h 0m / 4h 30m  1h 30m left"), "{output}");
+        assert!("acme-webapp".parse::<Budget>().is_err());
+        assert!("acme-webapp=-1".parse::<Budget>().is_err());
+    }
+
+    #[test]
+    fn test_tag_percentages_sum_to_about_100() {
+        let data = ReportData {
+            generated_at: Utc.with_ymd_and_hms(2025, 1, 29, 16, 0, 0).unwrap(),
+            period_start: Utc.with_ymd_and_hms(2025, 1, 27, 8, 0, 0).unwrap(),
+            period_end: Utc.with_ymd_and_hms(2025, 2, 3, 8, 0, 0).unwrap(),
+            period_type: PeriodType::Week,
+            timezone: "Etc/UTC".to_string(),
+            streams: vec![
+                make_test_stream("stream-a", "a", 1_000_000, 0),
+                make_test_stream("stream-b", "b", 600_000, 400_000),
+                make_test_stream("stream-c", "c", 0, 1_000_000),
+            ],
+            tags_by_stream: HashMap::from([
+                ("stream-a".to_string(), vec!["acme".to_string()]),
+                ("stream-b".to_string(), vec!["internal".to_string()]),
+            ]),
+            agent_sessions: vec![],
+            unassigned_direct_ms: 0,
+            unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
+        };
+
+        let json = build_json_report(&data);
+        let percents: Vec<f64> = json.by_tag.iter().map(|entry| entry.percent).collect();
+        assert_eq!(percents, vec![33.3, 33.3]);
+        assert!((json.untagged.percent - 33.3).abs() < f64::EPSILON);
+        let sum: f64 = percents.iter().sum::<f64>() + json.untagged.percent;
+        assert!((sum - 100.0).abs() <= 0.2, "sum was {sum}");
+
+        let output = format_report(&data);
+        let acme_row = output.lines().find(|l| l.starts_with("acme")).unwrap();
+        assert!(acme_row.contains("16m  33%  "), "{acme_row}");
+        assert!(percent_of(500, 0).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_percentage_shown_at_30min() {
         // 30 minutes total - percentages should be shown
@@ -1900,6 +4418,8 @@ Delegated time: 3h 13m (36%)
             agent_sessions: vec![],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report(&data);
@@ -1925,6 +4445,8 @@ Delegated time: 3h 13m (36%)
             agent_sessions: vec![],
             unassigned_direct_ms: 0,
             unassigned_delegated_ms: 0,
+            total_tracked_ms: 0,
+            activity: Vec::new(),
         };
 
         let output = format_report(&data);
@@ -1956,7 +4478,8 @@ Delegated time: 3h 13m (36%)
         db.insert_stream(&zero_stream).unwrap();
 
         // Generate report - with no events, the allocation returns no time
-        let data = generate_report_data(&db, Period::Week, now).unwrap();
+        let data =
+            generate_report_data(&db, Period::Week, now, &AllocationConfig::default()).unwrap();
 
         // Zero-time stream should be excluded (no events = no time allocated)
         assert!(
@@ -1965,6 +4488,263 @@ Delegated time: 3h 13m (36%)
         );
     }
 
+    #[test]
+    fn test_session_tree_nests_subagents() {
+        let db = Database::open_in_memory().unwrap();
+        let base = Utc.with_ymd_and_hms(2025, 1, 29, 10, 0, 0).unwrap();
+        let period_start = Utc.with_ymd_and_hms(2025, 1, 29, 0, 0, 0).unwrap();
+        let period_end = Utc.with_ymd_and_hms(2025, 1, 30, 0, 0, 0).unwrap();
+
+        let mut parent = make_test_session(
+            "parent-session",
+            SessionSource::OpenCode,
+            SessionType::User,
+            base,
+            Some(base + chrono::Duration::hours(1)),
+            Some("build the feature"),
+        );
+        parent.tool_call_count = 4;
+        db.upsert_agent_session(&parent, None).unwrap();
+
+        for (id, offset_min, duration_min, tools) in [("sub-one", 5, 10, 3), ("sub-two", 20, 25, 7)]
+        {
+            let start = base + chrono::Duration::minutes(offset_min);
+            let mut sub = make_test_session(
+                id,
+                SessionSource::OpenCode,
+                SessionType::Subagent,
+                start,
+                Some(start + chrono::Duration::minutes(duration_min)),
+                None,
+            );
+            sub.parent_session_id = Some("parent-session".to_string());
+            sub.tool_call_count = tools;
+            db.upsert_agent_session(&sub, None).unwrap();
+        }
+
+        let sessions = db
+            .agent_sessions_in_range(period_start, period_end, None)
+            .unwrap();
+        let tree = build_session_tree(&sessions, period_start, period_end, &mut |id| {
+            Ok(db.get_subagents(id)?)
+        })
+        .unwrap();
+
+        assert_eq!(tree.len(), 1, "subagents should not appear at top level");
+        let root = &tree[0];
+        assert_eq!(root.session_id, "parent-session");
+        assert_eq!(root.delegated_ms, 60 * 60 * 1000);
+        assert_eq!(root.tool_call_count, 4);
+
+        let children: Vec<(&str, i32, i64)> = root
+            .children
+            .iter()
+            .map(|c| (c.session_id.as_str(), c.tool_call_count, c.delegated_ms))
+            .collect();
+        assert_eq!(
+            children,
+            vec![
+                ("sub-one", 3, 10 * 60 * 1000),
+                ("sub-two", 7, 25 * 60 * 1000)
+            ]
+        );
+
+        let output = format_session_tree(&tree);
+        assert!(output.contains("SESSION TREE"));
+        assert!(output.contains("└─ sub-on  opencode/subagent"));
+        assert!(output.contains("7 tools"));
+    }
+
+    #[test]
+    fn test_session_tree_shortens_non_ascii_ids_by_char() {
+        let node = |session_id: &str, children| SessionTreeNode {
+            session_id: session_id.to_string(),
+            source: "claude".to_string(),
+            session_type: "user".to_string(),
+            tool_call_count: 1,
+            delegated_ms: 60_000,
+            starting_prompt: String::new(),
+            children,
+        };
+        // Byte 6 falls inside the first 'é'
+        let tree = vec![node("sessié-parent", vec![node("ééééééé", vec![])])];
+
+        let output = format_session_tree(&tree);
+        assert!(output.contains("  sessié  claude/user"), "{output}");
+        assert!(output.contains("└─ éééééé  claude/user"), "{output}");
+    }
+
+    #[test]
+    fn test_consecutive_days_sum_to_two_day_total_across_midnight() {
+        let db = tt_db::Database::open_in_memory().unwrap();
+        let yesterday = NaiveDate::from_ymd_opt(2025, 1, 28).unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 1, 29).unwrap();
+        let tomorrow = NaiveDate::from_ymd_opt(2025, 1, 30).unwrap();
+        let midnight = local_midnight_to_utc(today);
+        let at = |minutes: i64| midnight + chrono::Duration::minutes(minutes);
+        db.insert_stream(&tt_db::Stream {
+            id: "late-night".to_string(),
+            name: Some("late-night".to_string()),
+            created_at: at(-60),
+            updated_at: at(-60),
+            time_direct_ms: 0,
+            time_delegated_ms: 0,
+            first_event_at: None,
+            last_event_at: None,
+            needs_recompute: false,
+        })
+        .unwrap();
+        let event = |id: &str, minutes, event_type, action| {
+            make_agent_event(id, at(minutes), event_type, "late", "late-night", action)
+        };
+        db.insert_events(&[
+            event(
+                "start",
+                -30,
+                tt_core::EventType::AgentSession,
+                Some("started"),
+            ),
+            event("tool-1", -20, tt_core::EventType::AgentToolUse, None),
+            event("focus", -3, tt_core::EventType::TmuxPaneFocus, None),
+            event("scroll", 1, tt_core::EventType::TmuxScroll, None),
+            event("tool-2", 5, tt_core::EventType::AgentToolUse, None),
+            event("end", 30, tt_core::EventType::AgentSession, Some("ended")),
+        ])
+        .unwrap();
+
+        let report = |start: NaiveDate, end: NaiveDate| {
+            generate_report_data_for_date(
+                &db,
+                Period::Custom(local_midnight_to_utc(start), local_midnight_to_utc(end)),
+                local_midnight_to_utc(tomorrow),
+                start,
+                "Etc/UTC".to_string(),
+                &AllocationConfig::default(),
+            )
+            .unwrap()
+        };
+        let stream_ms = |data: &ReportData| {
+            let stream = data.streams.iter().find(|s| s.id == "late-night");
+            stream.map_or((0, 0), |s| (s.time_direct_ms, s.time_delegated_ms))
+        };
+
+        let first_day = stream_ms(&report(yesterday, today));
+        let second_day = stream_ms(&report(today, tomorrow));
+        let both_days = stream_ms(&report(yesterday, tomorrow));
+
+        // Focus 23:57 → 00:01 → +5 min window; delegated 23:40 → 00:30.
+        assert_eq!(both_days, (9 * 60_000, 50 * 60_000));
+        // Each day counts only its own side of midnight.
+        assert_eq!(first_day, (3 * 60_000, 20 * 60_000));
+        assert_eq!(second_day, (6 * 60_000, 30 * 60_000));
+    }
+
+    /// Reports a `late-night` stream's `(direct, delegated)` time on 2025-01-29, from
+    /// events given as minutes relative to that day's local midnight.
+    fn stream_ms_after_midnight(
+        events: &[(&str, i64, tt_core::EventType, Option<&str>)],
+        config: &AllocationConfig,
+    ) -> (i64, i64) {
+        let db = tt_db::Database::open_in_memory().unwrap();
+        let today = NaiveDate::from_ymd_opt(2025, 1, 29).unwrap();
+        let tomorrow = NaiveDate::from_ymd_opt(2025, 1, 30).unwrap();
+        let midnight = local_midnight_to_utc(today);
+        let at = |minutes: i64| midnight + chrono::Duration::minutes(minutes);
+        db.insert_stream(&tt_db::Stream {
+            id: "late-night".to_string(),
+            name: Some("late-night".to_string()),
+            created_at: at(-60),
+            updated_at: at(-60),
+            time_direct_ms: 0,
+            time_delegated_ms: 0,
+            first_event_at: None,
+            last_event_at: None,
+            needs_recompute: false,
+        })
+        .unwrap();
+        let events: Vec<_> = events
+            .iter()
+            .map(|&(id, minutes, event_type, action)| {
+                make_agent_event(id, at(minutes), event_type, "late", "late-night", action)
+            })
+            .collect();
+        db.insert_events(&events).unwrap();
+
+        let data = generate_report_data_for_date(
+            &db,
+            Period::Custom(midnight, local_midnight_to_utc(tomorrow)),
+            local_midnight_to_utc(tomorrow),
+            today,
+            "Etc/UTC".to_string(),
+            config,
+        )
+        .unwrap();
+        data.streams
+            .iter()
+            .find(|s| s.id == "late-night")
+            .map_or((0, 0), |s| (s.time_direct_ms, s.time_delegated_ms))
+    }
+
+    #[test]
+    fn test_no_carry_open_session_across_midnight_counts_only_its_own_day() {
+        let config = AllocationConfig {
+            carry_open_sessions: false,
+            ..AllocationConfig::default()
+        };
+        // Still open at the period end: it stops at its last tool use, 00:10.
+        let ms = stream_ms_after_midnight(
+            &[
+                ("start", -15, EventType::AgentSession, Some("started")),
+                ("tool-1", -10, EventType::AgentToolUse, None),
+                ("tool-2", 10, EventType::AgentToolUse, None),
+            ],
+            &config,
+        );
+
+        assert_eq!(ms, (0, 10 * 60_000));
+    }
+
+    #[test]
+    fn test_min_session_span_met_only_across_midnight_counts_only_its_own_day() {
+        let config = AllocationConfig {
+            min_session_ms: 15 * 60_000,
+            ..AllocationConfig::default()
+        };
+        // Too short before midnight on its own, but its full 25-minute span counts.
+        let ms = stream_ms_after_midnight(
+            &[
+                ("start", -10, EventType::AgentSession, Some("started")),
+                ("tool-1", -5, EventType::AgentToolUse, None),
+                ("tool-2", 20, EventType::AgentToolUse, None),
+                ("end", 25, EventType::AgentSession, Some("ended")),
+            ],
+            &config,
+        );
+
+        assert_eq!(ms, (0, 25 * 60_000));
+    }
+
+    #[test]
+    fn test_focus_held_by_agent_activity_across_midnight_counts_only_its_own_day() {
+        let config = AllocationConfig {
+            agent_activity_implies_focus: true,
+            ..AllocationConfig::default()
+        };
+        // The tool uses hold focus over the whole 23:50 → 00:10 gap, then the
+        // attention window runs to 00:15.
+        let ms = stream_ms_after_midnight(
+            &[
+                ("start", -15, EventType::AgentSession, Some("started")),
+                ("tool-1", -10, EventType::AgentToolUse, None),
+                ("tool-2", 10, EventType::AgentToolUse, None),
+                ("end", 15, EventType::AgentSession, Some("ended")),
+            ],
+            &config,
+        );
+
+        assert_eq!(ms, (15 * 60_000, 15 * 60_000));
+    }
+
     #[test]
     #[expect(
         clippy::too_many_lines,
@@ -2050,7 +4830,12 @@ Delegated time: 3h 13m (36%)
             &HashMap::new(),
             &HashMap::new(),
         );
-        assert_eq!(without_seed.stream_times.len(), 0);
+        // The tool uses open the session on their own, without the start event
+        assert_eq!(without_seed.stream_times.len(), 1);
+        assert_eq!(
+            without_seed.stream_times[0].time_delegated_ms,
+            expected_delegated_ms
+        );
 
         let mut seeded_events = Vec::with_capacity(period_events.len() + 1);
         seeded_events.push(start_event);
@@ -2075,6 +4860,7 @@ Delegated time: 3h 13m (36%)
             period_end + chrono::Duration::hours(1),
             reference_date,
             "Etc/UTC".to_string(),
+            &AllocationConfig::default(),
         )
         .unwrap();
 
@@ -0,0 +1,128 @@
+//! Session command for correcting agent session assignments in bulk.
+
+use anyhow::{Context, Result, bail};
+use tt_db::Database;
+
+use crate::output::OutputCtx;
+
+/// Moves every event of an agent session to a stream as a user assignment.
+///
+/// Each stream the session was previously spread across, plus the target, is
+/// marked for recompute so their time totals catch up on the next `tt recompute`.
+pub fn assign(db: &Database, session_id: &str, stream: &str, ctx: &OutputCtx) -> Result<()> {
+    let Some(target) = db
+        .resolve_stream(stream)
+        .context("failed to query streams")?
+    else {
+        bail!(
+            "Stream '{stream}' not found.\n\nHint: Use 'tt streams' to see available stream IDs."
+        );
+    };
+
+    let previous = db
+        .stream_ids_for_session(session_id)
+        .context("failed to query session streams")?;
+    let count = db
+        .reassign_events_by_session(session_id, &target.id, "user")
+        .context("failed to reassign session events")?;
+    if count == 0 {
+        bail!("Session '{session_id}' has no events.");
+    }
+
+    let affected: Vec<&str> = std::iter::once(target.id.as_str())
+        .chain(
+            previous
+                .iter()
+                .map(String::as_str)
+                .filter(|id| *id != target.id),
+        )
+        .collect();
+    db.mark_streams_for_recompute(&affected)
+        .context("failed to mark streams for recompute")?;
+
+    let target_name = target.name.as_deref().unwrap_or("<unnamed>");
+    ctx.info(format_args!(
+        "Assigned {count} event(s) from session {session_id} to {} ({target_name})",
+        target.id
+    ));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tt_db::{StoredEvent, Stream};
+
+    fn make_stream(id: &str) -> Stream {
+        let now = Utc::now();
+        Stream {
+            id: id.to_string(),
+            name: Some(id.to_string()),
+            created_at: now,
+            updated_at: now,
+            time_direct_ms: 0,
+            time_delegated_ms: 0,
+            first_event_at: None,
+            last_event_at: None,
+            needs_recompute: false,
+        }
+    }
+
+    fn make_event(id: &str, session_id: &str, stream_id: &str) -> StoredEvent {
+        StoredEvent {
+            id: id.to_string(),
+            timestamp: Utc::now(),
+            event_type: tt_core::EventType::UserMessage,
+            source: "remote.agent".to_string(),
+            machine_id: None,
+            schema_version: 1,
+            pane_id: None,
+            tmux_session: None,
+            window_index: None,
+            git_project: None,
+            git_workspace: None,
+            status: None,
+            idle_duration_ms: None,
+            window_app_id: None,
+            window_title: None,
+            url: None,
+            action: None,
+            cwd: Some("/home/user/project".to_string()),
+            session_id: Some(session_id.to_string()),
+            stream_id: Some(stream_id.to_string()),
+            assignment_source: Some("inferred".to_string()),
+            data: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_assign_moves_session_and_flags_all_touched_streams() {
+        let db = Database::open_in_memory().unwrap();
+        for id in ["a", "b", "target", "other"] {
+            db.insert_stream(&make_stream(id)).unwrap();
+        }
+        db.insert_events(&[
+            make_event("e1", "sess-1", "a"),
+            make_event("e2", "sess-1", "b"),
+            make_event("e3", "sess-2", "other"),
+        ])
+        .unwrap();
+
+        assign(&db, "sess-1", "target", &OutputCtx::default()).unwrap();
+
+        assert_eq!(db.get_events_by_stream("target").unwrap().len(), 2);
+        let mut flagged: Vec<String> = db
+            .get_streams_needing_recompute()
+            .unwrap()
+            .into_iter()
+            .map(|stream| stream.id)
+            .collect();
+        flagged.sort();
+        assert_eq!(flagged, vec!["a", "b", "target"]);
+
+        let err = assign(&db, "missing", "target", &OutputCtx::default()).unwrap_err();
+        assert!(err.to_string().contains("Session 'missing' has no events"));
+    }
+}
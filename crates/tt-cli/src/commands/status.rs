@@ -10,10 +10,13 @@ use anyhow::Result;
 use chrono::SecondsFormat;
 use tt_db::Database;
 
+use crate::output::OutputCtx;
+
 /// Formats and prints the status output.
 ///
-/// Returns the formatted output string (for testing).
-pub fn format_status(db: &Database, db_path: &Path) -> Result<String> {
+/// Returns the formatted output string (for testing). The hint line is left
+/// out when `ctx.quiet`.
+pub fn format_status(db: &Database, db_path: &Path, ctx: &OutputCtx) -> Result<String> {
     let statuses = db.get_last_event_per_source()?;
 
     let mut output = String::new();
@@ -31,12 +34,29 @@ pub fn format_status(db: &Database, db_path: &Path) -> Result<String> {
         }
     }
 
+    let unknown_sessions = db.events_with_unknown_session()?;
+    if !unknown_sessions.is_empty() {
+        writeln!(
+            output,
+            "\nWarning: {} session(s) referenced by events have no session metadata:",
+            unknown_sessions.len()
+        )?;
+        for session_id in &unknown_sessions {
+            writeln!(output, "  {session_id}")?;
+        }
+        if !ctx.quiet {
+            output.push_str(
+                "Hint: Run 'tt sync' or 'tt ingest sessions' to import their metadata.\n",
+            );
+        }
+    }
+
     Ok(output)
 }
 
 /// Runs the status command.
-pub fn run(db: &Database, db_path: &Path) -> Result<()> {
-    let output = format_status(db, db_path)?;
+pub fn run(db: &Database, db_path: &Path, ctx: &OutputCtx) -> Result<()> {
+    let output = format_status(db, db_path, ctx)?;
     print!("{output}");
     Ok(())
 }
@@ -67,6 +87,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: None,
             cwd: None,
             session_id: None,
@@ -81,7 +102,7 @@ mod tests {
         let db = Database::open_in_memory().unwrap();
         let db_path = PathBuf::from("/path/to/events.db");
 
-        let output = format_status(&db, &db_path).unwrap();
+        let output = format_status(&db, &db_path, &OutputCtx::default()).unwrap();
 
         assert_snapshot!(output);
     }
@@ -100,7 +121,7 @@ mod tests {
         db.insert_event(&make_event("e2", ts_agent, "remote.agent"))
             .unwrap();
 
-        let output = format_status(&db, &db_path).unwrap();
+        let output = format_status(&db, &db_path, &OutputCtx::default()).unwrap();
 
         assert_snapshot!(output);
     }
@@ -122,7 +143,7 @@ mod tests {
         db.insert_event(&make_event("e3", ts_agent, "remote.agent"))
             .unwrap();
 
-        let output = format_status(&db, &db_path).unwrap();
+        let output = format_status(&db, &db_path, &OutputCtx::default()).unwrap();
 
         // Verify ordering in output - most recent first
         // Find the Sources: section and check order
@@ -147,4 +168,20 @@ mod tests {
             "third should be remote.tmux (10:00)"
         );
     }
+
+    #[test]
+    fn test_status_warns_about_events_without_session_metadata() {
+        let db = Database::open_in_memory().unwrap();
+        let db_path = PathBuf::from("/path/to/events.db");
+
+        let ts = Utc.with_ymd_and_hms(2025, 1, 29, 10, 0, 0).unwrap();
+        let mut event = make_event("e1", ts, "remote.agent");
+        event.session_id = Some("sess-missing".to_string());
+        db.insert_event(&event).unwrap();
+
+        let output = format_status(&db, &db_path, &OutputCtx::default()).unwrap();
+
+        assert!(output.contains("1 session(s) referenced by events have no session metadata"));
+        assert!(output.contains("  sess-missing\n"));
+    }
 }
@@ -1,19 +1,33 @@
 //! Streams command for listing streams with time totals and tags.
 //!
 //! This module implements `tt streams` which displays all streams
-//! from the last 7 days with their direct/delegated time and tags.
+//! from the last 7 days with their direct/delegated time and tags,
+//! and `tt streams show` which drills into a single stream.
 
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 
 use anyhow::Result;
 use chrono::{DateTime, Local, LocalResult, NaiveDate, NaiveTime, TimeZone, Utc};
 use serde::Serialize;
+use tt_core::AllocationConfig;
 use tt_db::Database;
 
-use super::report::format_duration;
+use super::report::{Period, format_duration, generate_report_data, get_period_boundaries};
+use crate::output::OutputCtx;
 
+mod categorize;
 mod link;
+mod retag;
+mod show;
+mod untag;
+pub use categorize::{CATEGORY_TAG_PREFIX, categorize, category_of};
 pub use link::{LinkOptions, link};
+pub use retag::retag;
+pub use show::{
+    CountEntry, StreamDetail, StreamSession, format_stream_detail, get_stream_detail, show,
+};
+pub use untag::untag;
 
 // ========== Period Calculation ==========
 
@@ -53,13 +67,61 @@ pub struct StreamEntry {
     pub tags: Vec<String>,
 }
 
-/// Get streams from the last 7 days, filtered and sorted.
+/// Order for `tt streams list --sort`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum StreamSort {
+    /// Most total (direct + delegated) time first.
+    #[default]
+    Time,
+    /// Most recent activity first.
+    Recent,
+    /// Alphabetical by name, unnamed streams last.
+    Name,
+    /// Most recently created first.
+    Created,
+}
+
+/// Sorts streams in place by `sort`, flipping the order when `reverse`.
+///
+/// Ties keep their incoming order.
+pub fn sort_streams(streams: &mut [(tt_db::Stream, Vec<String>)], sort: StreamSort, reverse: bool) {
+    match sort {
+        StreamSort::Time => {
+            streams.sort_by_cached_key(|(stream, _)| std::cmp::Reverse(stream.total_ms()));
+        }
+        StreamSort::Recent => {
+            streams.sort_by_key(|(stream, _)| std::cmp::Reverse(stream.last_event_at));
+        }
+        StreamSort::Name => streams.sort_by(|(a, _), (b, _)| {
+            (a.name.is_none(), &a.name).cmp(&(b.name.is_none(), &b.name))
+        }),
+        StreamSort::Created => {
+            streams.sort_by_key(|(stream, _)| std::cmp::Reverse(stream.created_at));
+        }
+    }
+    if reverse {
+        streams.reverse();
+    }
+}
+
+/// Get streams from the last 7 days, filtered and sorted by total time.
 pub fn get_streams_for_display(db: &Database, today: NaiveDate) -> Result<Vec<StreamEntry>> {
+    get_sorted_streams_for_display(db, today, StreamSort::Time, false)
+}
+
+/// Get streams from the last 7 days, filtered and sorted by `sort`.
+pub fn get_sorted_streams_for_display(
+    db: &Database,
+    today: NaiveDate,
+    sort: StreamSort,
+    reverse: bool,
+) -> Result<Vec<StreamEntry>> {
     let period_start = last_7_days_boundary(today);
 
-    let streams_with_tags = db.get_streams_with_tags()?;
+    let mut streams_with_tags = db.get_streams_with_tags()?;
+    sort_streams(&mut streams_with_tags, sort, reverse);
 
-    let mut entries: Vec<StreamEntry> = streams_with_tags
+    let entries: Vec<StreamEntry> = streams_with_tags
         .into_iter()
         .filter(|(stream, _)| {
             // Filter by period: last_event_at must be within last 7 days
@@ -67,38 +129,131 @@ pub fn get_streams_for_display(db: &Database, today: NaiveDate) -> Result<Vec<St
         })
         .filter(|(stream, _)| {
             // Exclude zero-time streams
-            stream.time_direct_ms > 0 || stream.time_delegated_ms > 0
-        })
-        .map(|(stream, tags)| {
-            let id_short: String = stream.id.chars().take(6).collect();
-            StreamEntry {
-                id: stream.id,
-                id_short,
-                name: stream.name,
-                time_direct_ms: stream.time_direct_ms,
-                time_delegated_ms: stream.time_delegated_ms,
-                tags,
-            }
+            stream.total_ms() > 0
         })
+        .map(|(stream, tags)| to_entry(stream, tags))
         .collect();
 
-    // Sort by total time descending
-    entries.sort_by_key(|e| std::cmp::Reverse(e.time_direct_ms + e.time_delegated_ms));
-
     Ok(entries)
 }
 
+/// Get streams with events in `[start, end)`, sorted by `sort`.
+///
+/// Times are each stream's share of a fresh allocation over the range, not
+/// lifetime totals. Streams with events in the range are listed even when
+/// none of their time falls inside it.
+pub fn get_streams_in_period(
+    db: &Database,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    generated_at: DateTime<Utc>,
+    sort: StreamSort,
+    reverse: bool,
+) -> Result<Vec<StreamEntry>> {
+    let data = generate_report_data(
+        db,
+        Period::Custom(start, end),
+        generated_at,
+        &AllocationConfig::default(),
+    )?;
+    let period_times: HashMap<&str, (i64, i64)> = data
+        .streams
+        .iter()
+        .map(|s| (s.id.as_str(), (s.time_direct_ms, s.time_delegated_ms)))
+        .collect();
+    let active: HashSet<String> = db
+        .get_events_in_range(start, end)?
+        .into_iter()
+        .filter(|event| event.timestamp < end)
+        .filter_map(|event| event.stream_id)
+        .collect();
+
+    let mut streams_with_tags = db
+        .streams_in_range(start, end)?
+        .into_iter()
+        .filter(|stream| active.contains(&stream.id))
+        .map(|mut stream| {
+            let (direct_ms, delegated_ms) = period_times
+                .get(stream.id.as_str())
+                .copied()
+                .unwrap_or_default();
+            stream.time_direct_ms = direct_ms;
+            stream.time_delegated_ms = delegated_ms;
+            let tags = db.get_tags(&stream.id)?;
+            Ok((stream, tags))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    sort_streams(&mut streams_with_tags, sort, reverse);
+
+    Ok(streams_with_tags
+        .into_iter()
+        .map(|(stream, tags)| to_entry(stream, tags))
+        .collect())
+}
+
+fn to_entry(stream: tt_db::Stream, tags: Vec<String>) -> StreamEntry {
+    let id_short: String = stream.id.chars().take(6).collect();
+    StreamEntry {
+        id: stream.id,
+        id_short,
+        name: stream.name,
+        time_direct_ms: stream.time_direct_ms,
+        time_delegated_ms: stream.time_delegated_ms,
+        tags,
+    }
+}
+
+/// One page of all streams, most recently updated first.
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamPage {
+    pub streams: Vec<StreamEntry>,
+    /// 1-based page number.
+    pub page: u32,
+    pub page_size: u32,
+    pub total_streams: usize,
+    pub total_pages: usize,
+}
+
+/// Gets page `page` (1-based) of all streams, regardless of activity period.
+pub fn get_stream_page(db: &Database, page: u32, page_size: u32) -> Result<StreamPage> {
+    let total_streams = db.count_streams()?;
+    let offset = page.saturating_sub(1).saturating_mul(page_size);
+    let streams = db
+        .get_streams_paginated(page_size, offset)?
+        .into_iter()
+        .map(|stream| {
+            let tags = db.get_tags(&stream.id)?;
+            Ok(to_entry(stream, tags))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(StreamPage {
+        streams,
+        page,
+        page_size,
+        total_streams,
+        total_pages: total_streams.div_ceil(page_size.max(1) as usize),
+    })
+}
+
 // ========== Human-Readable Output ==========
 
 /// Format streams for human-readable output.
 pub fn format_streams(entries: &[StreamEntry]) -> String {
+    format_period_streams(entries, "last 7 days", "in the last 7 days")
+}
+
+/// Format streams under a `STREAMS ({label})` header.
+///
+/// `empty_suffix` completes "No streams with activity ..." when `entries` is empty.
+pub fn format_period_streams(entries: &[StreamEntry], label: &str, empty_suffix: &str) -> String {
     let mut output = String::new();
 
-    writeln!(output, "STREAMS (last 7 days)").unwrap();
+    writeln!(output, "STREAMS ({label})").unwrap();
     writeln!(output).unwrap();
 
     if entries.is_empty() {
-        writeln!(output, "No streams with activity in the last 7 days.").unwrap();
+        writeln!(output, "No streams with activity {empty_suffix}.").unwrap();
         writeln!(output).unwrap();
         writeln!(
             output,
@@ -108,6 +263,42 @@ pub fn format_streams(entries: &[StreamEntry]) -> String {
         return output;
     }
 
+    write_stream_rows(&mut output, entries);
+
+    // Tip
+    writeln!(output).unwrap();
+    writeln!(
+        output,
+        "Tip: Use 'tt tag <id> <tag>' to group sessions into projects."
+    )
+    .unwrap();
+
+    output
+}
+
+/// Format one page of streams for human-readable output.
+pub fn format_stream_page(page: &StreamPage) -> String {
+    let mut output = String::new();
+
+    writeln!(
+        output,
+        "STREAMS (page {} of {}, {} total, most recently updated first)",
+        page.page, page.total_pages, page.total_streams
+    )
+    .unwrap();
+    writeln!(output).unwrap();
+
+    if page.streams.is_empty() {
+        writeln!(output, "No streams on this page.").unwrap();
+        return output;
+    }
+    write_stream_rows(&mut output, &page.streams);
+
+    output
+}
+
+/// Writes the table header and one row per stream.
+fn write_stream_rows(output: &mut String, entries: &[StreamEntry]) {
     // Header
     writeln!(
         output,
@@ -141,16 +332,6 @@ pub fn format_streams(entries: &[StreamEntry]) -> String {
         )
         .unwrap();
     }
-
-    // Tip
-    writeln!(output).unwrap();
-    writeln!(
-        output,
-        "Tip: Use 'tt tag <id> <tag>' to group sessions into projects."
-    )
-    .unwrap();
-
-    output
 }
 
 // ========== JSON Output ==========
@@ -170,13 +351,20 @@ pub struct JsonPeriod {
 
 /// Format streams as JSON.
 pub fn format_streams_json(entries: &[StreamEntry], today: NaiveDate) -> Result<String> {
-    let start_date = today - chrono::Duration::days(6);
+    format_period_streams_json(entries, today - chrono::Duration::days(6), today)
+}
 
+/// Format streams for the local dates `start..=end` as JSON.
+pub fn format_period_streams_json(
+    entries: &[StreamEntry],
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<String> {
     let json_streams = JsonStreams {
         streams: entries.to_vec(),
         period: JsonPeriod {
-            start: start_date.format("%Y-%m-%d").to_string(),
-            end: today.format("%Y-%m-%d").to_string(),
+            start: start.format("%Y-%m-%d").to_string(),
+            end: end.format("%Y-%m-%d").to_string(),
         },
     };
 
@@ -186,11 +374,65 @@ pub fn format_streams_json(entries: &[StreamEntry], today: NaiveDate) -> Result<
 // ========== Public Interface ==========
 
 /// Runs the streams command.
-pub fn run(db: &Database, json: bool) -> Result<()> {
+///
+/// With `page`, lists all streams one page at a time instead of the last 7 days.
+/// With `period`, lists streams active in that period with their time in it.
+/// Otherwise streams are ordered by `sort`, reversed when `reverse`.
+/// Prints JSON when `ctx.json`.
+pub fn run(
+    db: &Database,
+    page: Option<u32>,
+    page_size: u32,
+    period: Option<Period>,
+    sort: StreamSort,
+    reverse: bool,
+    ctx: &OutputCtx,
+) -> Result<()> {
+    if let Some(page) = page {
+        let page = get_stream_page(db, page, page_size)?;
+        if ctx.json {
+            println!("{}", serde_json::to_string_pretty(&page)?);
+        } else {
+            print!("{}", format_stream_page(&page));
+        }
+        return Ok(());
+    }
+
+    if let Some(period) = period {
+        let now = Utc::now();
+        let (start, end) = get_period_boundaries(period, now.with_timezone(&Local).date_naive());
+        let entries = get_streams_in_period(db, start, end, now, sort, reverse)?;
+        // The period end is exclusive, so the last day shown is the one before it.
+        let first_day = start.with_timezone(&Local).date_naive();
+        let last_day = (end - chrono::Duration::milliseconds(1))
+            .with_timezone(&Local)
+            .date_naive();
+
+        if ctx.json {
+            println!(
+                "{}",
+                format_period_streams_json(&entries, first_day, last_day)?
+            );
+        } else if first_day == last_day {
+            let label = first_day.format("%Y-%m-%d").to_string();
+            print!(
+                "{}",
+                format_period_streams(&entries, &label, &format!("on {label}"))
+            );
+        } else {
+            let label = format!("{first_day} to {last_day}");
+            print!(
+                "{}",
+                format_period_streams(&entries, &label, &format!("from {label}"))
+            );
+        }
+        return Ok(());
+    }
+
     let today = Local::now().date_naive();
-    let entries = get_streams_for_display(db, today)?;
+    let entries = get_sorted_streams_for_display(db, today, sort, reverse)?;
 
-    if json {
+    if ctx.json {
         let output = format_streams_json(&entries, today)?;
         println!("{output}");
     } else {
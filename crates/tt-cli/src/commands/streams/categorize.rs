@@ -0,0 +1,51 @@
+use anyhow::{Context, Result, bail};
+use tt_db::Database;
+
+use crate::output::OutputCtx;
+
+/// Reserved tag prefix marking a stream's work type (e.g. `type:coding`).
+///
+/// Categories are ordinary tags, so filtering and allocation treat them like
+/// any other; `tt report --by-category` breaks them out separately.
+pub const CATEGORY_TAG_PREFIX: &str = "type:";
+
+/// Returns the work type among a stream's tags, without the prefix.
+pub fn category_of(tags: &[String]) -> Option<&str> {
+    tags.iter()
+        .find_map(|tag| tag.strip_prefix(CATEGORY_TAG_PREFIX))
+}
+
+/// Sets a stream's work type, replacing any previous one.
+pub fn categorize(db: &Database, stream: &str, category: &str, ctx: &OutputCtx) -> Result<()> {
+    let category = category.trim();
+    let category = category
+        .strip_prefix(CATEGORY_TAG_PREFIX)
+        .unwrap_or(category);
+    if category.is_empty() {
+        bail!("category must not be empty");
+    }
+
+    let Some(resolved) = db
+        .resolve_stream(stream)
+        .context("failed to query streams")?
+    else {
+        bail!(
+            "Stream '{stream}' not found.\n\nHint: Use 'tt streams' to see available stream IDs."
+        );
+    };
+
+    let tags = db.get_tags(&resolved.id).context("failed to get tags")?;
+    for tag in tags.iter().filter(|t| t.starts_with(CATEGORY_TAG_PREFIX)) {
+        db.delete_tag(&resolved.id, tag)
+            .context("failed to remove previous category")?;
+    }
+    db.add_tag(&resolved.id, &format!("{CATEGORY_TAG_PREFIX}{category}"))
+        .context("failed to add category")?;
+
+    let stream_name = resolved.name.as_deref().unwrap_or("<unnamed>");
+    ctx.info(format_args!(
+        "Categorized stream {} ({stream_name}) as \"{category}\"",
+        resolved.id
+    ));
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anyhow::{Context, Result, bail};
+use tt_db::Database;
+
+use crate::output::OutputCtx;
+
+/// Replaces tag `old` with `new` on one stream, without touching other streams.
+pub fn retag(db: &Database, stream: &str, old: &str, new: &str, ctx: &OutputCtx) -> Result<()> {
+    let Some(resolved) = db
+        .resolve_stream(stream)
+        .context("failed to query streams")?
+    else {
+        bail!(
+            "Stream '{stream}' not found.\n\nHint: Use 'tt streams' to see available stream IDs."
+        );
+    };
+
+    let new = new.trim();
+    if new.is_empty() {
+        bail!("tag cannot be empty");
+    }
+    let renamed = db
+        .rename_tag_for_stream(&resolved.id, old, new)
+        .context("failed to rename tag")?;
+    if !renamed {
+        bail!("Stream {} has no tag '{old}'", resolved.id);
+    }
+
+    let stream_name = resolved.name.as_deref().unwrap_or("<unnamed>");
+    ctx.info(format_args!(
+        "Retagged stream {} ({stream_name}): {old} -> {new}",
+        resolved.id
+    ));
+    Ok(())
+}
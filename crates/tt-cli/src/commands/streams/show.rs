@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tt_db::Database;
+
+use crate::commands::report::format_duration;
+
+/// Number of entries shown in the top directories/projects lists.
+const TOP_LIMIT: usize = 5;
+
+/// Detailed view of a single stream.
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamDetail {
+    pub id: String,
+    pub name: Option<String>,
+    pub tags: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub first_event_at: Option<String>,
+    pub last_event_at: Option<String>,
+    pub time_direct_ms: i64,
+    pub time_delegated_ms: i64,
+    pub time_total_ms: i64,
+    pub event_count: usize,
+    pub events_by_type: BTreeMap<String, usize>,
+    pub sessions: Vec<StreamSession>,
+    pub top_cwds: Vec<CountEntry>,
+    pub top_projects: Vec<CountEntry>,
+}
+
+/// An agent session linked to a stream.
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamSession {
+    pub session_id: String,
+    pub source: String,
+    pub session_type: String,
+    pub start_time: String,
+    pub starting_prompt: Option<String>,
+}
+
+/// A value and how many of the stream's events carry it.
+#[derive(Debug, Clone, Serialize)]
+pub struct CountEntry {
+    pub value: String,
+    pub count: usize,
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Returns the `limit` most frequent values, ties broken alphabetically.
+fn top_counts<'a>(values: impl Iterator<Item = &'a str>, limit: usize) -> Vec<CountEntry> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_default() += 1;
+    }
+    let mut entries: Vec<CountEntry> = counts
+        .into_iter()
+        .map(|(value, count)| CountEntry {
+            value: value.to_string(),
+            count,
+        })
+        .collect();
+    entries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    entries.truncate(limit);
+    entries
+}
+
+/// Collects the detailed view for a stream identified by ID or name.
+pub fn get_stream_detail(db: &Database, query: &str) -> Result<StreamDetail> {
+    let Some(stream) = db
+        .resolve_stream(query)
+        .context("failed to query streams")?
+    else {
+        bail!("Stream '{query}' not found.\n\nHint: Use 'tt streams' to see available stream IDs.");
+    };
+
+    let tags = db.get_tags(&stream.id).context("failed to get tags")?;
+    let events = db
+        .get_events_by_stream(&stream.id)
+        .context("failed to load stream events")?;
+    let sessions = db
+        .sessions_for_stream(&stream.id)
+        .context("failed to load stream sessions")?;
+
+    let mut events_by_type = BTreeMap::new();
+    for event in &events {
+        *events_by_type
+            .entry(event.event_type.to_string())
+            .or_insert(0) += 1;
+    }
+
+    let top_cwds = top_counts(events.iter().filter_map(|e| e.cwd.as_deref()), TOP_LIMIT);
+    let top_projects = top_counts(
+        events.iter().filter_map(|e| e.git_project.as_deref()),
+        TOP_LIMIT,
+    );
+
+    let sessions = sessions
+        .into_iter()
+        .map(|session| StreamSession {
+            session_id: session.session_id,
+            source: session.source.as_str().to_string(),
+            session_type: session.session_type.as_str().to_string(),
+            start_time: format_timestamp(session.start_time),
+            starting_prompt: session.starting_prompt,
+        })
+        .collect();
+
+    Ok(StreamDetail {
+        time_total_ms: stream.total_ms(),
+        id: stream.id,
+        name: stream.name,
+        tags,
+        created_at: format_timestamp(stream.created_at),
+        updated_at: format_timestamp(stream.updated_at),
+        first_event_at: stream.first_event_at.map(format_timestamp),
+        last_event_at: stream.last_event_at.map(format_timestamp),
+        time_direct_ms: stream.time_direct_ms,
+        time_delegated_ms: stream.time_delegated_ms,
+        event_count: events.len(),
+        events_by_type,
+        sessions,
+        top_cwds,
+        top_projects,
+    })
+}
+
+/// Format a stream detail for human-readable output.
+pub fn format_stream_detail(detail: &StreamDetail) -> String {
+    let mut output = String::new();
+
+    writeln!(output, "STREAM {}", detail.id).unwrap();
+    writeln!(output).unwrap();
+    writeln!(
+        output,
+        "Name:         {}",
+        detail.name.as_deref().unwrap_or("(unnamed)")
+    )
+    .unwrap();
+    let tags = if detail.tags.is_empty() {
+        "(none)".to_string()
+    } else {
+        detail.tags.join(", ")
+    };
+    writeln!(output, "Tags:         {tags}").unwrap();
+    writeln!(output, "Created:      {}", detail.created_at).unwrap();
+    writeln!(output, "Updated:      {}", detail.updated_at).unwrap();
+    writeln!(
+        output,
+        "First event:  {}",
+        detail.first_event_at.as_deref().unwrap_or("-")
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "Last event:   {}",
+        detail.last_event_at.as_deref().unwrap_or("-")
+    )
+    .unwrap();
+
+    writeln!(output).unwrap();
+    writeln!(output, "TIME").unwrap();
+    writeln!(
+        output,
+        "Direct:       {}",
+        format_duration(detail.time_direct_ms)
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "Delegated:    {}",
+        format_duration(detail.time_delegated_ms)
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "Total:        {}",
+        format_duration(detail.time_total_ms)
+    )
+    .unwrap();
+
+    writeln!(output).unwrap();
+    writeln!(output, "EVENTS ({})", detail.event_count).unwrap();
+    if detail.events_by_type.is_empty() {
+        writeln!(output, "  (none)").unwrap();
+    }
+    for (event_type, count) in &detail.events_by_type {
+        writeln!(output, "  {event_type:<24}{count:>6}").unwrap();
+    }
+
+    writeln!(output).unwrap();
+    writeln!(output, "AGENT SESSIONS ({})", detail.sessions.len()).unwrap();
+    if detail.sessions.is_empty() {
+        writeln!(output, "  (none)").unwrap();
+    }
+    for session in &detail.sessions {
+        let id_short: String = session.session_id.chars().take(6).collect();
+        let prompt = session.starting_prompt.as_deref().unwrap_or("(no prompt)");
+        writeln!(
+            output,
+            "  {id_short}  {}/{}  {}  {prompt}",
+            session.source, session.session_type, session.start_time
+        )
+        .unwrap();
+    }
+
+    for (title, entries) in [
+        ("TOP DIRECTORIES", &detail.top_cwds),
+        ("TOP PROJECTS", &detail.top_projects),
+    ] {
+        writeln!(output).unwrap();
+        writeln!(output, "{title}").unwrap();
+        if entries.is_empty() {
+            writeln!(output, "  (none)").unwrap();
+        }
+        for entry in entries {
+            writeln!(output, "  {:>6}  {}", entry.count, entry.value).unwrap();
+        }
+    }
+
+    output
+}
+
+/// Show a detailed view of one stream.
+pub fn show(db: &Database, query: &str, json: bool) -> Result<()> {
+    let detail = get_stream_detail(db, query)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&detail)?);
+    } else {
+        print!("{}", format_stream_detail(&detail));
+    }
+
+    Ok(())
+}
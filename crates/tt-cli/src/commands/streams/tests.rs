@@ -2,7 +2,12 @@ use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use insta::assert_snapshot;
 use tt_db::{Database, Stream};
 
-use super::{format_streams, format_streams_json, get_streams_for_display};
+use super::{
+    StreamSort, categorize, category_of, format_stream_detail, format_stream_page, format_streams,
+    format_streams_json, get_stream_detail, get_stream_page, get_streams_for_display,
+    get_streams_in_period, retag, sort_streams, untag,
+};
+use crate::output::OutputCtx;
 
 fn make_stream(
     id: &str,
@@ -295,3 +300,278 @@ fn test_streams_unicode_name_truncation() {
         "the full long name should not appear in truncated output"
     );
 }
+
+fn make_stream_event(
+    id: &str,
+    timestamp: DateTime<Utc>,
+    stream_id: &str,
+    cwd: &str,
+    session_id: Option<&str>,
+) -> tt_db::StoredEvent {
+    tt_db::StoredEvent {
+        id: id.to_string(),
+        timestamp,
+        event_type: tt_core::EventType::TmuxPaneFocus,
+        source: "remote.tmux".to_string(),
+        machine_id: None,
+        schema_version: 1,
+        pane_id: Some("%1".to_string()),
+        tmux_session: None,
+        window_index: None,
+        git_project: Some("time-tracker".to_string()),
+        git_workspace: None,
+        status: None,
+        idle_duration_ms: None,
+        window_app_id: None,
+        window_title: None,
+        url: None,
+        action: None,
+        cwd: Some(cwd.to_string()),
+        session_id: session_id.map(String::from),
+        stream_id: Some(stream_id.to_string()),
+        assignment_source: Some("user".to_string()),
+        data: serde_json::json!({}),
+    }
+}
+
+#[test]
+fn test_stream_detail_aggregates_events_sessions_and_cwds() {
+    let db = Database::open_in_memory().unwrap();
+    let start = Utc.with_ymd_and_hms(2025, 1, 28, 12, 0, 0).unwrap();
+    db.insert_stream(&make_stream(
+        "abc123def456",
+        Some("time-tracker"),
+        3_600_000,
+        1_800_000,
+        Some(start),
+    ))
+    .unwrap();
+    db.add_tag("abc123def456", "acme").unwrap();
+
+    for (i, cwd) in ["/repo", "/repo", "/repo/docs"].iter().enumerate() {
+        let event = make_stream_event(
+            &format!("e{i}"),
+            start + chrono::Duration::minutes(i64::try_from(i).unwrap()),
+            "abc123def456",
+            cwd,
+            Some("sess-1"),
+        );
+        db.insert_event(&event).unwrap();
+    }
+    db.upsert_agent_session(
+        &tt_core::session::AgentSession {
+            session_id: "sess-1".to_string(),
+            source: tt_core::session::SessionSource::Claude,
+            parent_session_id: None,
+            session_type: tt_core::session::SessionType::User,
+            project_path: "/repo".to_string(),
+            project_name: "repo".to_string(),
+            start_time: start,
+            end_time: None,
+            message_count: 1,
+            summary: None,
+            user_prompts: vec![],
+            starting_prompt: Some("fix the report".to_string()),
+            assistant_message_count: 0,
+            tool_call_count: 0,
+            user_message_timestamps: Vec::new(),
+            tool_call_timestamps: Vec::new(),
+        },
+        None,
+    )
+    .unwrap();
+    db.link_sessions_to_streams().unwrap();
+
+    let detail = get_stream_detail(&db, "time-tracker").unwrap();
+
+    assert_eq!(detail.id, "abc123def456");
+    assert_eq!(detail.tags, vec!["acme"]);
+    assert_eq!(detail.time_total_ms, 5_400_000);
+    assert_eq!(detail.event_count, 3);
+    assert_eq!(detail.events_by_type.get("tmux_pane_focus"), Some(&3));
+    assert_eq!(detail.sessions.len(), 1);
+    assert_eq!(
+        detail.sessions[0].starting_prompt.as_deref(),
+        Some("fix the report")
+    );
+    assert_eq!(detail.top_cwds[0].value, "/repo");
+    assert_eq!(detail.top_cwds[0].count, 2);
+    assert_eq!(detail.top_projects[0].value, "time-tracker");
+
+    let output = format_stream_detail(&detail);
+    assert!(output.contains("Total:        1h 30m"));
+    assert!(output.contains("fix the report"));
+
+    let json: serde_json::Value = serde_json::to_value(&detail).unwrap();
+    assert_eq!(json["events_by_type"]["tmux_pane_focus"], 3);
+}
+
+#[test]
+fn test_stream_detail_unknown_stream_errors() {
+    let db = Database::open_in_memory().unwrap();
+    let err = get_stream_detail(&db, "missing").unwrap_err();
+    assert!(err.to_string().contains("not found"));
+}
+
+#[test]
+fn test_stream_page_includes_idle_streams_and_page_totals() {
+    let db = Database::open_in_memory().unwrap();
+    let base = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+    for (i, name) in ["old", "middle", "newest"].into_iter().enumerate() {
+        let mut stream = make_stream(name, Some(name), 0, 0, None);
+        stream.updated_at = base + chrono::Duration::days(i64::try_from(i).unwrap());
+        db.insert_stream(&stream).unwrap();
+    }
+    db.add_tag("newest", "acme").unwrap();
+
+    let first = get_stream_page(&db, 1, 2).unwrap();
+    let second = get_stream_page(&db, 2, 2).unwrap();
+
+    assert_eq!((first.total_streams, first.total_pages), (3, 2));
+    let names: Vec<_> = first.streams.iter().map(|s| s.id.as_str()).collect();
+    assert_eq!(names, vec!["newest", "middle"]);
+    assert_eq!(first.streams[0].tags, vec!["acme"]);
+    assert_eq!(second.streams.len(), 1);
+    assert_eq!(second.streams[0].id, "old");
+
+    let output = format_stream_page(&second);
+    assert!(output.starts_with("STREAMS (page 2 of 2, 3 total, most recently updated first)"));
+    assert!(output.contains("old"));
+}
+
+#[test]
+fn test_categorize_replaces_previous_category_and_keeps_other_tags() {
+    let db = Database::open_in_memory().unwrap();
+    db.insert_stream(&make_stream("s1", Some("auth"), 0, 0, None))
+        .unwrap();
+    db.add_tag("s1", "acme").unwrap();
+
+    categorize(&db, "auth", "coding", &OutputCtx::default()).unwrap();
+    categorize(&db, "s1", "type:reviewing", &OutputCtx::default()).unwrap();
+
+    let tags = db.get_tags("s1").unwrap();
+    assert_eq!(tags, vec!["acme", "type:reviewing"]);
+    assert_eq!(category_of(&tags), Some("reviewing"));
+    assert!(categorize(&db, "s1", "  ", &OutputCtx::default()).is_err());
+    assert!(categorize(&db, "missing", "coding", &OutputCtx::default()).is_err());
+}
+
+#[test]
+fn test_untag_clears_all_tags_or_only_the_given_ones() {
+    let db = Database::open_in_memory().unwrap();
+    db.insert_stream(&make_stream("s1", Some("auth"), 0, 0, None))
+        .unwrap();
+    for tag in ["acme", "urgent", "type:coding"] {
+        db.add_tag("s1", tag).unwrap();
+    }
+
+    let subset = ["urgent".to_string(), "not-present".to_string()];
+    assert_eq!(
+        untag(&db, "auth", &subset, &OutputCtx::default()).unwrap(),
+        1
+    );
+    assert_eq!(db.get_tags("s1").unwrap(), vec!["acme", "type:coding"]);
+
+    assert_eq!(untag(&db, "s1", &[], &OutputCtx::default()).unwrap(), 2);
+    assert!(db.get_tags("s1").unwrap().is_empty());
+    assert!(untag(&db, "missing", &[], &OutputCtx::default()).is_err());
+}
+
+#[test]
+fn test_retag_changes_only_the_target_stream() {
+    let db = Database::open_in_memory().unwrap();
+    db.insert_stream(&make_stream("s1", Some("auth"), 0, 0, None))
+        .unwrap();
+    db.insert_stream(&make_stream("s2", Some("billing"), 0, 0, None))
+        .unwrap();
+    db.add_tag("s1", "acme").unwrap();
+    db.add_tag("s2", "acme").unwrap();
+
+    retag(&db, "auth", "acme", "acme-corp", &OutputCtx::default()).unwrap();
+    assert_eq!(db.get_tags("s1").unwrap(), vec!["acme-corp"]);
+    assert_eq!(db.get_tags("s2").unwrap(), vec!["acme"]);
+
+    assert!(retag(&db, "auth", "acme", "other", &OutputCtx::default()).is_err());
+    assert!(retag(&db, "missing", "acme", "other", &OutputCtx::default()).is_err());
+}
+
+#[test]
+fn test_sort_streams_by_each_order_and_reversed() {
+    let day = |d| Utc.with_ymd_and_hms(2025, 1, d, 12, 0, 0).unwrap();
+    let stream = |id: &str, name: Option<&str>, total_ms, created: u32, last: u32| {
+        let mut stream = make_stream(id, name, total_ms, 0, Some(day(last)));
+        stream.created_at = day(created);
+        (stream, Vec::new())
+    };
+    let fixture = vec![
+        stream("a", Some("beta"), 1_000, 3, 10),
+        stream("b", None, 3_000, 1, 12),
+        stream("c", Some("alpha"), 2_000, 2, 11),
+    ];
+    let order = |sort, reverse| {
+        let mut streams = fixture.clone();
+        sort_streams(&mut streams, sort, reverse);
+        streams
+            .iter()
+            .map(|(stream, _)| stream.id.clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(order(StreamSort::Time, false), vec!["b", "c", "a"]);
+    assert_eq!(order(StreamSort::Recent, false), vec!["b", "c", "a"]);
+    assert_eq!(order(StreamSort::Name, false), vec!["c", "a", "b"]);
+    assert_eq!(order(StreamSort::Created, false), vec!["a", "c", "b"]);
+    assert_eq!(order(StreamSort::Time, true), vec!["a", "c", "b"]);
+    assert_eq!(order(StreamSort::Name, true), vec!["b", "a", "c"]);
+}
+
+#[test]
+fn test_streams_in_period_are_those_with_events_in_range_with_period_times() {
+    let db = Database::open_in_memory().unwrap();
+    let at = |d, h, m| Utc.with_ymd_and_hms(2025, 1, d, h, m, 0).unwrap();
+    let (start, end) = (at(28, 0, 0), at(29, 0, 0));
+
+    // "spanning" brackets the range without any events inside it.
+    for (id, first, last) in [
+        ("alpha", at(28, 10, 0), at(28, 10, 0)),
+        ("beta", at(20, 9, 0), at(28, 10, 3)),
+        ("spanning", at(27, 12, 0), at(29, 12, 0)),
+        ("earlier", at(20, 9, 0), at(20, 9, 0)),
+    ] {
+        let mut stream = make_stream(id, Some(id), 36_000_000, 0, Some(last));
+        stream.first_event_at = Some(first);
+        db.insert_stream(&stream).unwrap();
+    }
+    db.insert_events(&[
+        make_stream_event("e1", at(20, 9, 0), "beta", "/src", None),
+        make_stream_event("e2", at(20, 9, 0), "earlier", "/src", None),
+        make_stream_event("e3", at(27, 12, 0), "spanning", "/src", None),
+        make_stream_event("e4", at(28, 10, 0), "alpha", "/src", None),
+        make_stream_event("e5", at(28, 10, 3), "beta", "/src", None),
+        make_stream_event("e6", at(29, 12, 0), "spanning", "/src", None),
+    ])
+    .unwrap();
+
+    let entries =
+        get_streams_in_period(&db, start, end, at(30, 0, 0), StreamSort::Time, false).unwrap();
+
+    let mut with_events: Vec<String> = db
+        .get_events_in_range(start, end)
+        .unwrap()
+        .into_iter()
+        .filter_map(|event| event.stream_id)
+        .collect();
+    with_events.sort();
+    with_events.dedup();
+    let mut listed: Vec<String> = entries.iter().map(|e| e.id.clone()).collect();
+    listed.sort();
+    assert_eq!(listed, with_events);
+
+    // Period times, not the 10h lifetime totals: alpha until beta's focus,
+    // beta for one attention window after it.
+    let times: Vec<(&str, i64)> = entries
+        .iter()
+        .map(|e| (e.id.as_str(), e.time_direct_ms))
+        .collect();
+    assert_eq!(times, vec![("beta", 300_000), ("alpha", 180_000)]);
+}
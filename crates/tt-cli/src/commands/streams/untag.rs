@@ -0,0 +1,39 @@
+use anyhow::{Context, Result, bail};
+use tt_db::Database;
+
+use crate::output::OutputCtx;
+
+/// Removes tags from a stream: all of them, or only `tags` when non-empty.
+///
+/// Returns the number of tags removed.
+pub fn untag(db: &Database, stream: &str, tags: &[String], ctx: &OutputCtx) -> Result<u64> {
+    let Some(resolved) = db
+        .resolve_stream(stream)
+        .context("failed to query streams")?
+    else {
+        bail!(
+            "Stream '{stream}' not found.\n\nHint: Use 'tt streams' to see available stream IDs."
+        );
+    };
+
+    let removed = if tags.is_empty() {
+        db.clear_tags(&resolved.id)
+            .context("failed to clear tags")?
+    } else {
+        let current = db.get_tags(&resolved.id).context("failed to get tags")?;
+        let mut removed = 0;
+        for tag in tags.iter().filter(|tag| current.contains(tag)) {
+            db.delete_tag(&resolved.id, tag)
+                .context("failed to remove tag")?;
+            removed += 1;
+        }
+        removed
+    };
+
+    let stream_name = resolved.name.as_deref().unwrap_or("<unnamed>");
+    ctx.info(format_args!(
+        "Removed {removed} tag(s) from stream {} ({stream_name})",
+        resolved.id
+    ));
+    Ok(removed)
+}
@@ -8,26 +8,35 @@ use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Duration, Utc};
 use flate2::read::GzDecoder;
 
+use crate::Config;
 use crate::commands::{import, ingest, recompute};
+use crate::output::OutputCtx;
 
 /// Runs the sync command for one or more remotes.
-pub fn run(db: &tt_db::Database, remotes: &[String]) -> Result<()> {
+///
+/// Unless `ctx.quiet`, each remote's import is followed by an import summary.
+pub fn run(
+    db: &tt_db::Database,
+    config: &Config,
+    remotes: &[String],
+    ctx: &OutputCtx,
+) -> Result<()> {
     for remote in remotes {
-        println!("Syncing from {remote}...");
-        sync_single(db, remote)?;
+        ctx.info(format_args!("Syncing from {remote}..."));
+        sync_single(db, remote, *ctx)?;
     }
 
     // Reindex sessions and recompute after all syncs
-    println!("\nIndexing sessions...");
-    ingest::index_sessions(db)?;
-    println!("Recomputing time...");
-    recompute::run(db, false)?;
+    ctx.info(format_args!("\nIndexing sessions..."));
+    ingest::index_sessions(db, config, ctx)?;
+    ctx.info(format_args!("Recomputing time..."));
+    recompute::run(db, false, &config.allocation, ctx)?;
 
     Ok(())
 }
 
 /// Syncs events from a single remote.
-fn sync_single(db: &tt_db::Database, remote: &str) -> Result<()> {
+fn sync_single(db: &tt_db::Database, remote: &str, ctx: OutputCtx) -> Result<()> {
     let last_event_id = db.get_machine_last_event_id_by_label(remote)?;
     let last_sync_at = db.get_machine_last_sync_at_by_label(remote)?;
 
@@ -66,15 +75,22 @@ fn sync_single(db: &tt_db::Database, remote: &str) -> Result<()> {
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    sync_single_with_command(db, remote, &mut command)
+    sync_single_with_command(db, remote, &mut command, ctx)
 }
 
+#[allow(clippy::too_many_lines)]
 fn sync_single_with_command(
     db: &tt_db::Database,
     remote: &str,
     command: &mut Command,
+    ctx: OutputCtx,
 ) -> Result<()> {
     command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let baseline = if ctx.quiet {
+        None
+    } else {
+        Some(import::ImportBaseline::capture(db)?)
+    };
 
     let program = command.get_program().to_owned();
     let args: Vec<std::ffi::OsString> = command.get_args().map(std::ffi::OsString::from).collect();
@@ -175,10 +191,14 @@ fn sync_single_with_command(
 
     let result = result?;
 
-    println!(
+    ctx.info(format_args!(
         "  Imported {} events, {} sessions ({} duplicates, {} malformed)",
         result.inserted, result.sessions_imported, result.duplicates, result.malformed
-    );
+    ));
+    if let Some(baseline) = baseline {
+        let summary = import::ImportSummary::compute(db, &baseline, &result)?;
+        print!("{}", import::format_import_summary(&summary));
+    }
     if let Some(ref mid) = result.machine_id {
         let new_last_id = db.get_latest_event_id_for_machine(mid)?;
         let now_utc = Utc::now();
@@ -208,6 +228,13 @@ mod tests {
 
     use super::sync_single_with_command;
     use crate::commands::import;
+    use crate::output::OutputCtx;
+
+    const QUIET: OutputCtx = OutputCtx {
+        color: false,
+        quiet: true,
+        json: false,
+    };
 
     fn run_with_shell(db: &Database, remote: &str, script: &str) -> Result<()> {
         let mut command = Command::new("sh");
@@ -216,7 +243,7 @@ mod tests {
             .arg(script)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
-        sync_single_with_command(db, remote, &mut command)
+        sync_single_with_command(db, remote, &mut command, QUIET)
     }
 
     fn make_jsonl_event(id: &str, ts: &str) -> String {
@@ -370,7 +397,7 @@ mod tests {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        sync_single_with_command(&db, "compat-remote", &mut command)?;
+        sync_single_with_command(&db, "compat-remote", &mut command, QUIET)?;
 
         let events = db.get_events(None, None)?;
         assert_eq!(events.len(), 1);
@@ -3,10 +3,12 @@
 use anyhow::{Context, Result, bail};
 use tt_db::Database;
 
+use crate::output::OutputCtx;
+
 /// Run the tag command.
 ///
 /// Adds a tag to a stream, identified by ID or name.
-pub fn run(db: &Database, stream: &str, tag: &str) -> Result<()> {
+pub fn run(db: &Database, stream: &str, tag: &str, ctx: &OutputCtx) -> Result<()> {
     // Resolve stream by ID or name
     let resolved = db
         .resolve_stream(stream)
@@ -26,12 +28,28 @@ pub fn run(db: &Database, stream: &str, tag: &str) -> Result<()> {
 
     // Print confirmation
     let stream_name = resolved.name.as_deref().unwrap_or("<unnamed>");
-    println!(
+    ctx.info(format_args!(
         "Tagged stream {} ({}) as \"{}\"",
         resolved.id, stream_name, tag
-    );
-    println!("Tags: {}", tags.join(", "));
+    ));
+    ctx.info(format_args!("Tags: {}", tags.join(", ")));
+
+    Ok(())
+}
 
+/// Merge tag `from` into `to` on every stream carrying it.
+pub fn merge(db: &Database, from: &str, to: &str, ctx: &OutputCtx) -> Result<()> {
+    if from == to {
+        bail!("Cannot merge tag \"{from}\" into itself.");
+    }
+    let moved = db.merge_tags(from, to).context("failed to merge tags")?;
+    match moved {
+        0 => ctx.info(format_args!("No streams are tagged \"{from}\".")),
+        1 => ctx.info(format_args!("Merged \"{from}\" into \"{to}\" on 1 stream")),
+        n => ctx.info(format_args!(
+            "Merged \"{from}\" into \"{to}\" on {n} streams"
+        )),
+    }
     Ok(())
 }
 
@@ -59,7 +77,7 @@ mod tests {
         db.insert_stream(&stream).unwrap();
 
         // Tag by ID
-        run(&db, "test-stream-123", "acme-webapp").unwrap();
+        run(&db, "test-stream-123", "acme-webapp", &OutputCtx::default()).unwrap();
 
         // Verify tag was added
         let tags = db.get_tags("test-stream-123").unwrap();
@@ -86,7 +104,7 @@ mod tests {
         db.insert_stream(&stream).unwrap();
 
         // Tag by name
-        run(&db, "my-project", "internal").unwrap();
+        run(&db, "my-project", "internal", &OutputCtx::default()).unwrap();
 
         // Verify tag was added
         let tags = db.get_tags("test-stream-456").unwrap();
@@ -97,7 +115,7 @@ mod tests {
     fn test_tag_nonexistent_stream() {
         let db = Database::open_in_memory().unwrap();
 
-        let result = run(&db, "nonexistent", "some-tag");
+        let result = run(&db, "nonexistent", "some-tag", &OutputCtx::default());
         assert!(result.is_err());
 
         let err = result.unwrap_err().to_string();
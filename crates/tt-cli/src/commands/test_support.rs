@@ -0,0 +1,48 @@
+//! Fixtures shared by command tests.
+
+use chrono::{DateTime, TimeZone, Utc};
+use tt_db::{StoredEvent, Stream};
+
+/// A `tmux_pane_focus` event with no stream, project or cwd.
+pub fn make_event(id: &str, timestamp: DateTime<Utc>) -> StoredEvent {
+    StoredEvent {
+        id: id.to_string(),
+        timestamp,
+        event_type: tt_core::EventType::TmuxPaneFocus,
+        source: "remote.tmux".to_string(),
+        machine_id: None,
+        schema_version: 1,
+        pane_id: Some("%1".to_string()),
+        tmux_session: None,
+        window_index: None,
+        git_project: None,
+        git_workspace: None,
+        status: None,
+        idle_duration_ms: None,
+        window_app_id: None,
+        window_title: None,
+        url: None,
+        action: None,
+        cwd: None,
+        session_id: None,
+        stream_id: None,
+        assignment_source: None,
+        data: serde_json::json!({}),
+    }
+}
+
+/// A stream with no recorded time, created 2025-01-15 09:00 UTC.
+pub fn make_stream(id: &str, name: Option<&str>) -> Stream {
+    let now = Utc.with_ymd_and_hms(2025, 1, 15, 9, 0, 0).unwrap();
+    Stream {
+        id: id.to_string(),
+        name: name.map(str::to_string),
+        created_at: now,
+        updated_at: now,
+        time_direct_ms: 0,
+        time_delegated_ms: 0,
+        first_event_at: None,
+        last_event_at: None,
+        needs_recompute: false,
+    }
+}
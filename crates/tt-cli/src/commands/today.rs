@@ -0,0 +1,78 @@
+//! Today command: the day report, optionally refreshed live.
+//!
+//! `tt today --watch` re-renders the day report every `--interval` seconds,
+//! clearing the screen between renders. The database connection is opened
+//! once by the caller and re-queried each tick. Ctrl-C terminates the loop;
+//! nothing is written during a render, so interrupting is always safe.
+
+use std::io::Write;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use tt_core::AllocationConfig;
+use tt_db::Database;
+
+use super::report::{Period, format_report_styled, generate_report_data};
+use crate::output::OutputCtx;
+
+/// ANSI sequence: clear the screen and move the cursor to the top-left.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Default refresh interval for `--watch`, in seconds.
+pub const DEFAULT_INTERVAL_SECS: u64 = 10;
+
+/// Renders one watch frame: a screen clear followed by the day report.
+fn render_frame(
+    db: &Database,
+    config: &AllocationConfig,
+    generated_at: DateTime<Utc>,
+    color: bool,
+) -> Result<String> {
+    let data = generate_report_data(db, Period::Day, generated_at, config)?;
+    Ok(format!(
+        "{CLEAR_SCREEN}{}",
+        format_report_styled(&data, color)
+    ))
+}
+
+/// Runs the today command.
+///
+/// Without `watch`, prints the day report once. With `watch`, loops until
+/// interrupted, re-rendering every `interval`. The report is colored per `ctx`.
+pub fn run(
+    db: &Database,
+    config: &AllocationConfig,
+    watch: bool,
+    interval: Duration,
+    ctx: &OutputCtx,
+) -> Result<()> {
+    if !watch {
+        let data = generate_report_data(db, Period::Day, Utc::now(), config)?;
+        print!("{}", format_report_styled(&data, ctx.color));
+        return Ok(());
+    }
+
+    let mut stdout = std::io::stdout();
+    loop {
+        let frame = render_frame(db, config, Utc::now(), ctx.color)?;
+        write!(stdout, "{frame}").context("failed to write report")?;
+        stdout.flush().context("failed to flush stdout")?;
+        std::thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_frame_clears_screen_before_report() {
+        let db = Database::open_in_memory().unwrap();
+
+        let frame = render_frame(&db, &AllocationConfig::default(), Utc::now(), false).unwrap();
+
+        assert!(frame.starts_with(CLEAR_SCREEN));
+        assert!(frame.contains("TIME REPORT:"));
+    }
+}
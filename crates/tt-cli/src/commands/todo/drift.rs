@@ -15,9 +15,15 @@ pub fn run(db: &Database, config: &Config, period: Period, json: bool) -> Result
     let generated_at = Utc::now();
     let reference_date = generated_at.with_timezone(&Local).date_naive();
     let timezone = iana_time_zone::get_timezone().unwrap_or_else(|_| "Etc/UTC".to_string());
-    let report_data =
-        report::generate_report_data_for_date(db, period, generated_at, reference_date, timezone)
-            .context("failed to generate report data for todo drift")?;
+    let report_data = report::generate_report_data_for_date(
+        db,
+        period,
+        generated_at,
+        reference_date,
+        timezone,
+        &config.allocation,
+    )
+    .context("failed to generate report data for todo drift")?;
     let loaded = load_read_only(config)?;
     let priorities = priority_items(&loaded);
     let links = stream_links(&loaded);
@@ -1,6 +1,7 @@
 //! Shared utilities for CLI commands.
 
 use std::sync::LazyLock;
+use std::time::Instant;
 
 use anyhow::Context;
 use chrono::{DateTime, Duration, Utc};
@@ -13,6 +14,19 @@ static RELATIVE_TIME_RE: LazyLock<Regex> =
 /// Conservative bounds for relative time parsing (~1000 years in minutes).
 const MAX_RELATIVE_MINUTES: i64 = 1000 * 365 * 24 * 60;
 
+/// Tracing target for `--profile` phase timings, enabled at info level by the flag.
+pub const PROFILE_TARGET: &str = "tt::profile";
+
+/// Logs the wall-clock time spent in `phase` since `start`.
+pub fn log_phase(phase: &str, start: Instant) {
+    tracing::info!(
+        target: PROFILE_TARGET,
+        phase,
+        elapsed_ms = start.elapsed().as_millis(),
+        "phase complete"
+    );
+}
+
 /// Parse a datetime string as either ISO 8601 or relative time.
 ///
 /// Supports:
@@ -0,0 +1,146 @@
+//! Verify command for data-integrity checks on the local database.
+
+use std::fmt::Write;
+
+use anyhow::{Context, Result};
+use tt_db::{Database, DuplicateEventGroup};
+
+/// Formats duplicate groups, one header line per group followed by its IDs.
+pub fn format_duplicate_groups(groups: &[DuplicateEventGroup]) -> String {
+    let mut output = String::new();
+    if groups.is_empty() {
+        writeln!(output, "No duplicate events found.").unwrap();
+        return output;
+    }
+
+    let events: usize = groups.iter().map(|g| g.ids.len()).sum();
+    writeln!(
+        output,
+        "DUPLICATE EVENTS ({} group(s), {events} events)",
+        groups.len()
+    )
+    .unwrap();
+    for group in groups {
+        writeln!(output).unwrap();
+        writeln!(
+            output,
+            "{}  {}  session={}  stream={}",
+            group.timestamp,
+            group.event_type,
+            group.session_id.as_deref().unwrap_or("-"),
+            group.stream_id.as_deref().unwrap_or("-"),
+        )
+        .unwrap();
+        for id in &group.ids {
+            writeln!(output, "  {id}").unwrap();
+        }
+    }
+    output
+}
+
+/// Deletes all but the lexicographically smallest ID of each group.
+///
+/// Streams of deleted events are marked for recompute. Returns the number
+/// of events deleted.
+pub fn delete_duplicates(db: &Database, groups: &[DuplicateEventGroup]) -> Result<u64> {
+    let extra: Vec<String> = groups
+        .iter()
+        .flat_map(|g| g.ids.iter().skip(1).cloned())
+        .collect();
+    let deleted = db
+        .delete_events(&extra)
+        .context("failed to delete duplicate events")?;
+
+    let mut streams: Vec<&str> = groups
+        .iter()
+        .filter_map(|g| g.stream_id.as_deref())
+        .collect();
+    streams.sort_unstable();
+    streams.dedup();
+    db.mark_streams_for_recompute(&streams)
+        .context("failed to mark streams for recompute")?;
+    Ok(deleted)
+}
+
+/// Runs `tt verify --dupes`, deleting the extras when `delete` is set.
+pub fn run_dupes(db: &Database, delete: bool) -> Result<()> {
+    let groups = db
+        .find_duplicate_events()
+        .context("failed to query duplicate events")?;
+    print!("{}", format_duplicate_groups(&groups));
+
+    if delete && !groups.is_empty() {
+        let deleted = delete_duplicates(db, &groups)?;
+        println!();
+        println!("Deleted {deleted} duplicate event(s), keeping the smallest ID of each group.");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn make_message(id: &str, stream_id: &str) -> tt_db::StoredEvent {
+        tt_db::StoredEvent {
+            id: id.to_string(),
+            timestamp: Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap(),
+            event_type: tt_core::EventType::UserMessage,
+            source: "remote.agent".to_string(),
+            machine_id: None,
+            schema_version: 1,
+            pane_id: None,
+            tmux_session: None,
+            window_index: None,
+            git_project: None,
+            git_workspace: None,
+            status: None,
+            idle_duration_ms: None,
+            window_app_id: None,
+            window_title: None,
+            url: None,
+            action: None,
+            cwd: None,
+            session_id: Some("sess-1".to_string()),
+            stream_id: Some(stream_id.to_string()),
+            assignment_source: Some("inferred".to_string()),
+            data: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_differing_ids_are_flagged_and_smallest_id_is_kept() {
+        let db = Database::open_in_memory().unwrap();
+        let now = Utc::now();
+        db.insert_stream(&tt_db::Stream {
+            id: "s1".to_string(),
+            name: Some("s1".to_string()),
+            created_at: now,
+            updated_at: now,
+            time_direct_ms: 0,
+            time_delegated_ms: 0,
+            first_event_at: None,
+            last_event_at: None,
+            needs_recompute: false,
+        })
+        .unwrap();
+        db.insert_events(&[make_message("b-id", "s1"), make_message("a-id", "s1")])
+            .unwrap();
+
+        let groups = db.find_duplicate_events().unwrap();
+        let output = format_duplicate_groups(&groups);
+        assert!(output.starts_with("DUPLICATE EVENTS (1 group(s), 2 events)"));
+        assert!(output.contains("  a-id\n  b-id\n"), "{output}");
+
+        assert_eq!(delete_duplicates(&db, &groups).unwrap(), 1);
+        let remaining: Vec<_> = db
+            .get_events_by_stream("s1")
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(remaining, vec!["a-id"]);
+        assert!(db.get_stream("s1").unwrap().unwrap().needs_recompute);
+    }
+}
@@ -4,16 +4,25 @@ use std::fmt;
 use std::path::{Path, PathBuf};
 
 use figment::Figment;
-use figment::providers::{Env, Format, Serialized, Toml};
+use figment::providers::{Env, Format, Json, Serialized, Toml, Yaml};
 use serde::{Deserialize, Serialize};
+use tt_core::AllocationConfig;
+
+use crate::url_project::UrlProjectRule;
 
 /// Application configuration.
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Config {
     /// Path to the database file.
     pub database_path: PathBuf,
     /// Path to the markdown-backed todo store directory.
     pub todo_store_path: PathBuf,
+    /// Rules mapping `browser_tab` URLs to projects (stream names).
+    #[serde(default)]
+    pub url_project_map: Vec<UrlProjectRule>,
+    /// Time allocation parameters (`[allocation]`); unset fields keep their defaults.
+    #[serde(default)]
+    pub allocation: AllocationConfig,
 }
 
 impl fmt::Debug for Config {
@@ -21,6 +30,8 @@ impl fmt::Debug for Config {
         f.debug_struct("Config")
             .field("database_path", &self.database_path)
             .field("todo_store_path", &self.todo_store_path)
+            .field("url_project_map", &self.url_project_map)
+            .field("allocation", &self.allocation)
             .finish()
     }
 }
@@ -31,6 +42,8 @@ impl Default for Config {
         Self {
             database_path: data_dir.join("tt.db"),
             todo_store_path: data_dir,
+            url_project_map: Vec::new(),
+            allocation: AllocationConfig::default(),
         }
     }
 }
@@ -46,6 +59,11 @@ impl Config {
     }
 
     /// Loads configuration, optionally from a specific file.
+    ///
+    /// Files are parsed by extension: `.json` as JSON, `.yaml`/`.yml` as YAML,
+    /// anything else as TOML. In the default directory each of `config.toml`,
+    /// `config.json`, `config.yaml` and `config.yml` that exists is merged in
+    /// that order.
     #[expect(
         clippy::result_large_err,
         reason = "figment::Error is large but only returned at startup"
@@ -55,19 +73,85 @@ impl Config {
 
         // Load from default config location
         if let Some(config_dir) = dirs_config_path() {
-            figment = figment.merge(Toml::file(config_dir.join("config.toml")));
+            for name in DEFAULT_CONFIG_FILES {
+                figment = merge_config_file(figment, &config_dir.join(name));
+            }
         }
 
         // Load from specified config file
         if let Some(path) = config_path {
-            figment = figment.merge(Toml::file(path));
+            figment = merge_config_file(figment, path);
         }
 
         // Load from environment variables (TT_*)
         figment = figment.merge(Env::prefixed("TT_"));
 
+        // TT_DATABASE is a shorthand for TT_DATABASE_PATH and wins over it
+        figment = figment.merge(
+            Env::raw()
+                .only(&[DATABASE_ENV])
+                .map(|_| "database_path".into()),
+        );
+
         figment.extract()
     }
+
+    /// Returns the default config file location (`~/.config/time-tracker/config.toml` on Linux).
+    pub fn default_path() -> Option<PathBuf> {
+        dirs_config_path().map(|dir| dir.join("config.toml"))
+    }
+
+    /// Serializes the configuration as TOML, with secret values redacted.
+    pub fn to_redacted_toml(&self) -> Result<String, toml::ser::Error> {
+        let mut value = toml::Value::try_from(self)?;
+        redact_secrets(&mut value);
+        toml::to_string(&value)
+    }
+}
+
+/// Config file names looked up in the default directory, merged in order.
+const DEFAULT_CONFIG_FILES: &[&str] = &["config.toml", "config.json", "config.yaml", "config.yml"];
+
+/// Merges a config file into `figment`, choosing the format by extension (TOML by default).
+///
+/// Missing files are skipped, as with figment's file providers.
+fn merge_config_file(figment: Figment, path: &Path) -> Figment {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("json") => figment.merge(Json::file(path)),
+        Some("yaml" | "yml") => figment.merge(Yaml::file(path)),
+        _ => figment.merge(Toml::file(path)),
+    }
+}
+
+/// Environment variable overriding `database_path`, e.g. to point at a scratch DB.
+pub const DATABASE_ENV: &str = "TT_DATABASE";
+
+/// Key fragments marking a config value as secret.
+const SECRET_KEY_MARKERS: &[&str] = &["api_key", "token", "secret", "password"];
+
+/// Placeholder written in place of secret values.
+const REDACTED: &str = "<redacted>";
+
+/// Replaces every string value under a secret-looking key, at any depth.
+fn redact_secrets(value: &mut toml::Value) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if value.is_str() && SECRET_KEY_MARKERS.iter().any(|m| key.contains(m)) {
+                    *value = toml::Value::String(REDACTED.to_string());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        }
+        toml::Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
 }
 
 /// Returns the platform-specific config directory for time-tracker.
@@ -115,6 +199,97 @@ mod tests {
         assert_eq!(path.file_name().unwrap(), "time-tracker");
     }
 
+    #[test]
+    fn test_default_path_is_config_toml_in_config_dir() {
+        let path = Config::default_path().unwrap();
+        assert_eq!(path.file_name().unwrap(), "config.toml");
+        assert_eq!(path.parent(), dirs_config_path().as_deref());
+    }
+
+    #[test]
+    fn test_redacted_toml_round_trips() {
+        let config = Config {
+            database_path: PathBuf::from("/tmp/tt.db"),
+            todo_store_path: PathBuf::from("/tmp/todos"),
+            url_project_map: vec![UrlProjectRule {
+                domain: Some("docs.acme.dev".to_string()),
+                regex: None,
+                project: "acme-docs".to_string(),
+            }],
+            allocation: AllocationConfig::default(),
+        };
+
+        let dumped = config.to_redacted_toml().unwrap();
+        let parsed: Config = Figment::from(Toml::string(&dumped)).extract().unwrap();
+
+        assert_eq!(parsed.database_path, config.database_path);
+        assert_eq!(parsed.todo_store_path, config.todo_store_path);
+        assert_eq!(parsed.url_project_map, config.url_project_map);
+    }
+
+    #[test]
+    fn test_load_from_reads_toml_json_and_yaml_alike() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "config.toml",
+                concat!(
+                    "database_path = \"/tmp/tt-formats.db\"\n",
+                    "todo_store_path = \"/tmp/tt-formats-todos\"\n",
+                    "[[url_project_map]]\n",
+                    "domain = \"docs.acme.dev\"\n",
+                    "project = \"acme-docs\"\n",
+                ),
+            ),
+            (
+                "config.json",
+                r#"{"database_path": "/tmp/tt-formats.db", "todo_store_path": "/tmp/tt-formats-todos", "url_project_map": [{"domain": "docs.acme.dev", "project": "acme-docs"}]}"#,
+            ),
+            (
+                "config.yaml",
+                concat!(
+                    "database_path: /tmp/tt-formats.db\n",
+                    "todo_store_path: /tmp/tt-formats-todos\n",
+                    "url_project_map:\n",
+                    "  - domain: docs.acme.dev\n",
+                    "    project: acme-docs\n",
+                ),
+            ),
+            // No extension falls back to TOML
+            ("config", "database_path = \"/tmp/tt-formats.db\"\n"),
+        ];
+        let configs: Vec<Config> = files
+            .iter()
+            .map(|(name, contents)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, contents).unwrap();
+                Config::load_from(Some(&path)).unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            configs[0].database_path,
+            PathBuf::from("/tmp/tt-formats.db")
+        );
+        assert_eq!(configs[0].url_project_map.len(), 1);
+        assert_eq!(configs[0], configs[1]);
+        assert_eq!(configs[0], configs[2]);
+        assert_eq!(configs[3].database_path, configs[0].database_path);
+    }
+
+    #[test]
+    fn test_redact_secrets_replaces_nested_secret_strings() {
+        let mut value: toml::Value =
+            toml::from_str("api_key = \"sk-123\"\nname = \"tt\"\n[llm]\nauth_token = \"abc\"\n")
+                .unwrap();
+
+        redact_secrets(&mut value);
+
+        assert_eq!(value["api_key"].as_str(), Some(REDACTED));
+        assert_eq!(value["llm"]["auth_token"].as_str(), Some(REDACTED));
+        assert_eq!(value["name"].as_str(), Some("tt"));
+    }
+
     #[test]
     fn test_default_config_uses_data_dir_for_db() {
         let config = Config::default();
@@ -159,4 +334,39 @@ mod tests {
             String::from_utf8_lossy(&output.stderr)
         );
     }
+
+    #[test]
+    fn load_from_tt_database_env_overrides_config_file() {
+        const CHILD_MARKER: &str = "TT_TEST_DATABASE_ENV_CHILD_CONFIG";
+
+        if let Some(config_path) = std::env::var_os(CHILD_MARKER) {
+            let config = Config::load_from(Some(Path::new(&config_path))).unwrap();
+            assert_eq!(config.database_path, PathBuf::from("/tmp/tt-scratch.db"));
+            return;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        std::fs::write(&config_path, "database_path = \"/tmp/tt-from-file.db\"\n").unwrap();
+        assert_eq!(
+            Config::load_from(Some(&config_path)).unwrap().database_path,
+            PathBuf::from("/tmp/tt-from-file.db")
+        );
+
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .arg("--exact")
+            .arg("config::tests::load_from_tt_database_env_overrides_config_file")
+            .arg("--nocapture")
+            .env(CHILD_MARKER, &config_path)
+            .env(DATABASE_ENV, "/tmp/tt-scratch.db")
+            .output()
+            .unwrap();
+
+        assert!(
+            output.status.success(),
+            "child test failed\nstdout:\n{}\nstderr:\n{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
 }
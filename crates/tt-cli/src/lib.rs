@@ -2,11 +2,19 @@
 //!
 //! This crate provides the CLI interface for the time tracker.
 
+pub mod backup;
 mod cli;
 pub mod commands;
 mod config;
 pub mod machine;
+pub mod output;
+pub mod skip_counter;
+pub mod summarize;
 pub mod todo_store;
+pub mod url_project;
 
-pub use cli::{Cli, Commands, IngestEvent, PriorityAction, StreamsAction, TodoAction};
+pub use cli::{
+    Cli, Commands, ConfigAction, EventsAction, IngestEvent, LogFormat, MachineAction,
+    PriorityAction, SessionAction, StreamsAction, TagAction, TodoAction,
+};
 pub use config::{Config, dirs_data_path, dirs_state_path};
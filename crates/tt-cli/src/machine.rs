@@ -9,6 +9,8 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+pub use tt_db::extract_machine_id;
+
 /// Machine identity stored in `machine.json`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MachineIdentity {
@@ -88,19 +90,36 @@ pub(crate) fn init_machine_at(path: &Path, label: Option<&str>) -> Result<Machin
     Ok(identity)
 }
 
-/// Extracts the machine UUID prefix from an event ID.
+/// Replaces the machine UUID with a freshly generated one, keeping the label.
 ///
-/// Event IDs are formatted as `{machine_uuid}:{source}:{type}:{timestamp}:{discriminator}`.
-/// Returns `None` if the ID doesn't start with a valid UUID.
-pub fn extract_machine_id(event_id: &str) -> Option<String> {
-    // UUID v4 is exactly 36 chars: 8-4-4-4-12
-    if event_id.len() > 36 && event_id.as_bytes()[36] == b':' {
-        let candidate = &event_id[..36];
-        if Uuid::parse_str(candidate).is_ok() {
-            return Some(candidate.to_string());
-        }
-    }
-    None
+/// Events already recorded keep the old ID prefix. Fails if no identity exists yet.
+pub fn regenerate_machine() -> Result<MachineIdentity> {
+    regenerate_machine_at(&machine_json_path()?)
+}
+
+fn regenerate_machine_at(path: &Path) -> Result<MachineIdentity> {
+    let mut identity =
+        load_from(path)?.context("No machine identity found. Run 'tt init' first.")?;
+    identity.machine_id = Uuid::new_v4().to_string();
+    save_to(path, &identity)?;
+    Ok(identity)
+}
+
+/// Sets the machine UUID to a specific value, e.g. when migrating from another machine.
+///
+/// Keeps the existing label, or uses the hostname if no identity exists yet.
+pub fn import_machine_id(machine_id: &str) -> Result<MachineIdentity> {
+    import_machine_id_at(&machine_json_path()?, machine_id)
+}
+
+fn import_machine_id_at(path: &Path, machine_id: &str) -> Result<MachineIdentity> {
+    let machine_id = Uuid::parse_str(machine_id)
+        .with_context(|| format!("invalid machine ID '{machine_id}': expected a UUID"))?
+        .to_string();
+    let mut identity = init_machine_at(path, None)?;
+    identity.machine_id = machine_id;
+    save_to(path, &identity)?;
+    Ok(identity)
 }
 
 /// Writes machine identity to a specific path.
@@ -150,6 +169,59 @@ mod tests {
         assert_eq!(second.label, "new-name");
     }
 
+    #[test]
+    fn test_regenerate_mints_new_id_and_keeps_label() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("machine.json");
+
+        let original = init_machine_at(&path, Some("devpod")).unwrap();
+        let regenerated = regenerate_machine_at(&path).unwrap();
+        let loaded = load_from(&path).unwrap().unwrap();
+
+        assert_ne!(regenerated.machine_id, original.machine_id);
+        Uuid::parse_str(&regenerated.machine_id).unwrap();
+        assert_eq!(loaded.machine_id, regenerated.machine_id);
+        assert_eq!(loaded.label, "devpod");
+    }
+
+    #[test]
+    fn test_regenerate_without_identity_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("machine.json");
+
+        let err = regenerate_machine_at(&path).unwrap_err();
+        assert!(err.to_string().contains("tt init"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_import_sets_given_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("machine.json");
+        init_machine_at(&path, Some("laptop")).unwrap();
+
+        let id = "0f8fad5b-d9cb-469f-a165-70867728950e";
+        let imported = import_machine_id_at(&path, &id.to_uppercase()).unwrap();
+        let loaded = load_from(&path).unwrap().unwrap();
+
+        assert_eq!(imported.machine_id, id);
+        assert_eq!(loaded.machine_id, id);
+        assert_eq!(loaded.label, "laptop");
+    }
+
+    #[test]
+    fn test_import_rejects_non_uuid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("machine.json");
+        let original = init_machine_at(&path, Some("laptop")).unwrap();
+
+        assert!(import_machine_id_at(&path, "not-a-uuid").is_err());
+        assert_eq!(
+            load_from(&path).unwrap().unwrap().machine_id,
+            original.machine_id
+        );
+    }
+
     #[test]
     fn test_load_missing_returns_none() {
         let dir = tempfile::tempdir().unwrap();
@@ -2,16 +2,26 @@ use std::path::Path;
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{EnvFilter, fmt};
 
 mod todo_dispatch;
 
 use todo_dispatch::{run_priority_action, run_todo_action};
+use tt_cli::backup::backup_before;
+use tt_cli::commands::util::PROFILE_TARGET;
 use tt_cli::commands::{
-    classify, context, export, import, ingest, init, machines, recompute, report, status, streams,
-    sync, tag,
+    classify, completions, config, context, dump, events, export, heatmap, import, infer, ingest,
+    init, machine, machines, prune, recompute, replay, report, session, status, streams, sync, tag,
+    today, verify,
+};
+use tt_cli::output::OutputCtx;
+use tt_cli::skip_counter::SkipCounter;
+use tt_cli::{
+    Cli, Commands, Config, ConfigAction, EventsAction, IngestEvent, LogFormat, MachineAction,
+    SessionAction, StreamsAction, TagAction, TodoAction,
 };
-use tt_cli::{Cli, Commands, Config, IngestEvent, StreamsAction, TodoAction};
 
 /// Load config and open database, ensuring the parent directory exists.
 fn open_database(config_path: Option<&Path>) -> Result<(tt_db::Database, Config)> {
@@ -32,6 +42,21 @@ fn load_config(config_path: Option<&Path>) -> Result<Config> {
     Ok(config)
 }
 
+/// Parses `--start`/`--end` local dates into a report period; `end` defaults to tomorrow.
+fn custom_period(start: &str, end: Option<&str>) -> Result<report::Period> {
+    let start_date = chrono::NaiveDate::parse_from_str(start, "%Y-%m-%d")
+        .with_context(|| format!("invalid --start date '{start}', expected YYYY-MM-DD"))?;
+    let end_date = match end {
+        Some(end_str) => chrono::NaiveDate::parse_from_str(end_str, "%Y-%m-%d")
+            .with_context(|| format!("invalid --end date '{end_str}', expected YYYY-MM-DD"))?,
+        None => chrono::Local::now().date_naive() + chrono::Duration::days(1),
+    };
+    Ok(report::Period::Custom(
+        report::local_midnight_to_utc(start_date),
+        report::local_midnight_to_utc(end_date),
+    ))
+}
+
 #[expect(
     clippy::too_many_lines,
     reason = "CLI command dispatch is inherently verbose"
@@ -40,14 +65,31 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // Initialize tracing with verbose flag support
-    let filter = if cli.verbose > 0 {
+    let profile = matches!(
+        cli.command,
+        Some(Commands::Recompute { profile: true, .. } | Commands::Report { profile: true, .. })
+    );
+    let mut filter = if cli.verbose > 0 {
         EnvFilter::new("debug")
     } else {
         EnvFilter::from_default_env()
     };
+    if profile {
+        filter = filter.add_directive(format!("{PROFILE_TARGET}=info").parse()?);
+    }
+    let fmt_layer = match cli.log_format {
+        LogFormat::Text => fmt::layer().boxed(),
+        LogFormat::Json => fmt::layer().json().boxed(),
+    };
+    // Counts skip warnings even when the log filter hides them
+    let skips = SkipCounter::default();
     // Use try_init to avoid panic if tracing is already initialized (e.g., in tests)
-    let _ = tracing_subscriber::fmt().with_env_filter(filter).try_init();
+    let _ = tracing_subscriber::registry()
+        .with(fmt_layer.with_filter(filter))
+        .with(skips.clone().with_filter(LevelFilter::WARN))
+        .try_init();
 
+    let ctx = OutputCtx::new(cli.color, cli.quiet);
     match &cli.command {
         Some(Commands::Ingest { event }) => match event {
             IngestEvent::PaneFocus {
@@ -77,76 +119,228 @@ fn main() -> Result<()> {
                 }
             }
             IngestEvent::Sessions => {
-                let (db, _config) = open_database(cli.config.as_deref())?;
-                ingest::index_sessions(&db)?;
+                let (db, config) = open_database(cli.config.as_deref())?;
+                ingest::index_sessions(&db, &config, &ctx)?;
             }
         },
-        Some(Commands::Export { after, since }) => {
+        Some(Commands::Export {
+            after,
+            after_timestamp,
+            since,
+            source,
+            strict,
+            header,
+        }) => {
             // Export doesn't need config - just reads files and outputs to stdout
-            export::run(after.as_deref(), since.as_deref())?;
+            export::run(
+                after.as_deref(),
+                after_timestamp.as_deref(),
+                since.as_deref(),
+                source.as_deref(),
+                *strict,
+                *header,
+            )?;
         }
-        Some(Commands::Import) => {
+        Some(Commands::Import { strict }) => {
             let (db, _config) = open_database(cli.config.as_deref())?;
-            import::run(&db)?;
+            import::run(&db, &ctx, *strict)?;
         }
+        Some(Commands::Dump { anonymize }) => {
+            let (db, _config) = open_database(cli.config.as_deref())?;
+            dump::run(&db, *anonymize, &ctx)?;
+        }
+        Some(Commands::Replay { file }) => replay::run(file)?,
         Some(Commands::Status) => {
             let (db, config) = open_database(cli.config.as_deref())?;
-            status::run(&db, &config.database_path)?;
+            status::run(&db, &config.database_path, &ctx)?;
+        }
+        Some(Commands::Recompute { force, profile: _ }) => {
+            let (db, config) = open_database(cli.config.as_deref())?;
+            if *force {
+                backup_before(&db, "recompute")?;
+            }
+            recompute::run(&db, *force, &config.allocation, &ctx)?;
+        }
+        Some(Commands::Infer { project }) => {
+            let (db, config) = open_database(cli.config.as_deref())?;
+            infer::run(&db, &config, project.as_deref(), cli.verbose > 0, &ctx)?;
         }
-        Some(Commands::Recompute { force }) => {
+        Some(Commands::Prune { before }) => {
             let (db, _config) = open_database(cli.config.as_deref())?;
-            recompute::run(&db, *force)?;
+            backup_before(&db, "prune")?;
+            prune::run(&db, before, &ctx)?;
         }
         Some(Commands::Report {
             week: _,
             last_week,
             day,
             last_day,
+            month,
+            last_month,
+            quarter,
+            last_quarter,
             weeks,
             start,
             end,
             json,
+            jsonl,
+            tree,
+            daily,
+            by_project,
+            by_category,
+            agent_leverage,
+            budgets,
+            include_tags,
+            exclude_tags,
+            only_direct,
+            only_delegated,
+            no_carry_open,
+            exclude_weekends,
+            width,
+            no_truncate,
+            top,
+            profile: _,
         }) => {
-            let (db, _config) = open_database(cli.config.as_deref())?;
+            let (db, config) = open_database(cli.config.as_deref())?;
             let period = if let Some(start_str) = start {
-                let start_date = chrono::NaiveDate::parse_from_str(start_str, "%Y-%m-%d")
-                    .with_context(|| {
-                        format!("invalid --start date '{start_str}', expected YYYY-MM-DD")
-                    })?;
-                let end_date = match end {
-                    Some(end_str) => chrono::NaiveDate::parse_from_str(end_str, "%Y-%m-%d")
-                        .with_context(|| {
-                            format!("invalid --end date '{end_str}', expected YYYY-MM-DD")
-                        })?,
-                    None => chrono::Local::now().date_naive() + chrono::Duration::days(1),
-                };
-                report::Period::Custom(
-                    report::local_midnight_to_utc(start_date),
-                    report::local_midnight_to_utc(end_date),
-                )
+                custom_period(start_str, end.as_deref())?
             } else if *last_week {
                 report::Period::LastWeek
             } else if *day {
                 report::Period::Day
             } else if *last_day {
                 report::Period::LastDay
+            } else if *month {
+                report::Period::Month
+            } else if *last_month {
+                report::Period::LastMonth
+            } else if *quarter {
+                report::Period::Quarter
+            } else if *last_quarter {
+                report::Period::LastQuarter
             } else {
                 report::Period::Week
             };
-            report::run(&db, period, *json, *weeks)?;
+            let options = report::ReportOptions {
+                json: *json,
+                weeks: *weeks,
+                tree: *tree,
+                daily: *daily,
+                include_tags: include_tags.clone(),
+                exclude_tags: exclude_tags.clone(),
+                dimension: if *only_direct {
+                    report::TimeDimension::Direct
+                } else if *only_delegated {
+                    report::TimeDimension::Delegated
+                } else {
+                    report::TimeDimension::Both
+                },
+                by_project: *by_project,
+                by_category: *by_category,
+                exclude_weekends: *exclude_weekends,
+                jsonl: *jsonl,
+                agent_leverage: *agent_leverage,
+                width: if *no_truncate {
+                    report::TableWidth::Unlimited
+                } else {
+                    width.map_or(report::TableWidth::Auto, |width| {
+                        report::TableWidth::Fixed(width.into())
+                    })
+                },
+                top: top.map(usize::from),
+                allocation: tt_core::AllocationConfig {
+                    carry_open_sessions: config.allocation.carry_open_sessions && !*no_carry_open,
+                    ..config.allocation
+                },
+                budgets: budgets.clone(),
+            };
+            report::run(&db, period, &options, &ctx)?;
+        }
+        Some(Commands::Heatmap { start, end }) => {
+            let (db, config) = open_database(cli.config.as_deref())?;
+            let period = custom_period(start, end.as_deref())?;
+            heatmap::run(&db, &config.allocation, period, &ctx)?;
+        }
+        Some(Commands::Month { last }) => {
+            let (db, config) = open_database(cli.config.as_deref())?;
+            let period = if *last {
+                report::Period::LastMonth
+            } else {
+                report::Period::Month
+            };
+            let options = report::ReportOptions {
+                allocation: config.allocation,
+                ..report::ReportOptions::default()
+            };
+            report::run(&db, period, &options, &ctx)?;
+        }
+        Some(Commands::Today { watch, interval }) => {
+            let (db, config) = open_database(cli.config.as_deref())?;
+            today::run(
+                &db,
+                &config.allocation,
+                *watch,
+                std::time::Duration::from_secs(*interval),
+                &ctx,
+            )?;
         }
         Some(Commands::Tag {
             stream,
             tag: tag_name,
+            action,
         }) => {
             let (db, _config) = open_database(cli.config.as_deref())?;
-            tag::run(&db, stream, tag_name)?;
+            match (action, stream, tag_name) {
+                (Some(TagAction::Merge { from, to }), _, _) => tag::merge(&db, from, to, &ctx)?,
+                (None, Some(stream), Some(tag_name)) => tag::run(&db, stream, tag_name, &ctx)?,
+                // clap requires both positionals without a subcommand
+                (None, _, _) => unreachable!("tt tag requires a stream and a tag"),
+            }
         }
         Some(Commands::Streams(action)) => {
             let (db, config) = open_database(cli.config.as_deref())?;
             match action {
-                StreamsAction::List { json } => streams::run(&db, *json)?,
+                StreamsAction::List {
+                    json,
+                    page,
+                    page_size,
+                    week,
+                    today,
+                    start,
+                    end,
+                    sort,
+                    reverse,
+                } => {
+                    let period = if let Some(start_str) = start {
+                        Some(custom_period(start_str, end.as_deref())?)
+                    } else if *today {
+                        Some(report::Period::Day)
+                    } else if *week {
+                        Some(report::Period::Week)
+                    } else {
+                        None
+                    };
+                    streams::run(
+                        &db,
+                        *page,
+                        *page_size,
+                        period,
+                        *sort,
+                        *reverse,
+                        &ctx.with_json(*json),
+                    )?;
+                }
+                StreamsAction::Show { stream, json } => streams::show(&db, stream, *json)?,
                 StreamsAction::Create { name } => streams::create(&db, name.clone())?,
+                StreamsAction::Categorize { stream, category } => {
+                    streams::categorize(&db, stream, category, &ctx)?;
+                }
+                StreamsAction::Untag { stream, tags } => {
+                    streams::untag(&db, stream, tags, &ctx)?;
+                }
+                StreamsAction::Retag { stream, old, new } => {
+                    streams::retag(&db, stream, old, new, &ctx)?;
+                }
                 StreamsAction::Link { stream, priority } => {
                     streams::link(
                         &db,
@@ -159,6 +353,37 @@ fn main() -> Result<()> {
                 }
             }
         }
+        Some(Commands::Config(action)) => match action {
+            ConfigAction::Path => config::path(cli.config.as_deref())?,
+            ConfigAction::Show => config::show(&load_config(cli.config.as_deref())?)?,
+        },
+        Some(Commands::Events {
+            after,
+            before,
+            action,
+            ..
+        }) => {
+            let (db, _config) = open_database(cli.config.as_deref())?;
+            match action {
+                Some(EventsAction::Reassign { event, stream }) => {
+                    events::reassign(&db, event, stream, &ctx)?;
+                }
+                // Without a subcommand, clap only accepts `--count-only`
+                None => events::count(&db, after.as_deref(), before.as_deref())?,
+            }
+        }
+        Some(Commands::Session(action)) => {
+            let (db, _config) = open_database(cli.config.as_deref())?;
+            match action {
+                SessionAction::Assign { session, stream } => {
+                    session::assign(&db, session, stream, &ctx)?;
+                }
+            }
+        }
+        Some(Commands::Verify { delete_dupes, .. }) => {
+            let (db, _config) = open_database(cli.config.as_deref())?;
+            verify::run_dupes(&db, *delete_dupes)?;
+        }
         Some(Commands::Todo(action)) => {
             if matches!(action, TodoAction::Drift { .. }) {
                 let (db, config) = open_database(cli.config.as_deref())?;
@@ -172,16 +397,28 @@ fn main() -> Result<()> {
             let config = load_config(cli.config.as_deref())?;
             run_priority_action(&config, action)?;
         }
-        Some(Commands::Init { label }) => {
-            init::run(label.as_deref())?;
+        Some(Commands::Init { label, write }) => {
+            init::run(label.as_deref(), *write, &ctx)?;
+        }
+        Some(Commands::Completions { shell }) => {
+            completions::run(*shell)?;
         }
-        Some(Commands::Machines) => {
+        Some(Commands::Complete { kind }) => {
             let (db, _config) = open_database(cli.config.as_deref())?;
-            machines::run(&db)?;
+            completions::run_complete(&db, *kind)?;
         }
-        Some(Commands::Sync { remotes }) => {
+        Some(Commands::Machine(action)) => match action {
+            MachineAction::Show => machine::show()?,
+            MachineAction::Regenerate { yes } => machine::regenerate(*yes, &ctx)?,
+            MachineAction::Import { id } => machine::import(id, &ctx)?,
+        },
+        Some(Commands::Machines { json }) => {
             let (db, _config) = open_database(cli.config.as_deref())?;
-            sync::run(&db, remotes)?;
+            machines::run(&db, &ctx.with_json(*json))?;
+        }
+        Some(Commands::Sync { remotes }) => {
+            let (db, config) = open_database(cli.config.as_deref())?;
+            sync::run(&db, &config, remotes, &ctx)?;
         }
         Some(Commands::Context {
             events,
@@ -217,10 +454,18 @@ fn main() -> Result<()> {
             end,
             gaps,
             gap_threshold,
+            sample,
         }) => {
-            let (db, _config) = open_database(cli.config.as_deref())?;
+            let (db, config) = open_database(cli.config.as_deref())?;
             if let Some(input_path) = apply {
-                classify::run_apply(&db, input_path)?;
+                backup_before(&db, "classify")?;
+                classify::run_apply(
+                    &db,
+                    &config.allocation,
+                    input_path,
+                    cli.verbose > 0,
+                    &ctx.with_json(*json),
+                )?;
             } else {
                 classify::run_show(
                     &db,
@@ -231,6 +476,7 @@ fn main() -> Result<()> {
                     end.clone(),
                     *gaps,
                     *gap_threshold,
+                    *sample,
                 )?;
             }
         }
@@ -242,5 +488,21 @@ fn main() -> Result<()> {
         }
     }
 
+    if matches!(
+        cli.command,
+        Some(
+            Commands::Import { .. }
+                | Commands::Export { .. }
+                | Commands::Report { .. }
+                | Commands::Month { .. }
+                | Commands::Replay { .. }
+                | Commands::Dump { .. }
+        )
+    ) {
+        if let Some(summary) = skips.summary() {
+            ctx.info_stderr(format_args!("{summary}"));
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,83 @@
+//! Output styling shared by all commands.
+//!
+//! [`OutputCtx`] is built once in `main.rs` from the global `--color` and
+//! `--quiet` flags and passed to command `run` functions, so every command
+//! colors and stays silent the same way. Errors and warnings are never
+//! suppressed; `--quiet` only drops informational progress and confirmations.
+
+use std::fmt;
+
+/// When to emit ANSI color codes in human-readable output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and `NO_COLOR` is unset.
+    #[default]
+    Auto,
+    /// Always color.
+    Always,
+    /// Never color.
+    Never,
+}
+
+impl ColorChoice {
+    /// Resolves the choice against the environment.
+    ///
+    /// `auto` follows the `NO_COLOR` convention (any non-empty value disables color)
+    /// and disables color when stdout is not a terminal.
+    pub fn enabled(self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+                !no_color && std::io::IsTerminal::is_terminal(&std::io::stdout())
+            }
+        }
+    }
+}
+
+/// Resolved output settings for one command invocation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputCtx {
+    /// Whether human-readable output may contain ANSI color codes.
+    pub color: bool,
+    /// Suppress informational (non-error) output.
+    pub quiet: bool,
+    /// The command prints machine-readable JSON on stdout.
+    pub json: bool,
+}
+
+impl OutputCtx {
+    /// Builds the context from the global flags, resolving `color` once.
+    pub fn new(color: ColorChoice, quiet: bool) -> Self {
+        Self {
+            color: color.enabled(),
+            quiet,
+            json: false,
+        }
+    }
+
+    /// Marks the output as JSON, which is never colored.
+    #[must_use]
+    pub const fn with_json(self, json: bool) -> Self {
+        Self {
+            color: self.color && !json,
+            json,
+            ..self
+        }
+    }
+
+    /// Prints an informational line to stdout unless quiet.
+    pub fn info(&self, message: fmt::Arguments<'_>) {
+        if !self.quiet {
+            println!("{message}");
+        }
+    }
+
+    /// Prints an informational line to stderr unless quiet, keeping stdout for data.
+    pub fn info_stderr(&self, message: fmt::Arguments<'_>) {
+        if !self.quiet {
+            eprintln!("{message}");
+        }
+    }
+}
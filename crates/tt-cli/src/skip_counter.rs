@@ -0,0 +1,107 @@
+//! Tally of records skipped because of malformed data.
+//!
+//! The DB layer and importers log one warning per skipped record, tagged with
+//! a `skipped = true` field (e.g. `warn!(skipped = true, ..., "skipping event
+//! with malformed timestamp")`). In a large run those scroll past unnoticed,
+//! so [`SkipCounter`] counts every warning carrying that field and commands
+//! print the total at the end. Other warnings never count, even if their
+//! message mentions skipping.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Tracing layer counting warn-level `skipped = true` events. Clones share one count.
+#[derive(Debug, Clone, Default)]
+pub struct SkipCounter {
+    skipped: Arc<AtomicUsize>,
+}
+
+impl SkipCounter {
+    /// Returns the number of skip warnings seen so far.
+    pub fn count(&self) -> usize {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the end-of-command summary line, or `None` if nothing was skipped.
+    pub fn summary(&self) -> Option<String> {
+        match self.count() {
+            0 => None,
+            1 => Some("1 event skipped due to malformed data".to_string()),
+            n => Some(format!("{n} events skipped due to malformed data")),
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for SkipCounter {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if *event.metadata().level() != Level::WARN {
+            return;
+        }
+        let mut visitor = SkipVisitor::default();
+        event.record(&mut visitor);
+        if visitor.skipped {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Checks whether an event carries `skipped = true`.
+#[derive(Default)]
+struct SkipVisitor {
+    skipped: bool,
+}
+
+impl Visit for SkipVisitor {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if field.name() == "skipped" {
+            self.skipped |= value;
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use tracing_subscriber::layer::SubscriberExt;
+    use tt_db::Database;
+
+    use super::*;
+    use crate::commands::import::import_from_reader;
+
+    #[test]
+    fn test_counts_malformed_import_lines() {
+        let counter = SkipCounter::default();
+        let subscriber = tracing_subscriber::registry().with(counter.clone());
+        let db = Database::open_in_memory().unwrap();
+        let input = concat!(
+            r#"{"id":"e1","timestamp":"2025-01-29T12:00:00Z","source":"remote.tmux","type":"tmux_pane_focus","data":{}}"#,
+            "\nnot json\n{\"id\":\n[1, 2]\n",
+        );
+
+        let result = tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!("unrelated warning");
+            tracing::warn!("invalid last_sync_at format, skipping --since");
+            import_from_reader(&db, Cursor::new(input)).unwrap()
+        });
+
+        assert_eq!(result.malformed, 3);
+        assert_eq!(counter.count(), 3);
+        assert_eq!(
+            counter.summary().as_deref(),
+            Some("3 events skipped due to malformed data")
+        );
+    }
+
+    #[test]
+    fn test_no_summary_without_skips() {
+        assert_eq!(SkipCounter::default().summary(), None);
+    }
+}
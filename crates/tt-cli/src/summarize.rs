@@ -0,0 +1,212 @@
+//! Compact event summaries for LLM prompts.
+//!
+//! Commands that describe activity to a model (classify, suggest, ask) need the
+//! same few facts from each event: which tool ran, on which file, in which
+//! directory. [`SummarizeEvent::from_stored`] is the one place that mapping
+//! lives, so every prompt sees events the same way.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tt_core::EventType;
+use tt_db::StoredEvent;
+
+/// What an event tells a model about the work being done.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SummarizeEvent {
+    pub timestamp: DateTime<Utc>,
+    pub event_type: EventType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Tool name, for `agent_tool_use` events (e.g. `Edit`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    /// File the tool touched, for `agent_tool_use` events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+}
+
+impl SummarizeEvent {
+    /// Builds a summary, or `None` for events that say nothing about the work.
+    ///
+    /// Scrolls, AFK changes, and window/browser focus carry no tool, file, or
+    /// directory, and neither do other events missing all three.
+    pub fn from_stored(event: &StoredEvent) -> Option<Self> {
+        let data_str = |key: &str| {
+            event
+                .data
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(ToString::to_string)
+        };
+        let (tool, file) = match event.event_type {
+            EventType::AgentToolUse => (data_str("tool"), data_str("file")),
+            EventType::AgentSession | EventType::UserMessage | EventType::TmuxPaneFocus => {
+                (None, None)
+            }
+            EventType::TmuxScroll
+            | EventType::AfkChange
+            | EventType::WindowFocus
+            | EventType::BrowserTab => return None,
+        };
+        let cwd = event.cwd.clone().or_else(|| data_str("cwd"));
+        if tool.is_none() && file.is_none() && cwd.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            timestamp: event.timestamp,
+            event_type: event.event_type,
+            session_id: event.session_id.clone(),
+            tool,
+            file,
+            cwd,
+        })
+    }
+}
+
+/// Picks at most `n` representative events, keeping timestamp order.
+///
+/// Events are split into `n` consecutive chunks so the sample spans the whole
+/// timeline; each chunk contributes its first event touching a file (or, for
+/// events without one, a directory) not already in the sample, falling back
+/// to the chunk's first event. There is no randomness, so repeated runs over
+/// the same events produce the same sample (and the same prompt).
+pub fn sample_events(events: &[SummarizeEvent], n: usize) -> Vec<SummarizeEvent> {
+    if events.len() <= n {
+        return events.to_vec();
+    }
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut sample = Vec::with_capacity(n);
+    for chunk in 0..n {
+        let chunk = &events[chunk * events.len() / n..(chunk + 1) * events.len() / n];
+        let pick = chunk
+            .iter()
+            .find(|event| event.diversity_key().is_some_and(|key| !seen.contains(key)))
+            .unwrap_or(&chunk[0]);
+        if let Some(key) = pick.diversity_key() {
+            seen.insert(key);
+        }
+        sample.push(pick.clone());
+    }
+    sample
+}
+
+impl SummarizeEvent {
+    /// What sampling treats as "the same place": the file, else the directory.
+    fn diversity_key(&self) -> Option<&str> {
+        self.file.as_deref().or(self.cwd.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+
+    fn make_event(
+        event_type: EventType,
+        cwd: Option<&str>,
+        data: serde_json::Value,
+    ) -> StoredEvent {
+        StoredEvent {
+            id: "e1".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2025, 1, 29, 12, 0, 0).unwrap(),
+            event_type,
+            source: "remote.agent".to_string(),
+            machine_id: None,
+            schema_version: 1,
+            pane_id: None,
+            tmux_session: None,
+            window_index: None,
+            git_project: None,
+            git_workspace: None,
+            status: None,
+            idle_duration_ms: None,
+            window_app_id: None,
+            window_title: None,
+            url: None,
+            action: None,
+            cwd: cwd.map(ToString::to_string),
+            session_id: Some("sess1".to_string()),
+            stream_id: None,
+            assignment_source: None,
+            data,
+        }
+    }
+
+    #[test]
+    fn test_tool_use_extracts_tool_file_and_cwd() {
+        let event = make_event(
+            EventType::AgentToolUse,
+            Some("/home/user/project"),
+            json!({"tool": "Edit", "file": "/home/user/project/src/main.rs"}),
+        );
+
+        let summary = SummarizeEvent::from_stored(&event).unwrap();
+
+        assert_eq!(summary.tool.as_deref(), Some("Edit"));
+        assert_eq!(
+            summary.file.as_deref(),
+            Some("/home/user/project/src/main.rs")
+        );
+        assert_eq!(summary.cwd.as_deref(), Some("/home/user/project"));
+        assert_eq!(summary.session_id.as_deref(), Some("sess1"));
+    }
+
+    #[test]
+    fn test_pane_focus_keeps_cwd_only() {
+        let event = make_event(
+            EventType::TmuxPaneFocus,
+            None,
+            json!({"pane_id": "%1", "cwd": "/home/user/project", "tool": "ignored"}),
+        );
+
+        let summary = SummarizeEvent::from_stored(&event).unwrap();
+
+        assert_eq!(summary.cwd.as_deref(), Some("/home/user/project"));
+        assert_eq!(summary.tool, None);
+        assert_eq!(summary.file, None);
+    }
+
+    #[test]
+    fn test_non_informative_events_are_dropped() {
+        let scroll = make_event(EventType::TmuxScroll, Some("/home/user"), json!({}));
+        let bare_focus = make_event(EventType::TmuxPaneFocus, None, json!({"pane_id": "%1"}));
+
+        assert_eq!(SummarizeEvent::from_stored(&scroll), None);
+        assert_eq!(SummarizeEvent::from_stored(&bare_focus), None);
+    }
+
+    #[test]
+    fn test_sample_spans_timeline_and_prefers_distinct_files() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 29, 12, 0, 0).unwrap();
+        // 100 edits cycling through 5 files, then 20 edits of a sixth file at the end.
+        let events: Vec<SummarizeEvent> = (0..120)
+            .map(|i| {
+                let file = if i < 100 { i % 5 } else { 5 };
+                let mut event = make_event(
+                    EventType::AgentToolUse,
+                    Some("/home/user/project"),
+                    json!({"tool": "Edit", "file": format!("src/file{file}.rs")}),
+                );
+                event.timestamp = start + chrono::Duration::minutes(i);
+                SummarizeEvent::from_stored(&event).unwrap()
+            })
+            .collect();
+
+        let sample = sample_events(&events, 10);
+
+        assert_eq!(sample.len(), 10);
+        let distinct: HashSet<_> = sample.iter().filter_map(|e| e.file.as_deref()).collect();
+        assert_eq!(distinct.len(), 6, "{distinct:?}");
+        assert!(sample.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+        assert_eq!(sample, sample_events(&events, 10));
+        assert_eq!(sample_events(&events[..3], 10), events[..3]);
+    }
+}
@@ -0,0 +1,164 @@
+//! Mapping from browser URLs to project names.
+//!
+//! Rules come from `url_project_map` in the config file and are tried in order;
+//! the first match wins. A rule matches either an exact host (`domain`) or a
+//! regular expression against the full URL (`regex`):
+//!
+//! ```toml
+//! [[url_project_map]]
+//! domain = "docs.acme.dev"
+//! project = "acme-docs"
+//!
+//! [[url_project_map]]
+//! regex = "github\\.com/acme/webapp"
+//! project = "acme-webapp"
+//! ```
+
+use anyhow::{Context, Result, bail};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// One `url_project_map` entry as written in the config file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlProjectRule {
+    /// Exact host to match (case-insensitive), e.g. `docs.acme.dev`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+
+    /// Regular expression matched against the full URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+
+    /// Project the URL belongs to; matched against stream names.
+    pub project: String,
+}
+
+#[derive(Debug)]
+enum Matcher {
+    Domain(String),
+    Regex(Regex),
+}
+
+/// Compiled `url_project_map` rules.
+#[derive(Debug, Default)]
+pub struct UrlProjectMap {
+    rules: Vec<(Matcher, String)>,
+}
+
+impl UrlProjectMap {
+    /// Compiles config rules. Each rule must set exactly one of `domain` or `regex`.
+    pub fn new(rules: &[UrlProjectRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let matcher = match (&rule.domain, &rule.regex) {
+                    (Some(domain), None) => Matcher::Domain(domain.to_ascii_lowercase()),
+                    (None, Some(pattern)) => Matcher::Regex(
+                        Regex::new(pattern)
+                            .with_context(|| format!("invalid url_project_map regex '{pattern}'"))?,
+                    ),
+                    _ => bail!(
+                        "url_project_map entry for '{}' must set exactly one of 'domain' or 'regex'",
+                        rule.project
+                    ),
+                };
+                Ok((matcher, rule.project.clone()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Returns true if no rules are configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the project for a URL, if any rule matches.
+    pub fn project_for(&self, url: &str) -> Option<&str> {
+        let host = url_host(url).map(str::to_ascii_lowercase);
+        self.rules
+            .iter()
+            .find(|(matcher, _)| match matcher {
+                Matcher::Domain(domain) => host.as_deref() == Some(domain.as_str()),
+                Matcher::Regex(regex) => regex.is_match(url),
+            })
+            .map(|(_, project)| project.as_str())
+    }
+}
+
+/// Extracts the host from a URL (`https://user@host:8080/path` → `host`).
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = host_port.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domain_rule(domain: &str, project: &str) -> UrlProjectRule {
+        UrlProjectRule {
+            domain: Some(domain.to_string()),
+            regex: None,
+            project: project.to_string(),
+        }
+    }
+
+    fn regex_rule(regex: &str, project: &str) -> UrlProjectRule {
+        UrlProjectRule {
+            domain: None,
+            regex: Some(regex.to_string()),
+            project: project.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_exact_domain_match() {
+        let map = UrlProjectMap::new(&[domain_rule("docs.acme.dev", "acme-docs")]).unwrap();
+
+        assert_eq!(
+            map.project_for("https://docs.acme.dev/guide?page=2"),
+            Some("acme-docs")
+        );
+        assert_eq!(
+            map.project_for("https://user@Docs.Acme.Dev:8443/"),
+            Some("acme-docs")
+        );
+        assert_eq!(map.project_for("https://blog.docs.acme.dev/"), None);
+        assert_eq!(map.project_for("https://acme.dev/docs.acme.dev"), None);
+    }
+
+    #[test]
+    fn test_regex_match_against_full_url() {
+        let map = UrlProjectMap::new(&[
+            regex_rule(r"github\.com/acme/webapp(/|$)", "acme-webapp"),
+            domain_rule("github.com", "github-misc"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            map.project_for("https://github.com/acme/webapp/pull/42"),
+            Some("acme-webapp")
+        );
+        assert_eq!(
+            map.project_for("https://github.com/acme/other"),
+            Some("github-misc")
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let both = UrlProjectRule {
+            domain: Some("a.dev".to_string()),
+            regex: Some("a".to_string()),
+            project: "p".to_string(),
+        };
+        assert!(UrlProjectMap::new(&[both]).is_err());
+        assert!(UrlProjectMap::new(&[regex_rule("(", "p")]).is_err());
+    }
+}
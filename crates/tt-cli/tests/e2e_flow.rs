@@ -189,6 +189,47 @@ fn test_export_incremental() {
     );
 }
 
+/// Test `export --strict` fails on malformed events.jsonl lines instead of skipping them.
+#[test]
+fn test_export_strict_rejects_malformed_lines() {
+    let temp = TempDir::new().unwrap();
+    init_machine(temp.path());
+    let data_dir = temp.path().join(".local/share/time-tracker");
+    std::fs::create_dir_all(&data_dir).unwrap();
+    std::fs::write(
+        data_dir.join("events.jsonl"),
+        "{\"id\":\"1\",\"source\":\"remote.tmux\"}\n{truncated\n",
+    )
+    .unwrap();
+
+    let lenient = Command::new(tt_binary())
+        .env("HOME", temp.path())
+        .env_remove("CLAUDE_CONFIG_DIR")
+        .arg("export")
+        .output()
+        .unwrap();
+    assert!(lenient.status.success());
+    assert_eq!(String::from_utf8_lossy(&lenient.stdout).lines().count(), 1);
+
+    let strict = Command::new(tt_binary())
+        .env("HOME", temp.path())
+        .env_remove("CLAUDE_CONFIG_DIR")
+        .arg("export")
+        .arg("--strict")
+        .output()
+        .unwrap();
+    assert_eq!(strict.status.code(), Some(1));
+    assert!(
+        strict.stdout.is_empty(),
+        "strict export should emit nothing"
+    );
+    let stderr = String::from_utf8_lossy(&strict.stderr);
+    assert!(
+        stderr.contains("1 malformed line(s)") && stderr.contains(": 2"),
+        "unexpected stderr: {stderr}"
+    );
+}
+
 /// Test that import handles invalid JSON gracefully.
 #[test]
 fn test_import_invalid_json() {
@@ -689,6 +730,7 @@ fn test_delegated_time_from_agent_session_events() {
                 idle_duration_ms: None,
                 window_app_id: None,
                 window_title: None,
+                url: None,
                 action: None,
                 cwd: Some("/project".to_string()),
                 session_id: Some(session_id.clone()),
@@ -0,0 +1,119 @@
+//! Integration tests for the global `--quiet` flag.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use tempfile::TempDir;
+
+fn tt_binary() -> String {
+    env!("CARGO_BIN_EXE_tt").to_string()
+}
+
+/// Writes a config pointing at a database inside `temp` and returns its path.
+fn write_config(temp: &std::path::Path) -> std::path::PathBuf {
+    let config_file = temp.join("config.toml");
+    std::fs::write(
+        &config_file,
+        format!(r#"database_path = "{}""#, temp.join("tt.db").display()),
+    )
+    .unwrap();
+    config_file
+}
+
+/// Pipes `input` into `tt [args] import` and returns its output.
+fn import(temp: &std::path::Path, args: &[&str], input: &str) -> std::process::Output {
+    let config_file = write_config(temp);
+
+    let mut child = Command::new(tt_binary())
+        .env("HOME", temp)
+        .arg("--config")
+        .arg(&config_file)
+        .args(args)
+        .arg("import")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_quiet_init_prints_nothing() {
+    let temp = TempDir::new().unwrap();
+
+    let loud = Command::new(tt_binary())
+        .env("HOME", temp.path())
+        .arg("init")
+        .output()
+        .unwrap();
+    assert!(loud.status.success());
+    assert!(String::from_utf8_lossy(&loud.stdout).contains("Machine ID"));
+
+    let quiet = Command::new(tt_binary())
+        .env("HOME", temp.path())
+        .args(["--quiet", "init"])
+        .output()
+        .unwrap();
+    assert!(quiet.status.success());
+    assert!(
+        quiet.stdout.is_empty(),
+        "unexpected stdout: {}",
+        String::from_utf8_lossy(&quiet.stdout)
+    );
+}
+
+#[test]
+fn test_quiet_import_drops_summary_but_keeps_importing() {
+    let event = r#"{"id":"e1","timestamp":"2025-01-29T12:00:00Z","source":"test","type":"tmux_pane_focus","data":{}}"#;
+
+    let loud_dir = TempDir::new().unwrap();
+    let loud = import(loud_dir.path(), &[], event);
+    assert!(loud.status.success());
+    let stderr = String::from_utf8_lossy(&loud.stderr);
+    assert!(stderr.contains("Imported"), "unexpected stderr: {stderr}");
+
+    let quiet_dir = TempDir::new().unwrap();
+    let quiet = import(quiet_dir.path(), &["-q"], event);
+    assert!(quiet.status.success());
+    assert!(quiet.stdout.is_empty());
+    assert!(
+        quiet.stderr.is_empty(),
+        "unexpected stderr: {}",
+        String::from_utf8_lossy(&quiet.stderr)
+    );
+}
+
+#[test]
+fn test_quiet_prune_drops_confirmation() {
+    let temp = TempDir::new().unwrap();
+    let config_file = write_config(temp.path());
+    let prune = |args: &[&str]| {
+        Command::new(tt_binary())
+            .env("HOME", temp.path())
+            .arg("--config")
+            .arg(&config_file)
+            .args(args)
+            .args(["prune", "--before", "2025-01-01T00:00:00Z"])
+            .output()
+            .unwrap()
+    };
+
+    let loud = prune(&[]);
+    assert!(loud.status.success());
+    assert!(String::from_utf8_lossy(&loud.stdout).contains("Pruned 0 agent session(s)"));
+
+    let quiet = prune(&["--quiet"]);
+    assert!(quiet.status.success());
+    assert!(
+        quiet.stdout.is_empty(),
+        "unexpected stdout: {}",
+        String::from_utf8_lossy(&quiet.stdout)
+    );
+}
@@ -221,6 +221,7 @@ fn event(spec: EventSpec<'_>) -> StoredEvent {
         idle_duration_ms: None,
         window_app_id: None,
         window_title: None,
+        url: None,
         action: spec.action.map(ToString::to_string),
         cwd: Some("/tmp/project".to_string()),
         session_id: spec.session_id.map(ToString::to_string),
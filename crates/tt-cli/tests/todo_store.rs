@@ -4,12 +4,15 @@ use std::path::{Path, PathBuf};
 use tempfile::TempDir;
 use tt_cli::Config;
 use tt_cli::todo_store::{preflight_sync_conflicts, store_dir};
+use tt_core::AllocationConfig;
 
 #[test]
 fn store_dir_returns_configured_todo_store_path() {
     let config = Config {
         database_path: PathBuf::from("/tmp/tt.db"),
         todo_store_path: PathBuf::from("/tmp/todos"),
+        url_project_map: Vec::new(),
+        allocation: AllocationConfig::default(),
     };
 
     assert_eq!(store_dir(&config), Path::new("/tmp/todos"));
@@ -9,9 +9,10 @@
 //! 2. Build agent activity timeline from `agent_session` and `agent_tool_use` events
 //! 3. Iterate through event intervals, attributing time based on state
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::{EventType, SessionType};
 
@@ -21,7 +22,11 @@ use crate::{EventType, SessionType};
 const UNASSIGNED_STREAM_ID: &str = "(unassigned)";
 
 /// Configuration for time allocation.
-#[derive(Debug, Clone)]
+///
+/// Deserializes from the config file's `[allocation]` section; missing fields
+/// take their [`Default`] values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AllocationConfig {
     /// Grace period after last focus event before direct time pauses.
     /// Default: 300000 (5 minutes).
@@ -31,6 +36,61 @@ pub struct AllocationConfig {
     /// assume session crashed. Session ends at last tool use timestamp.
     /// Default: 1800000 (30 minutes).
     pub agent_timeout_ms: i64,
+
+    /// Per-agent overrides of `agent_timeout_ms`, keyed by the `agent` value in
+    /// `agent_session` event data (e.g. `"opencode"`). Agents without an entry
+    /// use `agent_timeout_ms`. Default: empty.
+    pub agent_timeout_overrides_ms: HashMap<String, i64>,
+
+    /// Additional app-name substrings (case-insensitive) recognized as terminals,
+    /// extending the built-in list. Default: empty.
+    pub extra_terminal_apps: Vec<String>,
+
+    /// Additional app-name substrings (case-insensitive) recognized as browsers,
+    /// extending the built-in list. Default: empty.
+    pub extra_browser_apps: Vec<String>,
+
+    /// Event types skipped entirely during allocation, for comparing totals
+    /// with and without a signal source. Default: empty.
+    pub ignored_event_types: HashSet<EventType>,
+
+    /// Treat any focus gap longer than `attention_window_ms` as idle, for machines
+    /// without an AFK detector. Focus ends at the end of the attention window, so
+    /// a later scroll cannot revive it and the gap never counts as tracked.
+    /// Default: false.
+    pub synthesize_idle_from_gaps: bool,
+
+    /// A `user_message` within this long of the previous one in the same session is
+    /// treated as a duplicate capture of the same message and ignored. 0 disables;
+    /// 2000 (2 seconds) catches a log parser and a hook capturing the same message.
+    /// Default: 0.
+    pub user_message_dedup_window_ms: i64,
+
+    /// An `afk_change` idle event whose `idle_duration_ms` is below this is a brief
+    /// pause (stepping away to think) and does not break focus. Idle events without
+    /// a duration always break focus. 0 disables. Default: 0.
+    pub min_idle_to_break_ms: i64,
+
+    /// Count an active agent session as supervision: while a session is between
+    /// tool uses it keeps (or, when unfocused, establishes) direct focus on its
+    /// stream, ending one attention window after the last tool use. It never
+    /// takes focus from another stream or revives focus after idle.
+    /// Default: false.
+    pub agent_activity_implies_focus: bool,
+
+    /// Agent sessions whose span (first to last tool use) is shorter than this
+    /// are treated as noise and contribute no delegated time. 0 disables.
+    /// Default: 0.
+    pub min_session_ms: i64,
+
+    /// Attribute delegated time to agent sessions still open at the period end, up
+    /// to the period end (capped by the agent timeout). When false, open sessions
+    /// end at their last tool use, giving a "committed so far" view.
+    ///
+    /// A session counts as open when it has no known end time or the known end
+    /// falls after the period end; a known end within the period wins in both
+    /// modes. Default: true.
+    pub carry_open_sessions: bool,
 }
 
 impl Default for AllocationConfig {
@@ -38,10 +98,90 @@ impl Default for AllocationConfig {
         Self {
             attention_window_ms: 300_000, // 5 minutes
             agent_timeout_ms: 1_800_000,  // 30 minutes
+            agent_timeout_overrides_ms: HashMap::new(),
+            extra_terminal_apps: Vec::new(),
+            extra_browser_apps: Vec::new(),
+            ignored_event_types: HashSet::new(),
+            synthesize_idle_from_gaps: false,
+            user_message_dedup_window_ms: 0,
+            min_idle_to_break_ms: 0,
+            agent_activity_implies_focus: false,
+            min_session_ms: 0,
+            carry_open_sessions: true,
         }
     }
 }
 
+impl AllocationConfig {
+    /// Checks that every window and timeout is usable.
+    ///
+    /// `attention_window_ms`, `agent_timeout_ms` and each timeout override must be
+    /// positive; `user_message_dedup_window_ms`, `min_idle_to_break_ms` and
+    /// `min_session_ms` must not be negative (0 disables them).
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the first invalid field and its value.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.attention_window_ms <= 0 {
+            return Err(format!(
+                "attention_window_ms must be positive, got {}",
+                self.attention_window_ms
+            ));
+        }
+        if self.agent_timeout_ms <= 0 {
+            return Err(format!(
+                "agent_timeout_ms must be positive, got {}",
+                self.agent_timeout_ms
+            ));
+        }
+        let mut overrides: Vec<_> = self.agent_timeout_overrides_ms.iter().collect();
+        overrides.sort();
+        if let Some((agent, timeout)) = overrides.into_iter().find(|(_, timeout)| **timeout <= 0) {
+            return Err(format!(
+                "agent_timeout_overrides_ms.{agent} must be positive, got {timeout}"
+            ));
+        }
+        if self.user_message_dedup_window_ms < 0 {
+            return Err(format!(
+                "user_message_dedup_window_ms must not be negative, got {}",
+                self.user_message_dedup_window_ms
+            ));
+        }
+        if self.min_idle_to_break_ms < 0 {
+            return Err(format!(
+                "min_idle_to_break_ms must not be negative, got {}",
+                self.min_idle_to_break_ms
+            ));
+        }
+        if self.min_session_ms < 0 {
+            return Err(format!(
+                "min_session_ms must not be negative, got {}",
+                self.min_session_ms
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the session timeout for an agent, falling back to `agent_timeout_ms`.
+    fn agent_timeout_for(&self, agent: Option<&str>) -> i64 {
+        agent
+            .and_then(|agent| self.agent_timeout_overrides_ms.get(agent))
+            .copied()
+            .unwrap_or(self.agent_timeout_ms)
+    }
+
+    /// Returns true if the app is a built-in or configured terminal.
+    fn is_terminal_app(&self, app: &str) -> bool {
+        is_terminal_app(app) || matches_any(app, &self.extra_terminal_apps)
+    }
+
+    /// Returns true if the app is a built-in or configured browser.
+    fn is_browser_app(&self, app: &str) -> bool {
+        is_browser_app(app) || matches_any(app, &self.extra_browser_apps)
+    }
+}
+
 /// Computed time for a single stream.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StreamTime {
@@ -55,12 +195,31 @@ pub struct StreamTime {
     pub time_delegated_ms: i64,
 }
 
+/// A span of time attributed to one stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActivityInterval {
+    /// The stream the time went to; `None` for unassigned activity.
+    pub stream_id: Option<String>,
+
+    /// Start of the span.
+    pub start: DateTime<Utc>,
+
+    /// End of the span (exclusive).
+    pub end: DateTime<Utc>,
+
+    /// Agent execution time rather than human attention.
+    pub delegated: bool,
+}
+
 /// Result of time allocation calculation.
 #[derive(Debug, Clone)]
 pub struct AllocationResult {
     /// Time computed per stream.
     pub stream_times: Vec<StreamTime>,
 
+    /// Every span behind `stream_times`, in allocation order. Spans may overlap.
+    pub activity: Vec<ActivityInterval>,
+
     /// Total wall-clock time with any activity (union of intervals, not sum).
     pub total_tracked_ms: i64,
 
@@ -71,6 +230,110 @@ pub struct AllocationResult {
     pub unassigned_delegated_ms: i64,
 }
 
+impl AllocationResult {
+    /// Buckets per-stream time into tags, returning `(direct_ms, delegated_ms)` per tag.
+    ///
+    /// **Multi-tag attribution**: a stream with several tags counts its FULL time
+    /// toward EACH tag, so the per-tag sums can exceed the stream totals. Use
+    /// [`Self::aggregate_by_tag_split_evenly`] when tag totals must add up.
+    /// Untagged streams are skipped.
+    #[allow(clippy::implicit_hasher)]
+    pub fn aggregate_by_tag(
+        &self,
+        tags: &HashMap<String, Vec<String>>,
+    ) -> HashMap<String, (i64, i64)> {
+        self.bucket_by_tag(tags, false)
+    }
+
+    /// Like [`Self::aggregate_by_tag`], but divides a multi-tagged stream's time
+    /// evenly between its tags, so per-tag sums equal the tagged streams' totals.
+    ///
+    /// Remainder milliseconds go to the stream's first tags.
+    #[allow(clippy::implicit_hasher)]
+    pub fn aggregate_by_tag_split_evenly(
+        &self,
+        tags: &HashMap<String, Vec<String>>,
+    ) -> HashMap<String, (i64, i64)> {
+        self.bucket_by_tag(tags, true)
+    }
+
+    /// Restricts the result to `[start, end)`, rebuilding every total from the parts
+    /// of its activity spans that fall inside.
+    ///
+    /// Allocating from before `start` and clipping keeps activity already running at
+    /// `start` without counting any of its earlier share. Streams left with no time
+    /// are dropped.
+    pub fn clip_to(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) {
+        self.activity = self
+            .activity
+            .drain(..)
+            .filter_map(|span| {
+                let clipped = ActivityInterval {
+                    start: span.start.max(start),
+                    end: span.end.min(end),
+                    ..span
+                };
+                (clipped.end > clipped.start).then_some(clipped)
+            })
+            .collect();
+
+        let mut times: HashMap<Option<&str>, (i64, i64)> = HashMap::new();
+        for span in &self.activity {
+            let (direct, delegated) = times.entry(span.stream_id.as_deref()).or_insert((0, 0));
+            let duration_ms = (span.end - span.start).num_milliseconds();
+            if span.delegated {
+                *delegated += duration_ms;
+            } else {
+                *direct += duration_ms;
+            }
+        }
+        (self.unassigned_direct_ms, self.unassigned_delegated_ms) =
+            times.get(&None).copied().unwrap_or((0, 0));
+        self.stream_times.retain_mut(|time| {
+            let (direct, delegated) = times
+                .get(&Some(time.stream_id.as_str()))
+                .copied()
+                .unwrap_or((0, 0));
+            time.time_direct_ms = direct;
+            time.time_delegated_ms = delegated;
+            direct > 0 || delegated > 0
+        });
+        self.total_tracked_ms = calculate_total_tracked(&self.activity);
+    }
+
+    fn bucket_by_tag(
+        &self,
+        tags: &HashMap<String, Vec<String>>,
+        split_evenly: bool,
+    ) -> HashMap<String, (i64, i64)> {
+        let mut by_tag: HashMap<String, (i64, i64)> = HashMap::new();
+        for stream in &self.stream_times {
+            let Some(stream_tags) = tags.get(&stream.stream_id) else {
+                continue;
+            };
+            let Ok(count) = i64::try_from(stream_tags.len()) else {
+                continue;
+            };
+            if count == 0 {
+                continue;
+            }
+            for (index, tag) in (0_i64..).zip(stream_tags) {
+                let share = |total: i64| {
+                    if split_evenly {
+                        total / count + i64::from(index < total % count)
+                    } else {
+                        total
+                    }
+                };
+                let (direct, delegated) = by_tag.entry(tag.clone()).or_insert((0, 0));
+                *direct += share(stream.time_direct_ms);
+                *delegated += share(stream.time_delegated_ms);
+            }
+        }
+        by_tag
+    }
+}
+
 /// An event suitable for time allocation.
 ///
 /// This trait allows allocation to work with different event representations
@@ -104,8 +367,11 @@ enum FocusState {
         /// When focus started or last activity occurred
         focus_start: DateTime<Utc>,
     },
-    /// No active focus (AFK or no focus events yet).
+    /// No active focus (no focus events yet, or a window without a stream).
     Unfocused,
+    /// User went idle (AFK, or a synthesized idle gap). Unlike `Unfocused`, a
+    /// scroll cannot establish focus; it takes a focus event or AFK "active".
+    Idle,
 }
 
 /// Current window focus state.
@@ -138,6 +404,20 @@ struct AgentSession {
 
     /// Whether the session has ended.
     ended: bool,
+
+    /// Inactivity timeout for this session's agent.
+    timeout_ms: i64,
+}
+
+impl AgentSession {
+    /// Whether the tool-use span is below `min_session_ms`, so the session
+    /// contributes no delegated time.
+    fn is_below_min_span(&self, min_session_ms: i64) -> bool {
+        let (Some(first), Some(last)) = (self.first_tool_use_at, self.last_tool_use_at) else {
+            return false;
+        };
+        (last - first).num_milliseconds() < min_session_ms
+    }
 }
 
 /// An activity interval for tracking total time.
@@ -187,20 +467,27 @@ pub fn allocate_time<E: AllocatableEvent>(
     let mut tmux_focus_stream_id: Option<String> = None;
     let mut agent_sessions: HashMap<String, AgentSession> = HashMap::new();
     let mut stream_times: HashMap<String, (i64, i64)> = HashMap::new(); // (direct_ms, delegated_ms)
-    let mut activity_intervals: Vec<Interval> = Vec::new();
+    let mut activity_intervals: Vec<ActivityInterval> = Vec::new();
     let mut last_event_time: Option<DateTime<Utc>> = None;
+    // Last counted user_message per session, for duplicate suppression
+    let mut last_user_message_at: HashMap<String, DateTime<Utc>> = HashMap::new();
 
     // Helper to add direct time
     let add_direct = |stream_id: &str,
                       start: DateTime<Utc>,
                       end: DateTime<Utc>,
-                      intervals: &mut Vec<Interval>,
+                      intervals: &mut Vec<ActivityInterval>,
                       times: &mut HashMap<String, (i64, i64)>| {
         if end > start {
             let duration_ms = (end - start).num_milliseconds();
             let (direct, _) = times.entry(stream_id.to_string()).or_insert((0, 0));
             *direct += duration_ms;
-            intervals.push(Interval { start, end });
+            intervals.push(ActivityInterval {
+                stream_id: (stream_id != UNASSIGNED_STREAM_ID).then(|| stream_id.to_string()),
+                start,
+                end,
+                delegated: false,
+            });
         }
     };
 
@@ -208,19 +495,27 @@ pub fn allocate_time<E: AllocatableEvent>(
     let add_delegated = |stream_id: &str,
                          start: DateTime<Utc>,
                          end: DateTime<Utc>,
-                         intervals: &mut Vec<Interval>,
+                         intervals: &mut Vec<ActivityInterval>,
                          times: &mut HashMap<String, (i64, i64)>| {
         if end > start {
             let duration_ms = (end - start).num_milliseconds();
             let (_, delegated) = times.entry(stream_id.to_string()).or_insert((0, 0));
             *delegated += duration_ms;
-            intervals.push(Interval { start, end });
+            intervals.push(ActivityInterval {
+                stream_id: (stream_id != UNASSIGNED_STREAM_ID).then(|| stream_id.to_string()),
+                start,
+                end,
+                delegated: true,
+            });
         }
     };
 
     for event in events {
-        let event_time = event.timestamp();
         let event_type = event.event_type();
+        if config.ignored_event_types.contains(&event_type) {
+            continue;
+        }
+        let event_time = event.timestamp();
         let data = event.data();
 
         // Check for agent timeouts before processing this event.
@@ -246,7 +541,7 @@ pub fn allocate_time<E: AllocatableEvent>(
                         None
                     }
                 } else {
-                    let timeout_at = last_tool + Duration::milliseconds(config.agent_timeout_ms);
+                    let timeout_at = last_tool + Duration::milliseconds(session.timeout_ms);
                     if event_time > timeout_at {
                         Some((
                             session_id.clone(),
@@ -262,20 +557,50 @@ pub fn allocate_time<E: AllocatableEvent>(
             .collect();
 
         for (session_id, stream_id, first_tool, timeout_at) in timeout_attributions {
-            // Attribute delegated time from first tool use to timeout
-            add_delegated(
-                &stream_id,
-                first_tool,
-                timeout_at,
-                &mut activity_intervals,
-                &mut stream_times,
-            );
+            // Attribute delegated time from first tool use to timeout, unless
+            // the session is too short to count
+            let is_noise = agent_sessions
+                .get(&session_id)
+                .is_some_and(|session| session.is_below_min_span(config.min_session_ms));
+            if !is_noise {
+                add_delegated(
+                    &stream_id,
+                    first_tool,
+                    timeout_at,
+                    &mut activity_intervals,
+                    &mut stream_times,
+                );
+            }
             // Mark session as ended
             if let Some(session) = agent_sessions.get_mut(&session_id) {
                 session.ended = true;
             }
         }
 
+        // Synthetic idle boundary: close focus once the gap exceeds the attention window.
+        if config.synthesize_idle_from_gaps {
+            if let FocusState::Focused { focus_start, .. } = &focus_state {
+                let idle_at = *focus_start + Duration::milliseconds(config.attention_window_ms);
+                if event_time > idle_at {
+                    if let Some(resolved_stream) = resolve_focus_stream(
+                        config,
+                        &window_focus_state,
+                        tmux_focus_stream_id.as_deref(),
+                        browser_focus_state.stream_id.as_deref(),
+                    ) {
+                        add_direct(
+                            &resolved_stream,
+                            *focus_start,
+                            idle_at,
+                            &mut activity_intervals,
+                            &mut stream_times,
+                        );
+                    }
+                    focus_state = FocusState::Idle;
+                }
+            }
+        }
+
         match event_type {
             EventType::TmuxPaneFocus => {
                 let stream_id = event.stream_id().unwrap_or(UNASSIGNED_STREAM_ID);
@@ -283,6 +608,7 @@ pub fn allocate_time<E: AllocatableEvent>(
                     // Close previous focus interval using resolved stream
                     if let FocusState::Focused { focus_start, .. } = &focus_state {
                         let resolved = resolve_focus_stream(
+                            config,
                             &window_focus_state,
                             tmux_focus_stream_id.as_deref(),
                             browser_focus_state.stream_id.as_deref(),
@@ -313,11 +639,14 @@ pub fn allocate_time<E: AllocatableEvent>(
 
             EventType::AfkChange => {
                 let status = data.get("status").and_then(|v| v.as_str()).unwrap_or("");
-                if status == "idle" {
+                let idle_duration_ms = data
+                    .get("idle_duration_ms")
+                    .and_then(serde_json::Value::as_i64);
+                let brief_pause =
+                    idle_duration_ms.is_some_and(|ms| ms < config.min_idle_to_break_ms);
+                if status == "idle" && !brief_pause {
                     // Check for retroactive idle duration
-                    let idle_start = data
-                        .get("idle_duration_ms")
-                        .and_then(serde_json::Value::as_i64)
+                    let idle_start = idle_duration_ms
                         .filter(|&ms| ms > 0)
                         .map_or(event_time, |ms| event_time - Duration::milliseconds(ms));
 
@@ -326,6 +655,7 @@ pub fn allocate_time<E: AllocatableEvent>(
                         let end_time = idle_start.max(*focus_start); // Don't go before focus started
                         if end_time > *focus_start {
                             let resolved = resolve_focus_stream(
+                                config,
                                 &window_focus_state,
                                 tmux_focus_stream_id.as_deref(),
                                 browser_focus_state.stream_id.as_deref(),
@@ -344,9 +674,12 @@ pub fn allocate_time<E: AllocatableEvent>(
                             }
                         }
                     }
+                    focus_state = FocusState::Idle;
+                } else if status == "active" && matches!(focus_state, FocusState::Idle) {
+                    // "active" does NOT restore focus - wait for the next focus event
+                    // (or a scroll, which can establish focus once no longer idle)
                     focus_state = FocusState::Unfocused;
                 }
-                // Note: "active" does NOT restore focus - wait for next focus event
             }
 
             EventType::TmuxScroll => {
@@ -359,6 +692,7 @@ pub fn allocate_time<E: AllocatableEvent>(
                 {
                     // Resolve which stream should actually get the time
                     let resolved = resolve_focus_stream(
+                        config,
                         &window_focus_state,
                         tmux_focus_stream_id.as_deref(),
                         browser_focus_state.stream_id.as_deref(),
@@ -391,6 +725,19 @@ pub fn allocate_time<E: AllocatableEvent>(
                         }
                     }
                     // If event is for a different stream, ignore it - doesn't affect focus state
+                } else if let (FocusState::Unfocused, Some(stream_id)) =
+                    (&focus_state, event.stream_id())
+                {
+                    // No focus yet (e.g. the pane's focus event was debounced away):
+                    // a scroll tagged to a stream establishes focus like a lightweight
+                    // pane focus. Unassigned scrolls carry no stream to focus.
+                    tmux_focus_stream_id = Some(stream_id.to_string());
+                    window_focus_state.app = None;
+                    window_focus_state.stream_id = None;
+                    focus_state = FocusState::Focused {
+                        stream_id: stream_id.to_string(),
+                        focus_start: event_time,
+                    };
                 }
             }
 
@@ -407,11 +754,22 @@ pub fn allocate_time<E: AllocatableEvent>(
                 if is_subagent_message {
                     continue;
                 }
+                if let Some(session_id) = event.session_id() {
+                    let window = Duration::milliseconds(config.user_message_dedup_window_ms);
+                    let is_duplicate = last_user_message_at
+                        .get(session_id)
+                        .is_some_and(|&previous| event_time - previous < window);
+                    if is_duplicate {
+                        continue;
+                    }
+                    last_user_message_at.insert(session_id.to_string(), event_time);
+                }
                 let stream_id = event.stream_id().unwrap_or(UNASSIGNED_STREAM_ID);
                 {
                     // Close previous focus interval
                     if let FocusState::Focused { focus_start, .. } = &focus_state {
                         let resolved = resolve_focus_stream(
+                            config,
                             &window_focus_state,
                             tmux_focus_stream_id.as_deref(),
                             browser_focus_state.stream_id.as_deref(),
@@ -447,6 +805,7 @@ pub fn allocate_time<E: AllocatableEvent>(
                 match action {
                     "started" => {
                         let stream_id = event.stream_id().unwrap_or(UNASSIGNED_STREAM_ID);
+                        let agent = data.get("agent").and_then(|v| v.as_str());
                         {
                             agent_sessions.insert(
                                 session_id.to_string(),
@@ -455,6 +814,7 @@ pub fn allocate_time<E: AllocatableEvent>(
                                     first_tool_use_at: None,
                                     last_tool_use_at: None,
                                     ended: false,
+                                    timeout_ms: config.agent_timeout_for(agent),
                                 },
                             );
                         }
@@ -462,7 +822,7 @@ pub fn allocate_time<E: AllocatableEvent>(
                     "ended" => {
                         // Close the session
                         if let Some(session) = agent_sessions.get(session_id) {
-                            if !session.ended {
+                            if !session.ended && !session.is_below_min_span(config.min_session_ms) {
                                 if let Some(first_tool) = session.first_tool_use_at {
                                     // Attribute from first tool use to end
                                     add_delegated(
@@ -485,15 +845,85 @@ pub fn allocate_time<E: AllocatableEvent>(
 
             EventType::AgentToolUse => {
                 let session_id = event.session_id().unwrap_or("");
+                // The start event may have been pruned or lost; open the session here
+                // so its delegated time is not dropped.
+                if !session_id.is_empty() && !agent_sessions.contains_key(session_id) {
+                    let agent = data.get("agent").and_then(|v| v.as_str());
+                    agent_sessions.insert(
+                        session_id.to_string(),
+                        AgentSession {
+                            stream_id: event
+                                .stream_id()
+                                .unwrap_or(UNASSIGNED_STREAM_ID)
+                                .to_string(),
+                            first_tool_use_at: None,
+                            last_tool_use_at: None,
+                            ended: false,
+                            timeout_ms: config.agent_timeout_for(agent),
+                        },
+                    );
+                }
+                // (stream, previous tool use) of the session, if still active
+                let mut active_session = None;
                 if let Some(session) = agent_sessions.get_mut(session_id) {
                     if !session.ended {
                         if session.first_tool_use_at.is_none() {
                             // First tool use - delegated time starts here
                             session.first_tool_use_at = Some(event_time);
                         }
+                        active_session =
+                            Some((session.stream_id.clone(), session.last_tool_use_at));
                         session.last_tool_use_at = Some(event_time);
                     }
                 }
+
+                if config.agent_activity_implies_focus {
+                    if let Some((stream_id, previous_tool_use)) = active_session {
+                        let resolved = resolve_focus_stream(
+                            config,
+                            &window_focus_state,
+                            tmux_focus_stream_id.as_deref(),
+                            browser_focus_state.stream_id.as_deref(),
+                        );
+                        match &focus_state {
+                            FocusState::Focused { focus_start, .. }
+                                if resolved.as_deref() == Some(stream_id.as_str()) =>
+                            {
+                                // Focus held since this session's previous tool use
+                                // spans the whole gap; other focus is capped as usual.
+                                let actual_end = if previous_tool_use == Some(*focus_start) {
+                                    event_time
+                                } else {
+                                    event_time.min(
+                                        *focus_start
+                                            + Duration::milliseconds(config.attention_window_ms),
+                                    )
+                                };
+                                add_direct(
+                                    &stream_id,
+                                    *focus_start,
+                                    actual_end,
+                                    &mut activity_intervals,
+                                    &mut stream_times,
+                                );
+                                focus_state = FocusState::Focused {
+                                    stream_id,
+                                    focus_start: event_time,
+                                };
+                            }
+                            FocusState::Unfocused => {
+                                tmux_focus_stream_id = Some(stream_id.clone());
+                                window_focus_state.app = None;
+                                window_focus_state.stream_id = None;
+                                focus_state = FocusState::Focused {
+                                    stream_id,
+                                    focus_start: event_time,
+                                };
+                            }
+                            FocusState::Focused { .. } | FocusState::Idle => {}
+                        }
+                    }
+                }
             }
 
             EventType::WindowFocus => {
@@ -504,6 +934,7 @@ pub fn allocate_time<E: AllocatableEvent>(
 
                 if let FocusState::Focused { focus_start, .. } = &focus_state {
                     let resolved = resolve_focus_stream(
+                        config,
                         &window_focus_state,
                         tmux_focus_stream_id.as_deref(),
                         browser_focus_state.stream_id.as_deref(),
@@ -526,6 +957,7 @@ pub fn allocate_time<E: AllocatableEvent>(
                 window_focus_state.stream_id = event.stream_id().map(String::from);
 
                 if let Some(stream_id) = resolve_focus_stream(
+                    config,
                     &window_focus_state,
                     tmux_focus_stream_id.as_deref(),
                     browser_focus_state.stream_id.as_deref(),
@@ -544,13 +976,14 @@ pub fn allocate_time<E: AllocatableEvent>(
                 if window_focus_state
                     .app
                     .as_ref()
-                    .is_some_and(|app| is_browser_app(app))
+                    .is_some_and(|app| config.is_browser_app(app))
                 {
                     let stream_id = event.stream_id().unwrap_or(UNASSIGNED_STREAM_ID);
                     {
                         // Close previous focus interval
                         if let FocusState::Focused { focus_start, .. } = &focus_state {
                             let resolved = resolve_focus_stream(
+                                config,
                                 &window_focus_state,
                                 tmux_focus_stream_id.as_deref(),
                                 browser_focus_state.stream_id.as_deref(),
@@ -595,6 +1028,7 @@ pub fn allocate_time<E: AllocatableEvent>(
         // Close focus - cap at attention window, using resolved stream
         if let FocusState::Focused { focus_start, .. } = &focus_state {
             let resolved = resolve_focus_stream(
+                config,
                 &window_focus_state,
                 tmux_focus_stream_id.as_deref(),
                 browser_focus_state.stream_id.as_deref(),
@@ -619,17 +1053,24 @@ pub fn allocate_time<E: AllocatableEvent>(
         let final_attributions: Vec<_> = agent_sessions
             .iter()
             .filter(|(_, session)| !session.ended)
+            .filter(|(_, session)| !session.is_below_min_span(config.min_session_ms))
             .filter_map(|(session_id, session)| {
                 let first_tool = session.first_tool_use_at?;
                 let last_tool = session.last_tool_use_at.unwrap_or(first_tool);
 
-                let session_end = if let Some(&known_end) = session_end_times.get(session_id) {
-                    // Use known end_time, capped at period end
-                    known_end.min(end)
-                } else {
+                let known_end = session_end_times.get(session_id).copied();
+                let session_end = match known_end {
+                    // Ended within the period, or carried: use known end_time, capped at period end
+                    Some(known_end) if config.carry_open_sessions || known_end <= end => {
+                        known_end.min(end)
+                    }
+                    // Open at the period end and not carried: nothing beyond the last tool use
+                    _ if !config.carry_open_sessions => last_tool.min(end),
                     // Timeout heuristic: last_tool + timeout, capped at period end
-                    let timeout_at = last_tool + Duration::milliseconds(config.agent_timeout_ms);
-                    if end > timeout_at { timeout_at } else { end }
+                    _ => {
+                        let timeout_at = last_tool + Duration::milliseconds(session.timeout_ms);
+                        if end > timeout_at { timeout_at } else { end }
+                    }
                 };
 
                 Some((session.stream_id.clone(), first_tool, session_end))
@@ -666,6 +1107,7 @@ pub fn allocate_time<E: AllocatableEvent>(
 
     AllocationResult {
         stream_times: stream_times_vec,
+        activity: activity_intervals,
         total_tracked_ms,
         unassigned_direct_ms,
         unassigned_delegated_ms,
@@ -673,7 +1115,7 @@ pub fn allocate_time<E: AllocatableEvent>(
 }
 
 /// Calculate total tracked time from interval union.
-fn calculate_total_tracked(intervals: &[Interval]) -> i64 {
+fn calculate_total_tracked(intervals: &[ActivityInterval]) -> i64 {
     if intervals.is_empty() {
         return 0;
     }
@@ -682,7 +1124,10 @@ fn calculate_total_tracked(intervals: &[Interval]) -> i64 {
     let mut sorted: Vec<Interval> = intervals
         .iter()
         .filter(|i| i.end > i.start)
-        .copied()
+        .map(|i| Interval {
+            start: i.start,
+            end: i.end,
+        })
         .collect();
     if sorted.is_empty() {
         return 0;
@@ -719,6 +1164,14 @@ fn is_terminal_app(app: &str) -> bool {
         || app_lower.contains("ghostty")
 }
 
+/// Returns true if `app` contains any of `needles`, ignoring ASCII case.
+fn matches_any(app: &str, needles: &[String]) -> bool {
+    let app_lower = app.to_ascii_lowercase();
+    needles
+        .iter()
+        .any(|needle| app_lower.contains(&needle.to_ascii_lowercase()))
+}
+
 /// Returns true if the app name indicates a browser application.
 fn is_browser_app(app: &str) -> bool {
     let app_lower = app.to_ascii_lowercase();
@@ -735,28 +1188,24 @@ fn is_browser_app(app: &str) -> bool {
 /// Hierarchy:
 /// - If window is a terminal app -> use tmux focus stream
 /// - If window is a browser app -> browser tab stream, else the window's own stream,
-///   else the UNASSIGNED bucket (active GUI time is never dropped to nothing)
-/// - Otherwise (non-terminal GUI) -> the window's own stream, else UNASSIGNED
+///   else the UNASSIGNED bucket (active browser time is never dropped to nothing)
+/// - Otherwise (non-terminal GUI) -> the window's own stream, else `None`: focusing
+///   an app like Slack with no stream is focus loss, so the time is not tracked
 fn resolve_focus_stream(
+    config: &AllocationConfig,
     window_state: &WindowFocusState,
     tmux_stream_id: Option<&str>,
     browser_stream_id: Option<&str>,
 ) -> Option<String> {
     match &window_state.app {
-        Some(app) if is_terminal_app(app) => tmux_stream_id.map(String::from),
-        Some(app) if is_browser_app(app) => Some(
+        Some(app) if config.is_terminal_app(app) => tmux_stream_id.map(String::from),
+        Some(app) if config.is_browser_app(app) => Some(
             browser_stream_id
                 .or(window_state.stream_id.as_deref())
                 .unwrap_or(UNASSIGNED_STREAM_ID)
                 .to_string(),
         ),
-        Some(_) => Some(
-            window_state
-                .stream_id
-                .as_deref()
-                .unwrap_or(UNASSIGNED_STREAM_ID)
-                .to_string(),
-        ),
+        Some(_) => window_state.stream_id.clone(),
         None => tmux_stream_id.map(String::from), // Fallback to tmux if no window info
     }
 }
@@ -764,175 +1213,17 @@ fn resolve_focus_stream(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::TimeZone;
+    use crate::test_util::{TestEvent, get_stream_time, ts};
     use serde_json::json;
 
     fn test_config() -> AllocationConfig {
         AllocationConfig {
             attention_window_ms: 60_000,
             agent_timeout_ms: 1_800_000,
+            ..Default::default()
         }
     }
 
-    /// Test event implementation.
-    struct TestEvent {
-        timestamp: DateTime<Utc>,
-        event_type: EventType,
-        stream_id: Option<String>,
-        session_id: Option<String>,
-        action: Option<String>,
-        data: serde_json::Value,
-    }
-
-    impl TestEvent {
-        fn tmux_focus(ts: DateTime<Utc>, stream_id: &str) -> Self {
-            Self {
-                timestamp: ts,
-                event_type: EventType::TmuxPaneFocus,
-                stream_id: Some(stream_id.to_string()),
-                session_id: None,
-                action: None,
-                data: json!({"pane_id": "%1", "cwd": "/test"}),
-            }
-        }
-
-        fn afk_change(ts: DateTime<Utc>, status: &str) -> Self {
-            Self {
-                timestamp: ts,
-                event_type: EventType::AfkChange,
-                stream_id: None,
-                session_id: None,
-                action: None,
-                data: json!({"status": status}),
-            }
-        }
-
-        fn tmux_scroll(ts: DateTime<Utc>, stream_id: &str) -> Self {
-            Self {
-                timestamp: ts,
-                event_type: EventType::TmuxScroll,
-                stream_id: Some(stream_id.to_string()),
-                session_id: None,
-                action: None,
-                data: json!({"direction": "up"}),
-            }
-        }
-
-        fn agent_session(
-            ts: DateTime<Utc>,
-            action: &str,
-            session_id: &str,
-            stream_id: Option<&str>,
-        ) -> Self {
-            Self {
-                timestamp: ts,
-                event_type: EventType::AgentSession,
-                stream_id: stream_id.map(String::from),
-                session_id: Some(session_id.to_string()),
-                action: Some(action.to_string()),
-                data: json!({"agent": "claude-code"}),
-            }
-        }
-
-        fn agent_tool_use(ts: DateTime<Utc>, session_id: &str, stream_id: &str) -> Self {
-            Self {
-                timestamp: ts,
-                event_type: EventType::AgentToolUse,
-                stream_id: Some(stream_id.to_string()),
-                session_id: Some(session_id.to_string()),
-                action: None,
-                data: json!({"tool": "Edit"}),
-            }
-        }
-
-        fn user_message(ts: DateTime<Utc>, session_id: &str, stream_id: &str) -> Self {
-            Self {
-                timestamp: ts,
-                event_type: EventType::UserMessage,
-                stream_id: Some(stream_id.to_string()),
-                session_id: Some(session_id.to_string()),
-                action: None,
-                data: json!({"length": 100}),
-            }
-        }
-
-        fn window_focus(ts: DateTime<Utc>, app: &str, stream_id: Option<&str>) -> Self {
-            Self {
-                timestamp: ts,
-                event_type: EventType::WindowFocus,
-                stream_id: stream_id.map(String::from),
-                session_id: None,
-                action: None,
-                data: json!({"app": app, "title": "test window"}),
-            }
-        }
-
-        fn browser_tab(ts: DateTime<Utc>, stream_id: &str) -> Self {
-            Self {
-                timestamp: ts,
-                event_type: EventType::BrowserTab,
-                stream_id: Some(stream_id.to_string()),
-                session_id: None,
-                action: None,
-                data: json!({"url": "https://example.com", "title": "Test Page"}),
-            }
-        }
-
-        fn afk_with_duration(ts: DateTime<Utc>, status: &str, idle_duration_ms: i64) -> Self {
-            Self {
-                timestamp: ts,
-                event_type: EventType::AfkChange,
-                stream_id: None,
-                session_id: None,
-                action: None,
-                data: json!({"status": status, "idle_duration_ms": idle_duration_ms}),
-            }
-        }
-    }
-
-    impl AllocatableEvent for TestEvent {
-        fn timestamp(&self) -> DateTime<Utc> {
-            self.timestamp
-        }
-
-        fn event_type(&self) -> EventType {
-            self.event_type
-        }
-
-        fn stream_id(&self) -> Option<&str> {
-            self.stream_id.as_deref()
-        }
-
-        fn session_id(&self) -> Option<&str> {
-            self.session_id.as_deref()
-        }
-
-        fn action(&self) -> Option<&str> {
-            self.action.as_deref()
-        }
-
-        fn data(&self) -> &serde_json::Value {
-            &self.data
-        }
-    }
-
-    fn ts(minutes: i64) -> DateTime<Utc> {
-        Utc.with_ymd_and_hms(2025, 1, 15, 9, 0, 0)
-            .single()
-            .expect("valid test timestamp")
-            + Duration::minutes(minutes)
-    }
-
-    fn get_stream_time<'a>(
-        result: &'a AllocationResult,
-        stream_id: &str,
-    ) -> Option<&'a StreamTime> {
-        result
-            .stream_times
-            .iter()
-            .find(|s| s.stream_id == stream_id)
-    }
-
     // Test 1: Single stream, continuous focus
     #[test]
     fn test_single_stream_continuous_focus() {
@@ -1064,47 +1355,277 @@ mod tests {
             TestEvent::agent_session(ts(30), "ended", "sess1", Some("A")),
         ];
 
-        let config = test_config();
+        let config = test_config();
+        let result = allocate_time(
+            &events,
+            &config,
+            Some(ts(30)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        // No tool use = no delegated time
+        let stream_a = get_stream_time(&result, "A");
+        assert!(stream_a.is_none() || stream_a.unwrap().time_delegated_ms == 0);
+    }
+
+    // Test: tool use whose session start was pruned still counts as delegated time
+    #[test]
+    fn test_agent_tool_use_without_session_start() {
+        let events = vec![
+            TestEvent::agent_tool_use(ts(5), "sess1", "A"),
+            TestEvent::agent_tool_use(ts(15), "sess1", "A"),
+            TestEvent::agent_session(ts(30), "ended", "sess1", Some("A")),
+        ];
+
+        let config = test_config();
+        let result = allocate_time(
+            &events,
+            &config,
+            Some(ts(30)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
+        // Delegated: from first tool use (5) to end (30) = 25 minutes
+        assert_eq!(stream_a.time_delegated_ms, 25 * 60 * 1000);
+        assert_eq!(stream_a.time_direct_ms, 0);
+    }
+
+    // Test 7: Agent timeout (crashed session)
+    #[test]
+    fn test_agent_timeout() {
+        let events = vec![
+            TestEvent::agent_session(ts(0), "started", "sess1", Some("A")),
+            TestEvent::agent_tool_use(ts(5), "sess1", "A"),
+            // No end event, next event at 60min (35min after last tool use)
+            TestEvent::tmux_focus(ts(60), "B"),
+        ];
+
+        let config = AllocationConfig {
+            agent_timeout_ms: 30 * 60 * 1000, // 30 minutes
+            ..Default::default()
+        };
+
+        let result = allocate_time(
+            &events,
+            &config,
+            Some(ts(60)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
+        // Session times out at 5 + 30 = 35 minutes
+        // Delegated: from 5 to 35 = 30 minutes
+        assert_eq!(stream_a.time_delegated_ms, 30 * 60 * 1000);
+    }
+
+    #[test]
+    fn test_agent_timeout_overrides_apply_per_agent() {
+        let mut opencode_start = TestEvent::agent_session(ts(0), "started", "oc", Some("B"));
+        opencode_start.data = json!({"agent": "opencode"});
+        let events = vec![
+            TestEvent::agent_session(ts(0), "started", "cc", Some("A")),
+            opencode_start,
+            TestEvent::agent_tool_use(ts(5), "cc", "A"),
+            TestEvent::agent_tool_use(ts(5), "oc", "B"),
+            // 45-minute gap: past the global 30-minute timeout, within OpenCode's 60.
+            TestEvent::agent_tool_use(ts(50), "cc", "A"),
+            TestEvent::agent_tool_use(ts(50), "oc", "B"),
+            TestEvent::tmux_focus(ts(120), "C"),
+        ];
+
+        let config = AllocationConfig {
+            agent_timeout_ms: 30 * 60 * 1000,
+            agent_timeout_overrides_ms: HashMap::from([("opencode".to_string(), 60 * 60 * 1000)]),
+            ..Default::default()
+        };
+
+        let result = allocate_time(
+            &events,
+            &config,
+            Some(ts(120)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        // Claude times out at 5 + 30 = 35; the tool use at 50 no longer counts.
+        let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
+        assert_eq!(stream_a.time_delegated_ms, 30 * 60 * 1000);
+
+        // OpenCode survives the gap and times out at 50 + 60 = 110.
+        let stream_b = get_stream_time(&result, "B").expect("Stream B should exist");
+        assert_eq!(stream_b.time_delegated_ms, 105 * 60 * 1000);
+    }
+
+    #[test]
+    fn test_ignored_event_types_are_skipped() {
+        let events = vec![
+            TestEvent::window_focus(ts(0), "firefox", Some("P")),
+            TestEvent::browser_tab(ts(0), "B"),
+            TestEvent::window_focus(ts(1), "slack", Some("S")),
+        ];
+        let allocate = |config: &AllocationConfig| {
+            allocate_time(
+                &events,
+                config,
+                Some(ts(2)),
+                &HashMap::new(),
+                &HashMap::new(),
+            )
+        };
+        let direct_ms = |result: &AllocationResult, stream_id: &str| {
+            get_stream_time(result, stream_id).map_or(0, |s| s.time_direct_ms)
+        };
+
+        let with_browser = allocate(&test_config());
+        let without_browser = allocate(&AllocationConfig {
+            ignored_event_types: HashSet::from([EventType::BrowserTab]),
+            ..test_config()
+        });
+
+        let browser_ms = direct_ms(&with_browser, "B");
+        assert_eq!(browser_ms, 60_000);
+        assert_eq!(direct_ms(&without_browser, "B"), 0);
+        // Without the tab, the browser window's own stream gets that time back.
+        assert_eq!(
+            direct_ms(&without_browser, "P") - direct_ms(&with_browser, "P"),
+            browser_ms
+        );
+        assert_eq!(
+            direct_ms(&without_browser, "S"),
+            direct_ms(&with_browser, "S")
+        );
+    }
+
+    #[test]
+    fn test_near_duplicate_user_messages_establish_focus_once() {
+        // The log parser and a hook both captured the same message, 500ms apart.
+        let events = vec![
+            TestEvent::user_message(ts(0), "sess1", "A"),
+            TestEvent::user_message(ts(0) + Duration::milliseconds(500), "sess1", "A"),
+        ];
+        let allocate = |config: &AllocationConfig| {
+            allocate_time(&events, config, None, &HashMap::new(), &HashMap::new())
+        };
+
+        // Deduplicated: one focus from 0, lasting one attention window.
+        let config = AllocationConfig {
+            user_message_dedup_window_ms: 2_000,
+            ..test_config()
+        };
+        let result = allocate(&config);
+        assert_eq!(
+            get_stream_time(&result, "A").unwrap().time_direct_ms,
+            60_000
+        );
+
+        // Off by default: the second message restarts the window 500ms later.
+        let result = allocate(&test_config());
+        assert_eq!(
+            get_stream_time(&result, "A").unwrap().time_direct_ms,
+            60_500
+        );
+    }
+
+    #[test]
+    fn test_user_message_dedup_is_per_session() {
+        // Same instant, different sessions: both are real messages.
+        let events = vec![
+            TestEvent::user_message(ts(0), "sess1", "A"),
+            TestEvent::user_message(ts(0) + Duration::milliseconds(500), "sess2", "B"),
+        ];
+        let config = AllocationConfig {
+            user_message_dedup_window_ms: 2_000,
+            ..test_config()
+        };
+        let result = allocate_time(&events, &config, None, &HashMap::new(), &HashMap::new());
+
+        assert_eq!(get_stream_time(&result, "A").unwrap().time_direct_ms, 500);
+        assert_eq!(
+            get_stream_time(&result, "B").unwrap().time_direct_ms,
+            60_000
+        );
+    }
+
+    #[test]
+    fn test_scroll_establishes_focus_when_unfocused() {
+        // The pane's focus event was debounced away; only the scroll arrived.
+        let events = vec![TestEvent::tmux_scroll(ts(0), "A")];
+
+        let result = allocate_time(
+            &events,
+            &test_config(),
+            Some(ts(10)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
+        assert_eq!(stream_a.time_direct_ms, 60_000);
+    }
+
+    #[test]
+    fn test_scroll_does_not_establish_focus_while_afk() {
+        let events = vec![
+            TestEvent::tmux_focus(ts(0), "A"),
+            TestEvent::afk_change(ts(5), "idle"),
+            TestEvent::tmux_scroll(ts(6), "A"),
+            TestEvent::afk_change(ts(8), "active"),
+            TestEvent::tmux_scroll(ts(9), "A"),
+        ];
+
         let result = allocate_time(
             &events,
-            &config,
-            Some(ts(30)),
+            &test_config(),
+            Some(ts(20)),
             &HashMap::new(),
             &HashMap::new(),
         );
 
-        // No tool use = no delegated time
-        let stream_a = get_stream_time(&result, "A");
-        assert!(stream_a.is_none() || stream_a.unwrap().time_delegated_ms == 0);
+        // [0, 1) before idle; the scroll while idle is ignored; [9, 10) after active.
+        let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
+        assert_eq!(stream_a.time_direct_ms, 2 * 60_000);
     }
 
-    // Test 7: Agent timeout (crashed session)
     #[test]
-    fn test_agent_timeout() {
+    fn test_synthesize_idle_from_gaps_closes_focus_after_long_gap() {
+        // No AFK detector: 8 hours of silence, then a scroll on the focused pane.
         let events = vec![
-            TestEvent::agent_session(ts(0), "started", "sess1", Some("A")),
-            TestEvent::agent_tool_use(ts(5), "sess1", "A"),
-            // No end event, next event at 60min (35min after last tool use)
-            TestEvent::tmux_focus(ts(60), "B"),
+            TestEvent::tmux_focus(ts(0), "A"),
+            TestEvent::tmux_scroll(ts(8 * 60), "A"),
         ];
-
-        let config = AllocationConfig {
-            agent_timeout_ms: 30 * 60 * 1000, // 30 minutes
-            ..Default::default()
+        let allocate = |config: &AllocationConfig| {
+            allocate_time(
+                &events,
+                config,
+                Some(ts(8 * 60 + 10)),
+                &HashMap::new(),
+                &HashMap::new(),
+            )
         };
 
-        let result = allocate_time(
-            &events,
-            &config,
-            Some(ts(60)),
-            &HashMap::new(),
-            &HashMap::new(),
-        );
+        // Without synthesis the scroll revives the stale focus for another window.
+        let baseline = allocate(&test_config());
+        let stream_a = get_stream_time(&baseline, "A").expect("Stream A should exist");
+        assert_eq!(stream_a.time_direct_ms, 2 * 60_000);
+        assert_eq!(baseline.total_tracked_ms, 2 * 60_000);
 
+        let result = allocate(&AllocationConfig {
+            synthesize_idle_from_gaps: true,
+            ..test_config()
+        });
         let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
-        // Session times out at 5 + 30 = 35 minutes
-        // Delegated: from 5 to 35 = 30 minutes
-        assert_eq!(stream_a.time_delegated_ms, 30 * 60 * 1000);
+        assert_eq!(
+            stream_a.time_direct_ms, 60_000,
+            "capped at the attention window"
+        );
+        assert_eq!(
+            result.total_tracked_ms, 60_000,
+            "the gap is idle, not tracked"
+        );
     }
 
     // Test 8: Concurrent agents in different streams
@@ -1174,6 +1695,7 @@ mod tests {
         let config = AllocationConfig {
             attention_window_ms: 60_000, // 1 minute
             agent_timeout_ms: 30 * 60 * 1000,
+            ..Default::default()
         };
         let result = allocate_time(
             &events,
@@ -1199,6 +1721,7 @@ mod tests {
         let config = AllocationConfig {
             attention_window_ms: 60_000, // 1 minute
             agent_timeout_ms: 30 * 60 * 1000,
+            ..Default::default()
         };
         let result = allocate_time(
             &events,
@@ -1367,6 +1890,7 @@ mod tests {
         let config = AllocationConfig {
             attention_window_ms: 60_000,
             agent_timeout_ms: 30 * 60 * 1000,
+            ..Default::default()
         };
         let result = allocate_time(
             &events,
@@ -1461,6 +1985,7 @@ mod tests {
         let config = AllocationConfig {
             attention_window_ms: 5 * 60 * 1000,
             agent_timeout_ms: 30 * 60 * 1000,
+            ..Default::default()
         };
         let result = allocate_time(
             &events,
@@ -1602,6 +2127,42 @@ mod tests {
         assert_eq!(stream_a.time_direct_ms, 2 * 60 * 1000);
     }
 
+    #[test]
+    fn test_extra_terminal_app_resolves_to_tmux_stream() {
+        let events = vec![
+            TestEvent::tmux_focus(ts(0), "A"),
+            TestEvent::window_focus(ts(2), "foot", None),
+            TestEvent::tmux_focus(ts(10), "A"),
+        ];
+
+        // Built-ins alone don't know "foot": focusing it is focus loss for A.
+        let result = allocate_time(
+            &events,
+            &test_config(),
+            Some(ts(10)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+        let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
+        assert_eq!(stream_a.time_direct_ms, 60 * 1000);
+
+        let config = AllocationConfig {
+            extra_terminal_apps: vec!["Foot".to_string()],
+            ..test_config()
+        };
+        let result = allocate_time(
+            &events,
+            &config,
+            Some(ts(10)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
+        assert_eq!(stream_a.time_direct_ms, 2 * 60 * 1000);
+        assert_eq!(result.unassigned_direct_ms, 0);
+    }
+
     #[test]
     fn test_focus_hierarchy_browser_uses_browser_stream() {
         let events = vec![
@@ -1646,6 +2207,52 @@ mod tests {
         assert_eq!(stream_a.time_direct_ms, 60 * 1000);
     }
 
+    #[test]
+    fn test_short_idle_below_threshold_does_not_break_focus() {
+        let events = vec![
+            TestEvent::tmux_focus(ts(0), "A"),
+            TestEvent::afk_with_duration(ts(0) + Duration::seconds(40), "idle", 10_000),
+        ];
+        let config = AllocationConfig {
+            min_idle_to_break_ms: 60_000,
+            ..test_config()
+        };
+        let result = allocate_time(
+            &events,
+            &config,
+            Some(ts(1)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
+        // The 10-second pause is continued focus: the full attention window counts
+        assert_eq!(stream_a.time_direct_ms, 60 * 1000);
+    }
+
+    #[test]
+    fn test_long_idle_above_threshold_breaks_focus() {
+        let events = vec![
+            TestEvent::tmux_focus(ts(0), "A"),
+            TestEvent::afk_with_duration(ts(20) + Duration::seconds(30), "idle", 20 * 60 * 1000),
+        ];
+        let config = AllocationConfig {
+            min_idle_to_break_ms: 60_000,
+            ..test_config()
+        };
+        let result = allocate_time(
+            &events,
+            &config,
+            Some(ts(21)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
+        // Idle started 30 seconds in, so focus ends there
+        assert_eq!(stream_a.time_direct_ms, 30 * 1000);
+    }
+
     #[test]
     fn test_focus_switch_caps_gap_at_attention_window() {
         let events = vec![
@@ -2023,6 +2630,145 @@ mod tests {
         assert!(result.stream_times.is_empty());
     }
 
+    #[test]
+    fn test_agent_activity_implies_focus_only_when_enabled() {
+        let events = vec![
+            TestEvent::agent_session(ts(0), "started", "sess1", Some("A")),
+            TestEvent::agent_tool_use(ts(1), "sess1", "A"),
+            TestEvent::agent_tool_use(ts(6), "sess1", "A"),
+            TestEvent::agent_tool_use(ts(11), "sess1", "A"),
+            TestEvent::agent_session(ts(20), "ended", "sess1", Some("A")),
+        ];
+
+        let result = allocate_time(
+            &events,
+            &test_config(),
+            Some(ts(30)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+        let a = get_stream_time(&result, "A").unwrap();
+        assert_eq!(a.time_direct_ms, 0);
+        assert_eq!(a.time_delegated_ms, 19 * 60_000);
+
+        let config = AllocationConfig {
+            agent_activity_implies_focus: true,
+            ..test_config()
+        };
+        let result = allocate_time(
+            &events,
+            &config,
+            Some(ts(30)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+        let a = get_stream_time(&result, "A").unwrap();
+        // First to last tool use (1..11), plus one attention window (1 min)
+        assert_eq!(a.time_direct_ms, 11 * 60_000);
+        assert_eq!(a.time_delegated_ms, 19 * 60_000);
+    }
+
+    #[test]
+    fn test_min_session_ms_drops_short_sessions_only() {
+        let events = vec![
+            TestEvent::agent_session(ts(0), "started", "short", Some("A")),
+            TestEvent::agent_tool_use(ts(0), "short", "A"),
+            TestEvent::agent_session(ts(0), "started", "long", Some("B")),
+            TestEvent::agent_tool_use(ts(1), "long", "B"),
+            TestEvent::agent_tool_use(ts(5), "long", "B"),
+            // Times out "short" (last tool use + 30 min); "long" is still open
+            TestEvent::tmux_focus(ts(31), "C"),
+        ];
+        let run = |config: &AllocationConfig| {
+            allocate_time(
+                &events,
+                config,
+                Some(ts(33)),
+                &HashMap::new(),
+                &HashMap::new(),
+            )
+        };
+
+        let result = run(&test_config());
+        assert_eq!(
+            get_stream_time(&result, "A").unwrap().time_delegated_ms,
+            30 * 60_000
+        );
+
+        let config = AllocationConfig {
+            min_session_ms: 60_000,
+            ..test_config()
+        };
+        let result = run(&config);
+        // Single tool use: zero span, dropped on the timeout path
+        assert!(get_stream_time(&result, "A").is_none());
+        // Four-minute span: closed at period end on the finalization path
+        assert_eq!(
+            get_stream_time(&result, "B").unwrap().time_delegated_ms,
+            32 * 60_000
+        );
+    }
+
+    #[test]
+    fn test_no_carry_open_ends_open_session_at_last_tool_use() {
+        // No `ended` event: the session is still open at the period end
+        let events = vec![
+            TestEvent::agent_session(ts(0), "started", "sess", Some("A")),
+            TestEvent::agent_tool_use(ts(0), "sess", "A"),
+            TestEvent::agent_tool_use(ts(12), "sess", "A"),
+        ];
+        let run = |config: &AllocationConfig| {
+            let result = allocate_time(
+                &events,
+                config,
+                Some(ts(20)),
+                &HashMap::new(),
+                &HashMap::new(),
+            );
+            get_stream_time(&result, "A").unwrap().time_delegated_ms
+        };
+
+        let carried = run(&test_config());
+        let committed = run(&AllocationConfig {
+            carry_open_sessions: false,
+            ..test_config()
+        });
+        assert_eq!(carried, 20 * 60_000);
+        assert_eq!(committed, 12 * 60_000);
+        // The modes differ by exactly the open interval after the last tool use
+        assert_eq!(carried - committed, 8 * 60_000);
+    }
+
+    #[test]
+    fn test_no_carry_open_with_known_end_time() {
+        let events = vec![
+            TestEvent::agent_session(ts(0), "started", "sess", Some("A")),
+            TestEvent::agent_tool_use(ts(0), "sess", "A"),
+            TestEvent::agent_tool_use(ts(12), "sess", "A"),
+        ];
+        let run = |known_end, carry_open_sessions| {
+            let config = AllocationConfig {
+                carry_open_sessions,
+                ..test_config()
+            };
+            let result = allocate_time(
+                &events,
+                &config,
+                Some(ts(20)),
+                &HashMap::from([("sess".to_string(), known_end)]),
+                &HashMap::new(),
+            );
+            get_stream_time(&result, "A").unwrap().time_delegated_ms
+        };
+
+        // Ended within the period: the known end wins in both modes
+        assert_eq!(run(ts(15), true), 15 * 60_000);
+        assert_eq!(run(ts(15), false), 15 * 60_000);
+        // Ends after the period end, so the session is open at the period end
+        assert_eq!(run(ts(40), true), 20 * 60_000);
+        assert_eq!(run(ts(40), false), 12 * 60_000);
+    }
+
     #[test]
     fn test_unassigned_focus_accrues_direct_time() {
         let events = vec![TestEvent {
@@ -2072,7 +2818,7 @@ mod tests {
     }
 
     #[test]
-    fn test_gui_focus_without_stream_accrues_unassigned_direct() {
+    fn test_gui_focus_without_stream_is_focus_loss() {
         // Given: focus on a non-terminal GUI window (Slack) with no stream.
         let events = vec![TestEvent::window_focus(ts(0), "slack", None)];
 
@@ -2086,8 +2832,215 @@ mod tests {
             &HashMap::new(),
         );
 
-        // Then: the active GUI time accrues to the unassigned bucket, not zero.
-        assert_eq!(result.unassigned_direct_ms, 60_000);
+        // Then: the Slack time is not tracked at all.
+        assert_eq!(result.unassigned_direct_ms, 0);
         assert!(result.stream_times.is_empty());
     }
+
+    fn make_result(stream_times: &[(&str, i64, i64)]) -> AllocationResult {
+        AllocationResult {
+            stream_times: stream_times
+                .iter()
+                .map(|&(stream_id, direct, delegated)| StreamTime {
+                    stream_id: stream_id.to_string(),
+                    time_direct_ms: direct,
+                    time_delegated_ms: delegated,
+                })
+                .collect(),
+            activity: Vec::new(),
+            total_tracked_ms: 0,
+            unassigned_direct_ms: 0,
+            unassigned_delegated_ms: 0,
+        }
+    }
+
+    fn tags(entries: &[(&str, &[&str])]) -> HashMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(stream_id, tags)| {
+                let tags = tags.iter().map(ToString::to_string).collect();
+                (stream_id.to_string(), tags)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_aggregate_by_tag_single_tag_streams() {
+        let result = make_result(&[("A", 1_000, 2_000), ("B", 500, 0), ("C", 7, 7)]);
+        let tags = tags(&[("A", &["acme"]), ("B", &["acme"]), ("D", &["other"])]);
+
+        let by_tag = result.aggregate_by_tag(&tags);
+
+        // C is untagged and D has no time, so only "acme" appears.
+        assert_eq!(by_tag.len(), 1);
+        assert_eq!(by_tag["acme"], (1_500, 2_000));
+        assert_eq!(result.aggregate_by_tag_split_evenly(&tags), by_tag);
+    }
+
+    #[test]
+    fn test_aggregate_by_tag_multi_tag_streams() {
+        let result = make_result(&[("A", 1_001, 300), ("B", 100, 0)]);
+        let tags = tags(&[("A", &["acme", "review"]), ("B", &["review"])]);
+
+        // Full attribution double-counts A.
+        let by_tag = result.aggregate_by_tag(&tags);
+        assert_eq!(by_tag["acme"], (1_001, 300));
+        assert_eq!(by_tag["review"], (1_101, 300));
+
+        // Even split preserves the total; the odd millisecond goes to the first tag.
+        let split = result.aggregate_by_tag_split_evenly(&tags);
+        assert_eq!(split["acme"], (501, 150));
+        assert_eq!(split["review"], (600, 150));
+    }
+
+    #[test]
+    fn test_clip_to_keeps_only_in_range_share() {
+        let span = |stream_id: Option<&str>, start, end, delegated| ActivityInterval {
+            stream_id: stream_id.map(String::from),
+            start,
+            end,
+            delegated,
+        };
+        let mut result = make_result(&[("A", 10 * 60_000, 20 * 60_000), ("B", 5 * 60_000, 0)]);
+        result.activity = vec![
+            span(Some("A"), ts(0), ts(10), false),
+            span(Some("A"), ts(0), ts(20), true),
+            span(Some("B"), ts(0), ts(5), false),
+            span(None, ts(8), ts(12), false),
+        ];
+
+        result.clip_to(ts(5), ts(15));
+
+        // B's only span ends at the start, so B is dropped.
+        assert_eq!(
+            result.stream_times,
+            vec![StreamTime {
+                stream_id: "A".to_string(),
+                time_direct_ms: 5 * 60_000,
+                time_delegated_ms: 10 * 60_000,
+            }]
+        );
+        assert_eq!(result.unassigned_direct_ms, 4 * 60_000);
+        assert_eq!(result.total_tracked_ms, 10 * 60_000);
+        assert_eq!(result.activity.len(), 3);
+    }
+
+    #[test]
+    fn test_gui_focus_without_stream_stops_code_stream_time() {
+        // Given: working in tmux on A, then alt-tab to Slack (no stream) until period end.
+        let events = vec![
+            TestEvent::tmux_focus(ts(0), "A"),
+            TestEvent::tmux_scroll(ts(1), "A"),
+            TestEvent::window_focus(ts(2), "Slack", None),
+        ];
+
+        let config = test_config();
+        let result = allocate_time(
+            &events,
+            &config,
+            Some(ts(30)),
+            &HashMap::new(),
+            &HashMap::new(),
+        );
+
+        // Then: A keeps only the time before the switch; Slack time goes nowhere.
+        let stream_a = get_stream_time(&result, "A").expect("Stream A should exist");
+        assert_eq!(stream_a.time_direct_ms, 2 * 60_000);
+        assert_eq!(result.unassigned_direct_ms, 0);
+        assert!(
+            result
+                .stream_times
+                .iter()
+                .all(|stream| stream.stream_id == "A")
+        );
+    }
+
+    #[test]
+    fn test_validate_accepts_default_and_test_configs() {
+        assert_eq!(AllocationConfig::default().validate(), Ok(()));
+        assert_eq!(test_config().validate(), Ok(()));
+        let no_dedup = AllocationConfig {
+            user_message_dedup_window_ms: 0,
+            ..Default::default()
+        };
+        assert_eq!(no_dedup.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_rejects_non_positive_attention_window() {
+        for attention_window_ms in [0, -1] {
+            let config = AllocationConfig {
+                attention_window_ms,
+                ..Default::default()
+            };
+            let err = config.validate().unwrap_err();
+            assert!(
+                err.starts_with("attention_window_ms must be positive"),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_non_positive_agent_timeout() {
+        let config = AllocationConfig {
+            agent_timeout_ms: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err("agent_timeout_ms must be positive, got 0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_non_positive_agent_timeout_override() {
+        let config = AllocationConfig {
+            agent_timeout_overrides_ms: HashMap::from([
+                ("claude".to_string(), 600_000),
+                ("opencode".to_string(), -5),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err("agent_timeout_overrides_ms.opencode must be positive, got -5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_negative_min_idle_to_break() {
+        let config = AllocationConfig {
+            min_idle_to_break_ms: -1,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err("min_idle_to_break_ms must not be negative, got -1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_negative_min_session() {
+        let config = AllocationConfig {
+            min_session_ms: -1,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err("min_session_ms must not be negative, got -1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_negative_dedup_window() {
+        let config = AllocationConfig {
+            user_message_dedup_window_ms: -1,
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err("user_message_dedup_window_ms must not be negative, got -1".to_string())
+        );
+    }
 }
@@ -33,12 +33,28 @@ impl fmt::Display for EventType {
     }
 }
 
+impl EventType {
+    /// Returns the `agent_session` action implied by a legacy type string.
+    ///
+    /// Old exports used `session_start`/`session_end` as standalone types; both
+    /// now parse as [`EventType::AgentSession`], with the action carried here.
+    pub fn legacy_action(s: &str) -> Option<&'static str> {
+        match s {
+            "session_start" => Some("started"),
+            "session_end" => Some("ended"),
+            _ => None,
+        }
+    }
+}
+
 impl FromStr for EventType {
     type Err = UnknownEventType;
 
+    /// Parses a canonical type string, also accepting the legacy
+    /// `session_start`/`session_end` aliases (see [`EventType::legacy_action`]).
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "agent_session" => Ok(Self::AgentSession),
+            "agent_session" | "session_start" | "session_end" => Ok(Self::AgentSession),
             "agent_tool_use" => Ok(Self::AgentToolUse),
             "user_message" => Ok(Self::UserMessage),
             "tmux_pane_focus" => Ok(Self::TmuxPaneFocus),
@@ -101,11 +117,17 @@ mod tests {
 
     #[test]
     fn legacy_aliases_parse() {
-        let session_start: Result<EventType, _> = "session_start".parse();
-        assert!(session_start.is_err());
+        let session_start: EventType = "session_start".parse().unwrap();
+        assert_eq!(session_start, EventType::AgentSession);
+        assert_eq!(EventType::legacy_action("session_start"), Some("started"));
+
+        let session_end: EventType = "session_end".parse().unwrap();
+        assert_eq!(session_end, EventType::AgentSession);
+        assert_eq!(EventType::legacy_action("session_end"), Some("ended"));
 
-        let session_end: Result<EventType, _> = "session_end".parse();
-        assert!(session_end.is_err());
+        // Canonical output is unchanged.
+        assert_eq!(session_start.to_string(), "agent_session");
+        assert_eq!(EventType::legacy_action("agent_session"), None);
     }
 
     #[test]
@@ -4,16 +4,21 @@
 //! - Allocation: computing direct/delegated time for streams
 //! - Session scanning: discovering Claude and `OpenCode` sessions
 //! - Project identification: extracting project names from git remotes
+//!
+//! The `test-util` feature adds [`test_util`], builders for allocation scenarios.
 
 mod allocation;
 pub mod event_type;
 pub mod opencode;
 pub mod project;
 pub mod session;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
 pub mod todos;
 
 pub use allocation::{
-    AllocatableEvent, AllocationConfig, AllocationResult, StreamTime, allocate_time,
+    ActivityInterval, AllocatableEvent, AllocationConfig, AllocationResult, StreamTime,
+    allocate_time,
 };
 pub use event_type::{EventType, UnknownEventType};
 pub use opencode::scan_opencode_sessions;
@@ -177,7 +177,7 @@ pub fn scan_opencode_sessions(
                 match build_agent_session(conn, sessions_dir, row) {
                     Ok(session) => Some(session),
                     Err(err) => {
-                        tracing::warn!(error = %err, "skipping invalid OpenCode session");
+                        tracing::warn!(skipped = true, error = %err, "skipping invalid OpenCode session");
                         None
                     }
                 }
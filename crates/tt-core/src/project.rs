@@ -1,6 +1,6 @@
 //! Git project identity extraction.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Project identity from git/jj context.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -36,6 +36,83 @@ pub fn parse_remote_name(url: &str) -> Option<String> {
     }
 }
 
+/// Finds the project root containing `start`.
+///
+/// Walks up from `start` (inclusive) and returns the first directory holding a
+/// `.git` or `.jj` entry. `.git` may be a file, as in git worktrees. Returns
+/// `None` if no ancestor is a repository.
+///
+/// ```
+/// use tt_core::project::find_project_root;
+///
+/// let tmp = tempfile::tempdir().unwrap();
+/// let outer = tmp.path().join("outer");
+/// let inner = outer.join("vendor/inner");
+/// std::fs::create_dir_all(outer.join(".git")).unwrap();
+/// std::fs::create_dir_all(inner.join(".git")).unwrap();
+/// std::fs::create_dir_all(inner.join("src")).unwrap();
+///
+/// // The nearest repository wins.
+/// assert_eq!(find_project_root(&inner.join("src")), Some(inner.clone()));
+/// assert_eq!(find_project_root(&outer.join("vendor")), Some(outer));
+/// assert_eq!(find_project_root(tmp.path()), None);
+/// ```
+pub fn find_project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists() || dir.join(".jj").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Returns the project name for a repository root.
+///
+/// Precedence:
+/// 1. The repo name from the `origin` remote in `.git/config` (see
+///    [`parse_remote_name`]).
+/// 2. The root directory's name.
+/// 3. `"unknown"` if the path has no final component.
+///
+/// ```
+/// use tt_core::project::project_name;
+///
+/// let tmp = tempfile::tempdir().unwrap();
+/// let root = tmp.path().join("checkout");
+/// std::fs::create_dir_all(root.join(".git")).unwrap();
+/// assert_eq!(project_name(&root), "checkout");
+///
+/// std::fs::write(
+///     root.join(".git/config"),
+///     "[remote \"origin\"]\n\turl = git@github.com:user/time-tracker.git\n",
+/// )
+/// .unwrap();
+/// assert_eq!(project_name(&root), "time-tracker");
+/// ```
+pub fn project_name(root: &Path) -> String {
+    git_origin_url(root)
+        .as_deref()
+        .and_then(parse_remote_name)
+        .or_else(|| root.file_name().and_then(|n| n.to_str()).map(String::from))
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Reads the `origin` remote URL from `<root>/.git/config`, if present.
+fn git_origin_url(root: &Path) -> Option<String> {
+    let config = std::fs::read_to_string(root.join(".git").join("config")).ok()?;
+    let mut in_origin = false;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_origin = line == r#"[remote "origin"]"#;
+        } else if in_origin {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "url" {
+                    return Some(value.trim().to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
 impl ProjectIdentity {
     /// Build identity from jj command outputs.
     pub fn from_jj_output(remote_url: Option<&str>, workspace_count: usize, jj_root: &str) -> Self {
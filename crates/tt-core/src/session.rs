@@ -431,7 +431,7 @@ pub fn scan_claude_sessions(projects_dir: &Path) -> Result<Vec<AgentSession>, Se
 
                 // Skip files with empty session IDs to prevent invalid event ID generation
                 if session_id.is_empty() {
-                    tracing::warn!(path = ?session_path, "skipping session file with empty session ID");
+                    tracing::warn!(skipped = true, path = ?session_path, "skipping session file with empty session ID");
                     continue;
                 }
 
@@ -463,7 +463,7 @@ pub fn scan_claude_sessions(projects_dir: &Path) -> Result<Vec<AgentSession>, Se
 
                                 // Skip files with empty session IDs to prevent invalid event ID generation
                                 if session_id.is_empty() {
-                                    tracing::warn!(path = ?subagent_path, "skipping subagent session file with empty session ID");
+                                    tracing::warn!(skipped = true, path = ?subagent_path, "skipping subagent session file with empty session ID");
                                     continue;
                                 }
 
@@ -486,7 +486,7 @@ pub fn scan_claude_sessions(projects_dir: &Path) -> Result<Vec<AgentSession>, Se
             match parse_session_file(&sf.path, &sf.session_id, sf.parent_session_id.as_deref()) {
                 Ok(entry) => Some(entry),
                 Err(e) => {
-                    tracing::warn!(path = ?sf.path, error = %e, "skipping invalid session");
+                    tracing::warn!(skipped = true, path = ?sf.path, error = %e, "skipping invalid session");
                     None
                 }
             }
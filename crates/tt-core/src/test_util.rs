@@ -0,0 +1,197 @@
+//! Builders for allocation test scenarios.
+//!
+//! Enabled by the `test-util` feature so downstream crates and integration
+//! tests can construct [`AllocatableEvent`] sequences without re-deriving
+//! an event type. Not part of the stable API.
+//!
+//! ```
+//! use std::collections::HashMap;
+//!
+//! use tt_core::test_util::{TestEvent, get_stream_time, ts};
+//! use tt_core::{AllocationConfig, allocate_time};
+//!
+//! let events = vec![
+//!     TestEvent::tmux_focus(ts(0), "A"),
+//!     TestEvent::tmux_focus(ts(3), "B"),
+//! ];
+//! let result = allocate_time(
+//!     &events,
+//!     &AllocationConfig::default(),
+//!     Some(ts(20)),
+//!     &HashMap::new(),
+//!     &HashMap::new(),
+//! );
+//!
+//! // A until the switch; B for one 5-minute attention window.
+//! assert_eq!(get_stream_time(&result, "A").unwrap().time_direct_ms, 3 * 60_000);
+//! assert_eq!(get_stream_time(&result, "B").unwrap().time_direct_ms, 5 * 60_000);
+//! ```
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde_json::json;
+
+use crate::allocation::{AllocatableEvent, AllocationResult, StreamTime};
+use crate::event_type::EventType;
+
+/// A minimal [`AllocatableEvent`]; fields are public for one-off tweaks.
+#[derive(Debug, Clone)]
+pub struct TestEvent {
+    pub timestamp: DateTime<Utc>,
+    pub event_type: EventType,
+    pub stream_id: Option<String>,
+    pub session_id: Option<String>,
+    pub action: Option<String>,
+    pub data: serde_json::Value,
+}
+
+impl TestEvent {
+    pub fn tmux_focus(ts: DateTime<Utc>, stream_id: &str) -> Self {
+        Self {
+            timestamp: ts,
+            event_type: EventType::TmuxPaneFocus,
+            stream_id: Some(stream_id.to_string()),
+            session_id: None,
+            action: None,
+            data: json!({"pane_id": "%1", "cwd": "/test"}),
+        }
+    }
+
+    pub fn afk_change(ts: DateTime<Utc>, status: &str) -> Self {
+        Self {
+            timestamp: ts,
+            event_type: EventType::AfkChange,
+            stream_id: None,
+            session_id: None,
+            action: None,
+            data: json!({"status": status}),
+        }
+    }
+
+    pub fn tmux_scroll(ts: DateTime<Utc>, stream_id: &str) -> Self {
+        Self {
+            timestamp: ts,
+            event_type: EventType::TmuxScroll,
+            stream_id: Some(stream_id.to_string()),
+            session_id: None,
+            action: None,
+            data: json!({"direction": "up"}),
+        }
+    }
+
+    /// An `agent_session` event; `action` is e.g. "started" or "ended".
+    pub fn agent_session(
+        ts: DateTime<Utc>,
+        action: &str,
+        session_id: &str,
+        stream_id: Option<&str>,
+    ) -> Self {
+        Self {
+            timestamp: ts,
+            event_type: EventType::AgentSession,
+            stream_id: stream_id.map(String::from),
+            session_id: Some(session_id.to_string()),
+            action: Some(action.to_string()),
+            data: json!({"agent": "claude-code"}),
+        }
+    }
+
+    pub fn agent_tool_use(ts: DateTime<Utc>, session_id: &str, stream_id: &str) -> Self {
+        Self {
+            timestamp: ts,
+            event_type: EventType::AgentToolUse,
+            stream_id: Some(stream_id.to_string()),
+            session_id: Some(session_id.to_string()),
+            action: None,
+            data: json!({"tool": "Edit"}),
+        }
+    }
+
+    pub fn user_message(ts: DateTime<Utc>, session_id: &str, stream_id: &str) -> Self {
+        Self {
+            timestamp: ts,
+            event_type: EventType::UserMessage,
+            stream_id: Some(stream_id.to_string()),
+            session_id: Some(session_id.to_string()),
+            action: None,
+            data: json!({"length": 100}),
+        }
+    }
+
+    pub fn window_focus(ts: DateTime<Utc>, app: &str, stream_id: Option<&str>) -> Self {
+        Self {
+            timestamp: ts,
+            event_type: EventType::WindowFocus,
+            stream_id: stream_id.map(String::from),
+            session_id: None,
+            action: None,
+            data: json!({"app": app, "title": "test window"}),
+        }
+    }
+
+    pub fn browser_tab(ts: DateTime<Utc>, stream_id: &str) -> Self {
+        Self {
+            timestamp: ts,
+            event_type: EventType::BrowserTab,
+            stream_id: Some(stream_id.to_string()),
+            session_id: None,
+            action: None,
+            data: json!({"url": "https://example.com", "title": "Test Page"}),
+        }
+    }
+
+    pub fn afk_with_duration(ts: DateTime<Utc>, status: &str, idle_duration_ms: i64) -> Self {
+        Self {
+            timestamp: ts,
+            event_type: EventType::AfkChange,
+            stream_id: None,
+            session_id: None,
+            action: None,
+            data: json!({"status": status, "idle_duration_ms": idle_duration_ms}),
+        }
+    }
+}
+
+impl AllocatableEvent for TestEvent {
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    fn event_type(&self) -> EventType {
+        self.event_type
+    }
+
+    fn stream_id(&self) -> Option<&str> {
+        self.stream_id.as_deref()
+    }
+
+    fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    fn action(&self) -> Option<&str> {
+        self.action.as_deref()
+    }
+
+    fn data(&self) -> &serde_json::Value {
+        &self.data
+    }
+}
+
+/// `minutes` after 2025-01-15 09:00 UTC.
+pub fn ts(minutes: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 1, 15, 9, 0, 0)
+        .single()
+        .expect("valid test timestamp")
+        + Duration::minutes(minutes)
+}
+
+/// Finds a stream's times in an allocation result.
+pub fn get_stream_time<'a>(
+    result: &'a AllocationResult,
+    stream_id: &str,
+) -> Option<&'a StreamTime> {
+    result
+        .stream_times
+        .iter()
+        .find(|s| s.stream_id == stream_id)
+}
@@ -26,8 +26,17 @@
 //! ## Schema Versioning
 //!
 //! The database tracks its schema version in a `schema_info` table. On open,
-//! supported older versions are migrated forward additively; unsupported
-//! version mismatches fail fast rather than silently corrupting data.
+//! databases from schema v7 onward are upgraded in place by running each
+//! pending step in `MIGRATIONS` inside a single transaction; older or newer
+//! versions fail fast rather than silently corrupting data.
+//!
+//! ## Attached Databases
+//!
+//! [`Database::open_with_attached`] attaches extra database files read-only
+//! for a merged view. Only [`Database::get_events_in_range`] and the stream
+//! listings ([`Database::get_streams`], [`Database::get_streams_paginated`],
+//! [`Database::count_streams`]) read across them; all other queries and all
+//! writes use the primary database alone.
 
 use std::{path::Path, time::Duration};
 
@@ -37,9 +46,54 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Current schema version. Increment when making schema changes.
-const SCHEMA_VERSION: i32 = 9;
+const SCHEMA_VERSION: i32 = 13;
 
-const EVENT_COLUMNS: &str = "id, timestamp, type, source, machine_id, schema_version, cwd, git_project, git_workspace, pane_id, tmux_session, window_index, status, idle_duration_ms, action, session_id, stream_id, assignment_source, window_app_id, window_title";
+const AGENT_SESSION_COLUMNS: &str = "session_id, source, parent_session_id, project_path, project_name, start_time, end_time, message_count, summary, user_prompts, starting_prompt, assistant_message_count, tool_call_count, session_type, user_message_timestamps, tool_call_timestamps";
+
+/// Column definitions of the `agent_sessions` table, shared by `init` and the v13 rebuild.
+///
+/// Sessions are keyed by `(session_id, machine_id)` because machines can mint the
+/// same session ID; sessions from an unknown machine have `machine_id = ''`.
+const AGENT_SESSIONS_DEFINITION: &str = "
+    session_id TEXT NOT NULL,
+    source TEXT NOT NULL DEFAULT 'claude',
+    parent_session_id TEXT,
+    session_type TEXT NOT NULL DEFAULT 'user',
+    project_path TEXT NOT NULL,
+    project_name TEXT NOT NULL,
+    start_time TEXT NOT NULL,
+    end_time TEXT,
+    message_count INTEGER NOT NULL,
+    summary TEXT,
+    user_prompts TEXT DEFAULT '[]',
+    starting_prompt TEXT,
+    assistant_message_count INTEGER DEFAULT 0,
+    tool_call_count INTEGER DEFAULT 0,
+    machine_id TEXT NOT NULL DEFAULT '',
+    user_message_timestamps TEXT DEFAULT '[]',
+    tool_call_timestamps TEXT DEFAULT '[]',
+    stream_id TEXT REFERENCES streams(id) ON DELETE SET NULL,
+    PRIMARY KEY (session_id, machine_id)
+";
+
+const STREAM_COLUMNS: &str = "id, created_at, updated_at, name, time_direct_ms, time_delegated_ms, first_event_at, last_event_at, needs_recompute";
+
+const EVENT_COLUMNS: &str = "id, timestamp, type, source, machine_id, schema_version, cwd, git_project, git_workspace, pane_id, tmux_session, window_index, status, idle_duration_ms, action, session_id, stream_id, assignment_source, window_app_id, window_title, url";
+
+/// Builds a read-only `SQLite` URI for `path`, escaping characters URIs reserve.
+fn read_only_uri(path: &Path) -> String {
+    let mut uri = String::from("file:");
+    for c in path.to_string_lossy().chars() {
+        match c {
+            '%' => uri.push_str("%25"),
+            '?' => uri.push_str("%3f"),
+            '#' => uri.push_str("%23"),
+            _ => uri.push(c),
+        }
+    }
+    uri.push_str("?mode=ro");
+    uri
+}
 
 /// Format a datetime as RFC3339 with second precision and 'Z' suffix.
 ///
@@ -53,6 +107,135 @@ fn format_timestamp_opt(dt: Option<DateTime<Utc>>) -> Option<String> {
     dt.map(format_timestamp)
 }
 
+/// Oldest schema version that can be upgraded in place.
+const OLDEST_MIGRATABLE_VERSION: i32 = 7;
+
+/// A forward migration step, run inside the upgrade transaction.
+type MigrationStep = fn(&Connection) -> Result<(), DbError>;
+
+/// Forward migrations, in order. Entry `(n, step)` upgrades a version `n - 1`
+/// database to version `n`.
+///
+/// Steps add columns or rebuild a table; new tables and indexes come from the
+/// `CREATE ... IF NOT EXISTS` batch that runs after migration.
+const MIGRATIONS: &[(i32, MigrationStep)] = &[
+    (8, migrate_v7_to_v8),
+    (9, migrate_v8_to_v9),
+    (10, migrate_v9_to_v10),
+    (11, migrate_v10_to_v11),
+    (12, migrate_v11_to_v12),
+    (13, migrate_v12_to_v13),
+];
+
+/// v8: machine provenance for events and sessions (the `machines` table is
+/// created by `init`).
+fn migrate_v7_to_v8(conn: &Connection) -> Result<(), DbError> {
+    add_column_if_missing(conn, "events", "machine_id", "TEXT")?;
+    add_column_if_missing(conn, "agent_sessions", "machine_id", "TEXT")
+}
+
+/// v9: desktop window focus fields.
+fn migrate_v8_to_v9(conn: &Connection) -> Result<(), DbError> {
+    add_column_if_missing(conn, "events", "window_app_id", "TEXT")?;
+    add_column_if_missing(conn, "events", "window_title", "TEXT")
+}
+
+/// v10: per-message and per-tool-call session timestamps.
+fn migrate_v9_to_v10(conn: &Connection) -> Result<(), DbError> {
+    add_column_if_missing(
+        conn,
+        "agent_sessions",
+        "user_message_timestamps",
+        "TEXT DEFAULT '[]'",
+    )?;
+    add_column_if_missing(
+        conn,
+        "agent_sessions",
+        "tool_call_timestamps",
+        "TEXT DEFAULT '[]'",
+    )
+}
+
+/// v11: session → stream links.
+fn migrate_v10_to_v11(conn: &Connection) -> Result<(), DbError> {
+    add_column_if_missing(
+        conn,
+        "agent_sessions",
+        "stream_id",
+        "TEXT REFERENCES streams(id) ON DELETE SET NULL",
+    )
+}
+
+/// v12: page URL for `browser_tab` events.
+fn migrate_v11_to_v12(conn: &Connection) -> Result<(), DbError> {
+    add_column_if_missing(conn, "events", "url", "TEXT")
+}
+
+/// v13: agent sessions keyed by `(session_id, machine_id)` (see
+/// [`AGENT_SESSIONS_DEFINITION`]). `SQLite` can't alter a primary key, so the
+/// table is rebuilt.
+fn migrate_v12_to_v13(conn: &Connection) -> Result<(), DbError> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'agent_sessions')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Ok(());
+    }
+    conn.execute_batch(&format!(
+        "CREATE TABLE agent_sessions_v13 ({AGENT_SESSIONS_DEFINITION});
+         INSERT INTO agent_sessions_v13 ({AGENT_SESSION_COLUMNS}, machine_id, stream_id)
+         SELECT {AGENT_SESSION_COLUMNS}, COALESCE(machine_id, ''), stream_id FROM agent_sessions;
+         DROP TABLE agent_sessions;
+         ALTER TABLE agent_sessions_v13 RENAME TO agent_sessions;"
+    ))?;
+    Ok(())
+}
+
+/// Adds a column to an existing table unless it is already present.
+///
+/// Tables that don't exist yet are left alone: the `CREATE TABLE IF NOT EXISTS`
+/// batch in `init` creates them with the full current column set.
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), DbError> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)")?;
+    let columns = stmt
+        .query_map(params![table], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    if columns.is_empty() || columns.iter().any(|c| c == column) {
+        return Ok(());
+    }
+    conn.execute(
+        &format!("ALTER TABLE {table} ADD COLUMN {column} {definition}"),
+        [],
+    )?;
+    Ok(())
+}
+
+/// Serializes timestamps as a JSON array of RFC3339 strings.
+fn timestamps_to_json(timestamps: &[DateTime<Utc>]) -> String {
+    let formatted: Vec<String> = timestamps.iter().copied().map(format_timestamp).collect();
+    serde_json::to_string(&formatted).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Parses a JSON array of RFC3339 strings, skipping malformed entries.
+fn timestamps_from_json(json: Option<&str>) -> Vec<DateTime<Utc>> {
+    json.and_then(|s| serde_json::from_str::<Vec<String>>(s).ok())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+        .collect()
+}
+
+/// Earliest and latest event timestamps, as returned by [`Database::event_time_range`].
+pub type EventTimeRange = (DateTime<Utc>, DateTime<Utc>);
+
 /// A coherent unit of work, grouping related events.
 ///
 /// Streams are materialized for performance but can be recomputed from events.
@@ -86,6 +269,35 @@ pub struct Stream {
     pub needs_recompute: bool,
 }
 
+impl Stream {
+    /// Total tracked time (direct + delegated) in milliseconds.
+    pub const fn total_ms(&self) -> i64 {
+        self.time_direct_ms + self.time_delegated_ms
+    }
+
+    /// Fraction of total time that was direct attention, in `0.0..=1.0`.
+    ///
+    /// Returns `0.0` when the stream has no tracked time.
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "millisecond totals are far below f64's exact integer range"
+    )]
+    pub const fn direct_ratio(&self) -> f64 {
+        let total = self.total_ms();
+        if total <= 0 {
+            return 0.0;
+        }
+        self.time_direct_ms as f64 / total as f64
+    }
+
+    /// Wall-clock span from the first to the last event.
+    ///
+    /// Returns `None` if either event timestamp is unknown.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        Some(self.last_event_at? - self.first_event_at?)
+    }
+}
+
 /// Database errors.
 #[derive(Debug, Error)]
 pub enum DbError {
@@ -96,6 +308,33 @@ pub enum DbError {
     /// Schema version mismatch.
     #[error("schema version mismatch: database has version {found}, expected {expected}")]
     SchemaVersionMismatch { found: i32, expected: i32 },
+
+    /// Writing exported data failed.
+    #[error("write error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Errors from parsing an export JSONL line.
+#[derive(Error, Debug)]
+pub enum ParseError {
+    /// The line is not valid JSON, or not a valid event.
+    #[error("invalid event JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Events with distinct IDs but the same type, timestamp, session and stream.
+///
+/// Likely duplicates that `INSERT OR IGNORE` let through because their IDs
+/// differ (e.g. after an ID format change between syncs).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateEventGroup {
+    pub event_type: String,
+    /// Timestamp as stored.
+    pub timestamp: String,
+    pub session_id: Option<String>,
+    pub stream_id: Option<String>,
+    /// Event IDs in the group, sorted ascending.
+    pub ids: Vec<String>,
 }
 
 /// Status of events from a single source.
@@ -182,6 +421,10 @@ pub struct StoredEvent {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub window_title: Option<String>,
 
+    /// Page URL (for `browser_tab` events).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
     /// Agent action (for `agent_session` events): "started", "ended", "`tool_use`".
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub action: Option<String>,
@@ -213,7 +456,52 @@ const fn default_schema_version() -> i32 {
     1
 }
 
+/// Extracts the machine UUID prefix from an event ID.
+///
+/// Event IDs are formatted as `{machine_uuid}:{source}:{type}:{timestamp}:{discriminator}`.
+/// Returns `None` if the ID doesn't start with a valid UUID.
+#[must_use]
+pub fn extract_machine_id(event_id: &str) -> Option<String> {
+    // UUID v4 is exactly 36 chars: 8-4-4-4-12
+    if event_id.len() > 36 && event_id.as_bytes()[36] == b':' {
+        let candidate = &event_id[..36];
+        if uuid::Uuid::parse_str(candidate).is_ok() {
+            return Some(candidate.to_string());
+        }
+    }
+    None
+}
+
 impl StoredEvent {
+    /// Parses one `tt export` JSONL line (an `ExportEvent` with flattened data).
+    ///
+    /// Legacy `session_start`/`session_end` types become `agent_session` with
+    /// the matching action. `stream_id` and `assignment_source` are cleared,
+    /// since streams are local to each database, and a missing `machine_id` is
+    /// taken from the event ID prefix.
+    pub fn from_export_line(line: &str) -> Result<Self, ParseError> {
+        let mut value: serde_json::Value = serde_json::from_str(line)?;
+        if let Some(obj) = value.as_object_mut() {
+            let type_str = obj.get("type").and_then(|t| t.as_str()).unwrap_or("");
+            if let Some(action) = tt_core::EventType::legacy_action(type_str) {
+                obj.insert(
+                    "type".into(),
+                    tt_core::EventType::AgentSession.to_string().into(),
+                );
+                obj.insert("action".into(), action.into());
+            }
+        }
+
+        let mut event: Self = serde_json::from_value(value)?;
+        event.stream_id = None;
+        event.assignment_source = None;
+        if event.machine_id.is_none() {
+            event.machine_id = extract_machine_id(&event.id);
+        }
+        event.data = event.build_data_json();
+        Ok(event)
+    }
+
     /// Builds a JSON object from the explicit data fields.
     ///
     /// This is used when inserting events into the database.
@@ -264,6 +552,9 @@ impl StoredEvent {
         if let Some(ref v) = self.window_title {
             map.insert("title".to_string(), serde_json::Value::String(v.clone()));
         }
+        if let Some(ref v) = self.url {
+            map.insert("url".to_string(), serde_json::Value::String(v.clone()));
+        }
         if let Some(ref v) = self.action {
             map.insert("action".to_string(), serde_json::Value::String(v.clone()));
         }
@@ -315,6 +606,8 @@ impl tt_core::AllocatableEvent for StoredEvent {
 #[derive(Debug)]
 pub struct Database {
     conn: Connection,
+    /// Schema names of read-only databases attached for merged reads.
+    attached: Vec<String>,
 }
 
 impl Database {
@@ -332,7 +625,10 @@ impl Database {
         // owns the WAL) while making per-commit cost negligible.
         conn.pragma_update(None, "journal_mode", "WAL")?;
         conn.pragma_update(None, "synchronous", "NORMAL")?;
-        let db = Self { conn };
+        let db = Self {
+            conn,
+            attached: Vec::new(),
+        };
         db.init()?;
         Ok(db)
     }
@@ -343,11 +639,65 @@ impl Database {
     pub fn open_in_memory() -> Result<Self, DbError> {
         let conn = Connection::open_in_memory()?;
         conn.busy_timeout(Duration::from_secs(30))?;
-        let db = Self { conn };
+        let db = Self {
+            conn,
+            attached: Vec::new(),
+        };
         db.init()?;
         Ok(db)
     }
 
+    /// Opens `primary` and attaches each of `additional` read-only.
+    ///
+    /// Attached databases must already exist at the current schema version;
+    /// they are never migrated or written to.
+    pub fn open_with_attached(primary: &Path, additional: &[&Path]) -> Result<Self, DbError> {
+        let mut db = Self::open(primary)?;
+        for (index, path) in additional.iter().enumerate() {
+            let alias = format!("attached_{index}");
+            db.conn.execute(
+                &format!("ATTACH DATABASE ?1 AS {alias}"),
+                params![read_only_uri(path)],
+            )?;
+            let found: i32 = db.conn.query_row(
+                &format!("SELECT version FROM {alias}.schema_info LIMIT 1"),
+                [],
+                |row| row.get(0),
+            )?;
+            if found != SCHEMA_VERSION {
+                return Err(DbError::SchemaVersionMismatch {
+                    found,
+                    expected: SCHEMA_VERSION,
+                });
+            }
+            db.attached.push(alias);
+        }
+        Ok(db)
+    }
+
+    /// Returns a `FROM` source for `table` covering the primary and attached databases.
+    ///
+    /// `columns` must be valid for `table` in every database.
+    fn merged_table(&self, table: &str, columns: &str) -> String {
+        if self.attached.is_empty() {
+            return table.to_string();
+        }
+        let selects: Vec<String> = std::iter::once("main")
+            .chain(self.attached.iter().map(String::as_str))
+            .map(|schema| format!("SELECT {columns} FROM {schema}.{table}"))
+            .collect();
+        format!("({})", selects.join(" UNION ALL "))
+    }
+
+    /// Copies the database to `path` using `SQLite`'s online backup API.
+    ///
+    /// The copy is consistent even while this connection has open readers,
+    /// and any existing file at `path` is overwritten.
+    pub fn backup_to(&self, path: &Path) -> Result<(), DbError> {
+        self.conn.backup(rusqlite::DatabaseName::Main, path, None)?;
+        Ok(())
+    }
+
     pub fn migrate_legacy_event_types(&self) -> Result<(usize, usize), DbError> {
         let started = self.conn.execute(
             "UPDATE events SET type = 'agent_session', action = 'started'
@@ -383,10 +733,12 @@ impl Database {
 
         match existing_version {
             Some(v) if v == SCHEMA_VERSION => {}
-            Some(8) => {
+            Some(v @ OLDEST_MIGRATABLE_VERSION..SCHEMA_VERSION) => {
                 let tx = self.conn.unchecked_transaction()?;
-                tx.execute("ALTER TABLE events ADD COLUMN window_app_id TEXT", [])?;
-                tx.execute("ALTER TABLE events ADD COLUMN window_title TEXT", [])?;
+                for (target, step) in MIGRATIONS.iter().filter(|(target, _)| *target > v) {
+                    step(&tx)?;
+                    tracing::info!(from = target - 1, to = target, "migrated database schema");
+                }
                 tx.execute(
                     "UPDATE schema_info SET version = ?1",
                     params![SCHEMA_VERSION],
@@ -404,7 +756,7 @@ impl Database {
             }
         }
 
-        self.conn.execute_batch(
+        self.conn.execute_batch(&format!(
             "
             -- Schema version tracking
             CREATE TABLE IF NOT EXISTS schema_info (
@@ -433,6 +785,7 @@ impl Database {
                 assignment_source TEXT DEFAULT 'inferred',
                 window_app_id TEXT,
                 window_title TEXT,
+                url TEXT,
 
                 FOREIGN KEY (stream_id) REFERENCES streams(id) ON DELETE SET NULL
             );
@@ -470,24 +823,9 @@ impl Database {
             CREATE INDEX IF NOT EXISTS idx_stream_tags_tag ON stream_tags(tag);
 
             -- Agent sessions table: indexed coding assistant sessions
-            CREATE TABLE IF NOT EXISTS agent_sessions (
-                session_id TEXT PRIMARY KEY,
-                source TEXT NOT NULL DEFAULT 'claude',
-                parent_session_id TEXT,
-                session_type TEXT NOT NULL DEFAULT 'user',
-                project_path TEXT NOT NULL,
-                project_name TEXT NOT NULL,
-                start_time TEXT NOT NULL,
-                end_time TEXT,
-                message_count INTEGER NOT NULL,
-                summary TEXT,
-                user_prompts TEXT DEFAULT '[]',
-                starting_prompt TEXT,
-                assistant_message_count INTEGER DEFAULT 0,
-                tool_call_count INTEGER DEFAULT 0,
-                machine_id TEXT
-            );
+            CREATE TABLE IF NOT EXISTS agent_sessions ({AGENT_SESSIONS_DEFINITION});
             CREATE INDEX IF NOT EXISTS idx_agent_sessions_start_time ON agent_sessions(start_time);
+            CREATE INDEX IF NOT EXISTS idx_agent_sessions_stream ON agent_sessions(stream_id);
             CREATE INDEX IF NOT EXISTS idx_agent_sessions_project_path ON agent_sessions(project_path);
             CREATE INDEX IF NOT EXISTS idx_agent_sessions_parent ON agent_sessions(parent_session_id);
 
@@ -498,8 +836,8 @@ impl Database {
                 last_sync_at TEXT,
                 last_event_id TEXT
             );
-            ",
-        )?;
+            "
+        ))?;
 
         if existing_version.is_none() {
             self.conn.execute(
@@ -531,8 +869,8 @@ impl Database {
 
         {
             let mut stmt = tx.prepare(
-                "INSERT OR IGNORE INTO events (id, timestamp, type, source, machine_id, schema_version, cwd, git_project, git_workspace, pane_id, tmux_session, window_index, status, idle_duration_ms, action, session_id, stream_id, assignment_source, window_app_id, window_title)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+                "INSERT OR IGNORE INTO events (id, timestamp, type, source, machine_id, schema_version, cwd, git_project, git_workspace, pane_id, tmux_session, window_index, status, idle_duration_ms, action, session_id, stream_id, assignment_source, window_app_id, window_title, url)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
             )?;
 
             for event in events {
@@ -559,6 +897,7 @@ impl Database {
                     event.assignment_source,
                     event.window_app_id,
                     event.window_title,
+                    event.url,
                 ])?;
 
                 count += rows;
@@ -584,20 +923,9 @@ impl Database {
         after: Option<DateTime<Utc>>,
         before: Option<DateTime<Utc>>,
     ) -> Result<Vec<StoredEvent>, DbError> {
-        let mut sql = format!("SELECT {EVENT_COLUMNS} FROM events WHERE 1=1");
-        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-
-        if let Some(ref after_ts) = after {
-            sql.push_str(" AND timestamp > ?");
-            params_vec.push(Box::new(format_timestamp(*after_ts)));
-        }
-
-        if let Some(ref before_ts) = before {
-            sql.push_str(" AND timestamp < ?");
-            params_vec.push(Box::new(format_timestamp(*before_ts)));
-        }
-
-        sql.push_str(" ORDER BY timestamp ASC");
+        let (filter, params_vec) = Self::event_range_filter(after, before);
+        let sql =
+            format!("SELECT {EVENT_COLUMNS} FROM events WHERE {filter} ORDER BY timestamp ASC");
 
         let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(AsRef::as_ref).collect();
         let mut stmt = self.conn.prepare(&sql)?;
@@ -613,6 +941,93 @@ impl Database {
         Ok(events)
     }
 
+    /// Counts the events [`Self::get_events`] would return for the same filters.
+    ///
+    /// Only the timestamp and type columns are read, but rows where either is
+    /// malformed are left out just as `get_events` skips them (without a warning).
+    pub fn count_events_in_range(
+        &self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Result<usize, DbError> {
+        let (filter, params_vec) = Self::event_range_filter(after, before);
+        let sql = format!("SELECT timestamp, type FROM events WHERE {filter}");
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(AsRef::as_ref).collect();
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(params_refs.as_slice())?;
+        let mut count = 0;
+        while let Some(row) = rows.next()? {
+            let timestamp: String = row.get(0)?;
+            let event_type: String = row.get(1)?;
+            if DateTime::parse_from_rfc3339(&timestamp).is_ok()
+                && event_type.parse::<tt_core::EventType>().is_ok()
+            {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Writes every stored event to `writer` as JSONL, ordered by timestamp then ID.
+    ///
+    /// Unlike `tt export`, which reads the source logs, this captures the
+    /// database's enriched state, including `stream_id` and `assignment_source`.
+    /// Rows are streamed rather than loaded at once. Events with malformed
+    /// timestamps or types are skipped with a warning.
+    ///
+    /// Returns the number of events written.
+    pub fn export_events_jsonl<W: std::io::Write>(&self, writer: W) -> Result<usize, DbError> {
+        self.export_events_jsonl_with(writer, |_| {})
+    }
+
+    /// Like [`Self::export_events_jsonl`], but passes each event through
+    /// `transform` before writing it (e.g. to anonymize it).
+    pub fn export_events_jsonl_with<W: std::io::Write>(
+        &self,
+        mut writer: W,
+        mut transform: impl FnMut(&mut StoredEvent),
+    ) -> Result<usize, DbError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {EVENT_COLUMNS} FROM events ORDER BY timestamp ASC, id ASC"
+        ))?;
+
+        let mut count = 0;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            if let Some(mut event) = Self::row_to_event(row)? {
+                transform(&mut event);
+                serde_json::to_writer(&mut writer, &event).map_err(std::io::Error::from)?;
+                writer.write_all(b"\n")?;
+                count += 1;
+            }
+        }
+        writer.flush()?;
+
+        Ok(count)
+    }
+
+    /// Builds the `WHERE` clause shared by `get_events` and `count_events_in_range`.
+    fn event_range_filter(
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+        let mut filter = "1=1".to_string();
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(after_ts) = after {
+            filter.push_str(" AND timestamp > ?");
+            params_vec.push(Box::new(format_timestamp(after_ts)));
+        }
+
+        if let Some(before_ts) = before {
+            filter.push_str(" AND timestamp < ?");
+            params_vec.push(Box::new(format_timestamp(before_ts)));
+        }
+
+        (filter, params_vec)
+    }
+
     /// Retrieves events within an inclusive time range.
     ///
     /// Events are returned ordered by timestamp ascending.
@@ -627,8 +1042,9 @@ impl Database {
         start: DateTime<Utc>,
         end: DateTime<Utc>,
     ) -> Result<Vec<StoredEvent>, DbError> {
+        let events = self.merged_table("events", EVENT_COLUMNS);
         let sql = format!(
-            "SELECT {EVENT_COLUMNS} FROM events
+            "SELECT {EVENT_COLUMNS} FROM {events}
              WHERE timestamp >= ?1 AND timestamp <= ?2
              ORDER BY timestamp ASC"
         );
@@ -722,10 +1138,10 @@ impl Database {
     ///
     /// Returns streams ordered by `updated_at` descending.
     pub fn get_streams(&self) -> Result<Vec<Stream>, DbError> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, created_at, updated_at, name, time_direct_ms, time_delegated_ms, first_event_at, last_event_at, needs_recompute
-             FROM streams ORDER BY updated_at DESC",
-        )?;
+        let streams = self.merged_table("streams", STREAM_COLUMNS);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {STREAM_COLUMNS} FROM {streams} ORDER BY updated_at DESC"
+        ))?;
 
         let mut streams = Vec::new();
         let mut rows = stmt.query([])?;
@@ -735,6 +1151,33 @@ impl Database {
         Ok(streams)
     }
 
+    /// Gets one page of streams, most recently updated first.
+    ///
+    /// Ties on `updated_at` are broken by ID, so consecutive pages never overlap.
+    pub fn get_streams_paginated(&self, limit: u32, offset: u32) -> Result<Vec<Stream>, DbError> {
+        let streams = self.merged_table("streams", STREAM_COLUMNS);
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {STREAM_COLUMNS} FROM {streams} ORDER BY updated_at DESC, id ASC LIMIT ?1 OFFSET ?2"
+        ))?;
+
+        let mut streams = Vec::new();
+        let mut rows = stmt.query(params![limit, offset])?;
+        while let Some(row) = rows.next()? {
+            streams.push(Self::row_to_stream(row)?);
+        }
+        Ok(streams)
+    }
+
+    /// Counts all streams.
+    pub fn count_streams(&self) -> Result<usize, DbError> {
+        let streams = self.merged_table("streams", "id");
+        Ok(self
+            .conn
+            .query_row(&format!("SELECT COUNT(*) FROM {streams}"), [], |row| {
+                row.get(0)
+            })?)
+    }
+
     /// Assigns an event to a stream.
     ///
     /// Updates the event's `stream_id` and `assignment_source` fields.
@@ -795,6 +1238,34 @@ impl Database {
         Ok(count as u64)
     }
 
+    /// Moves every event of a session to a stream, including user assignments.
+    ///
+    /// Unlike [`Self::assign_events_by_session_id`], this is a manual override
+    /// and touches events regardless of their current assignment. Returns the
+    /// number of events updated.
+    pub fn reassign_events_by_session(
+        &self,
+        session_id: &str,
+        stream_id: &str,
+        source: &str,
+    ) -> Result<u64, DbError> {
+        let count = self.conn.execute(
+            "UPDATE events SET stream_id = ?1, assignment_source = ?2 WHERE session_id = ?3",
+            params![stream_id, source, session_id],
+        )?;
+        Ok(count as u64)
+    }
+
+    /// Returns the distinct streams a session's events are assigned to, sorted.
+    pub fn stream_ids_for_session(&self, session_id: &str) -> Result<Vec<String>, DbError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT stream_id FROM events \
+             WHERE session_id = ?1 AND stream_id IS NOT NULL ORDER BY stream_id",
+        )?;
+        let rows = stmt.query_map(params![session_id], |row| row.get(0))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
     /// Assigns events with explicit IDs to a stream.
     ///
     /// Chunks updates to stay below `SQLite`'s variable limit. Skips events with
@@ -906,6 +1377,20 @@ impl Database {
         Ok(count as u64)
     }
 
+    /// Retrieves a single event by ID.
+    ///
+    /// Returns `None` if no event with the given ID exists.
+    pub fn get_event(&self, id: &str) -> Result<Option<StoredEvent>, DbError> {
+        let sql = format!("SELECT {EVENT_COLUMNS} FROM events WHERE id = ?1");
+        let mut stmt = self.conn.prepare(&sql)?;
+
+        let mut rows = stmt.query(params![id])?;
+        match rows.next()? {
+            Some(row) => Ok(Self::row_to_event(row)?),
+            None => Ok(None),
+        }
+    }
+
     /// Retrieves events assigned to a specific stream.
     ///
     /// Events are returned ordered by timestamp ascending.
@@ -926,6 +1411,26 @@ impl Database {
         Ok(events)
     }
 
+    /// Retrieves events from one `git_project`.
+    ///
+    /// Events are returned ordered by timestamp ascending.
+    pub fn get_events_by_git_project(&self, project: &str) -> Result<Vec<StoredEvent>, DbError> {
+        let sql = format!(
+            "SELECT {EVENT_COLUMNS} FROM events WHERE git_project = ?1 ORDER BY timestamp ASC"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+
+        let mut events = Vec::new();
+        let mut rows = stmt.query(params![project])?;
+        while let Some(row) = rows.next()? {
+            if let Some(event) = Self::row_to_event(row)? {
+                events.push(event);
+            }
+        }
+
+        Ok(events)
+    }
+
     /// Retrieves events that are not assigned to any stream.
     ///
     /// Events are returned ordered by timestamp ascending.
@@ -958,6 +1463,21 @@ impl Database {
         Ok(count as u64)
     }
 
+    /// Clears inferred stream assignments on events from one `git_project`.
+    ///
+    /// Other projects' events, user assignments and cwd/URL routing (`auto`)
+    /// assignments are left untouched, so inference can be re-run for a single
+    /// project.
+    /// Returns the number of events cleared.
+    pub fn clear_inferred_assignments_for_project(&self, project: &str) -> Result<u64, DbError> {
+        let count = self.conn.execute(
+            "UPDATE events SET stream_id = NULL \
+             WHERE assignment_source = 'inferred' AND git_project = ?1 AND stream_id IS NOT NULL",
+            params![project],
+        )?;
+        Ok(count as u64)
+    }
+
     /// Deletes all events from a specific machine.
     ///
     /// Used to force a clean re-import when the export format changes.
@@ -970,6 +1490,69 @@ impl Database {
         Ok(count as u64)
     }
 
+    /// Finds groups of likely duplicate events (see [`DuplicateEventGroup`]).
+    ///
+    /// Groups are ordered by timestamp, then type, session and stream.
+    pub fn find_duplicate_events(&self) -> Result<Vec<DuplicateEventGroup>, DbError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT e.type, e.timestamp, e.session_id, e.stream_id, e.id FROM events e
+             JOIN (
+               SELECT type, timestamp, session_id, stream_id FROM events
+               GROUP BY type, timestamp, session_id, stream_id HAVING COUNT(*) > 1
+             ) d ON e.type = d.type AND e.timestamp = d.timestamp
+               AND e.session_id IS d.session_id AND e.stream_id IS d.stream_id
+             ORDER BY e.timestamp, e.type, e.session_id, e.stream_id, e.id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+
+        let mut groups: Vec<DuplicateEventGroup> = Vec::new();
+        for row in rows {
+            let (event_type, timestamp, session_id, stream_id, id) = row?;
+            match groups.last_mut() {
+                Some(group)
+                    if group.event_type == event_type
+                        && group.timestamp == timestamp
+                        && group.session_id == session_id
+                        && group.stream_id == stream_id =>
+                {
+                    group.ids.push(id);
+                }
+                _ => groups.push(DuplicateEventGroup {
+                    event_type,
+                    timestamp,
+                    session_id,
+                    stream_id,
+                    ids: vec![id],
+                }),
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Deletes events by ID in a single transaction.
+    ///
+    /// Returns the number of events deleted.
+    pub fn delete_events(&self, ids: &[String]) -> Result<u64, DbError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let mut count = 0u64;
+        {
+            let mut stmt = tx.prepare("DELETE FROM events WHERE id = ?1")?;
+            for id in ids {
+                count += stmt.execute(params![id])? as u64;
+            }
+        }
+        tx.commit()?;
+        Ok(count)
+    }
+
     /// Deletes streams that have no events assigned to them.
     ///
     /// Returns the number of streams deleted.
@@ -1099,29 +1682,85 @@ impl Database {
         Ok(())
     }
 
-    /// Gets all tags grouped by stream ID.
+    /// Removes every tag from a stream.
     ///
-    /// Returns a vector of (`stream_id`, tags) pairs.
-    /// Only streams with at least one tag are included.
-    pub fn get_all_tags(&self) -> Result<Vec<(String, Vec<String>)>, DbError> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT stream_id, tag FROM stream_tags ORDER BY stream_id ASC, tag ASC")?;
-
-        let rows = stmt.query_map([], |row| {
-            let stream_id: String = row.get(0)?;
-            let tag: String = row.get(1)?;
-            Ok((stream_id, tag))
-        })?;
-
-        let mut result: Vec<(String, Vec<String>)> = Vec::new();
-        for row_result in rows {
-            let (stream_id, tag) = row_result?;
+    /// Returns the number of tags removed.
+    pub fn clear_tags(&self, stream_id: &str) -> Result<u64, DbError> {
+        let count = self.conn.execute(
+            "DELETE FROM stream_tags WHERE stream_id = ?1",
+            params![stream_id],
+        )?;
+        Ok(count as u64)
+    }
 
-            // Since rows are ordered by stream_id, we only need to check the last entry
-            if let Some((last_id, tags)) = result.last_mut() {
-                if last_id == &stream_id {
-                    tags.push(tag);
+    /// Replaces `old` with `new` on one stream, leaving other streams' tags alone.
+    ///
+    /// Idempotent if the stream already carries `new`. Returns `false` (changing
+    /// nothing) if the stream doesn't carry `old`.
+    pub fn rename_tag_for_stream(
+        &self,
+        stream_id: &str,
+        old: &str,
+        new: &str,
+    ) -> Result<bool, DbError> {
+        let tx = self.conn.unchecked_transaction()?;
+        let removed = tx.execute(
+            "DELETE FROM stream_tags WHERE stream_id = ?1 AND tag = ?2",
+            params![stream_id, old],
+        )?;
+        if removed == 0 {
+            return Ok(false);
+        }
+        tx.execute(
+            "INSERT OR IGNORE INTO stream_tags (stream_id, tag) VALUES (?1, ?2)",
+            params![stream_id, new],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Moves every stream tagged `from` to `to`, then drops `from` everywhere.
+    ///
+    /// Streams already carrying `to` just lose `from`. Returns the number of
+    /// streams that carried `from`; merging a tag into itself changes nothing.
+    pub fn merge_tags(&self, from: &str, to: &str) -> Result<u64, DbError> {
+        if from == to {
+            return Ok(0);
+        }
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO stream_tags (stream_id, tag)
+             SELECT stream_id, ?2 FROM stream_tags WHERE tag = ?1",
+            params![from, to],
+        )?;
+        let moved = tx.execute("DELETE FROM stream_tags WHERE tag = ?1", params![from])?;
+        tx.commit()?;
+        Ok(moved as u64)
+    }
+
+    /// Gets all tags grouped by stream ID.
+    ///
+    /// Returns a vector of (`stream_id`, tags) pairs.
+    /// Only streams with at least one tag are included.
+    pub fn get_all_tags(&self) -> Result<Vec<(String, Vec<String>)>, DbError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT stream_id, tag FROM stream_tags ORDER BY stream_id ASC, tag ASC")?;
+
+        let rows = stmt.query_map([], |row| {
+            let stream_id: String = row.get(0)?;
+            let tag: String = row.get(1)?;
+            Ok((stream_id, tag))
+        })?;
+
+        let mut result: Vec<(String, Vec<String>)> = Vec::new();
+        for row_result in rows {
+            let (stream_id, tag) = row_result?;
+
+            // Since rows are ordered by stream_id, we only need to check the last entry
+            if let Some((last_id, tags)) = result.last_mut() {
+                if last_id == &stream_id {
+                    tags.push(tag);
                     continue;
                 }
             }
@@ -1130,6 +1769,26 @@ impl Database {
         Ok(result)
     }
 
+    /// Returns the IDs of streams carrying any of the given tags, sorted ascending.
+    ///
+    /// Returns an empty vector when `tags` is empty.
+    pub fn get_stream_ids_with_any_tag(&self, tags: &[&str]) -> Result<Vec<String>, DbError> {
+        if tags.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = tags.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            "SELECT DISTINCT stream_id FROM stream_tags
+             WHERE tag IN ({placeholders})
+             ORDER BY stream_id"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+
+        let rows = stmt.query_map(params_from_iter(tags.iter()), |row| row.get(0))?;
+        rows.collect::<Result<Vec<String>, _>>().map_err(Into::into)
+    }
+
     /// Gets all streams with their tags.
     ///
     /// Returns a vector of (Stream, tags) pairs.
@@ -1180,7 +1839,7 @@ impl Database {
     /// Expects the row to have columns in this order:
     /// `id`, `timestamp`, `type`, `source`, `machine_id`, `schema_version`, `cwd`, `git_project`,
     /// `git_workspace`, `pane_id`, `tmux_session`, `window_index`, `status`, `idle_duration_ms`,
-    /// `action`, `session_id`, `stream_id`, `assignment_source`, `window_app_id`, `window_title`
+    /// `action`, `session_id`, `stream_id`, `assignment_source`, `window_app_id`, `window_title`, `url`
     ///
     /// Returns `None` if the row has malformed timestamp (with a warning logged).
     fn row_to_event(row: &rusqlite::Row<'_>) -> Result<Option<StoredEvent>, rusqlite::Error> {
@@ -1204,11 +1863,12 @@ impl Database {
         let assignment_source: Option<String> = row.get(17)?;
         let window_app_id: Option<String> = row.get(18)?;
         let window_title: Option<String> = row.get(19)?;
+        let url: Option<String> = row.get(20)?;
 
         let timestamp = match DateTime::parse_from_rfc3339(&timestamp_str) {
             Ok(dt) => dt.with_timezone(&Utc),
             Err(e) => {
-                tracing::warn!(event_id = %id, error = %e, "skipping event with malformed timestamp");
+                tracing::warn!(skipped = true, event_id = %id, error = %e, "skipping event with malformed timestamp");
                 return Ok(None);
             }
         };
@@ -1217,6 +1877,7 @@ impl Database {
             Ok(event_type) => event_type,
             Err(e) => {
                 tracing::warn!(
+                    skipped = true,
                     event_id = %id,
                     event_type = %event_type_str,
                     error = %e,
@@ -1242,6 +1903,7 @@ impl Database {
             idle_duration_ms,
             window_app_id,
             window_title,
+            url,
             action,
             cwd,
             session_id,
@@ -1308,18 +1970,45 @@ impl Database {
     /// Insert or update an agent session entry.
     ///
     /// Uses `INSERT ... ON CONFLICT DO UPDATE` for idempotent upserts.
-    /// If a session with the same ID already exists, all fields are updated.
+    /// Sessions are keyed by ID and machine (`None` is stored as `''`), so the
+    /// same session ID from two machines yields two rows. If a session with the
+    /// same ID and machine already exists, all other fields are updated.
+    ///
+    /// Rows with an unknown machine are matched rather than duplicated: a known
+    /// machine claims the session's `''` row (e.g. one stored before v13), and an
+    /// unknown machine updates the session's only row, whatever its machine.
     pub fn upsert_agent_session(
         &self,
         entry: &tt_core::session::AgentSession,
         machine_id: Option<&str>,
     ) -> Result<(), DbError> {
+        let machine_id = if let Some(machine_id) = machine_id {
+            self.conn.execute(
+                "UPDATE agent_sessions SET machine_id = ?2
+                 WHERE session_id = ?1 AND machine_id = ''
+                   AND NOT EXISTS (
+                       SELECT 1 FROM agent_sessions WHERE session_id = ?1 AND machine_id = ?2
+                   )",
+                params![entry.session_id, machine_id],
+            )?;
+            machine_id.to_string()
+        } else {
+            let machines: Vec<String> = self
+                .conn
+                .prepare("SELECT machine_id FROM agent_sessions WHERE session_id = ?1")?
+                .query_map(params![entry.session_id], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            match machines.as_slice() {
+                [only] => only.clone(),
+                _ => String::new(),
+            }
+        };
         let user_prompts_json =
             serde_json::to_string(&entry.user_prompts).unwrap_or_else(|_| "[]".to_string());
         self.conn.execute(
-            "INSERT INTO agent_sessions (session_id, source, parent_session_id, project_path, project_name, start_time, end_time, message_count, summary, user_prompts, starting_prompt, assistant_message_count, tool_call_count, session_type, machine_id)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
-             ON CONFLICT(session_id) DO UPDATE SET
+            "INSERT INTO agent_sessions (session_id, source, parent_session_id, project_path, project_name, start_time, end_time, message_count, summary, user_prompts, starting_prompt, assistant_message_count, tool_call_count, session_type, machine_id, user_message_timestamps, tool_call_timestamps)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+             ON CONFLICT(session_id, machine_id) DO UPDATE SET
                 source = excluded.source,
                 parent_session_id = excluded.parent_session_id,
                 project_path = excluded.project_path,
@@ -1333,7 +2022,8 @@ impl Database {
                 assistant_message_count = excluded.assistant_message_count,
                 tool_call_count = excluded.tool_call_count,
                 session_type = excluded.session_type,
-                machine_id = excluded.machine_id",
+                user_message_timestamps = excluded.user_message_timestamps,
+                tool_call_timestamps = excluded.tool_call_timestamps",
             params![
                 entry.session_id,
                 entry.source.as_str(),
@@ -1350,6 +2040,8 @@ impl Database {
                 entry.tool_call_count,
                 entry.session_type.as_str(),
                 machine_id,
+                timestamps_to_json(&entry.user_message_timestamps),
+                timestamps_to_json(&entry.tool_call_timestamps),
             ],
         )?;
         Ok(())
@@ -1361,77 +2053,206 @@ impl Database {
     /// - Its `start_time` is at or before the range end, AND
     /// - Its `end_time` is at or after the range start (or is `NULL` for ongoing sessions)
     ///
+    /// When `source` is provided, only sessions from that agent are returned.
+    ///
     /// Sessions are returned ordered by `start_time` ascending.
     /// Sessions with malformed timestamps in the database are skipped with a warning.
     pub fn agent_sessions_in_range(
         &self,
         start: DateTime<Utc>,
         end: DateTime<Utc>,
+        source: Option<tt_core::session::SessionSource>,
     ) -> Result<Vec<tt_core::session::AgentSession>, DbError> {
-        let mut stmt = self.conn.prepare(
-            "SELECT session_id, source, parent_session_id, project_path, project_name, start_time, end_time, message_count, summary, user_prompts, starting_prompt, assistant_message_count, tool_call_count, session_type
+        let sql = format!(
+            "SELECT {AGENT_SESSION_COLUMNS}
              FROM agent_sessions
              WHERE start_time <= ?2 AND (end_time IS NULL OR end_time >= ?1)
+               AND (?3 IS NULL OR source = ?3)
              ORDER BY start_time"
-        )?;
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
 
         let mut sessions = Vec::new();
-        let mut rows = stmt.query(params![format_timestamp(start), format_timestamp(end)])?;
+        let mut rows = stmt.query(params![
+            format_timestamp(start),
+            format_timestamp(end),
+            source.as_ref().map(tt_core::session::SessionSource::as_str),
+        ])?;
 
         while let Some(row) = rows.next()? {
-            let session_id: String = row.get(0)?;
-            let source_str: String = row.get(1)?;
-            let start_time_str: String = row.get(5)?;
-            let end_time_str: Option<String> = row.get(6)?;
-            let user_prompts_str: Option<String> = row.get(9)?;
+            if let Some(session) = Self::row_to_agent_session(row)? {
+                sessions.push(session);
+            }
+        }
 
-            let start_time = match DateTime::parse_from_rfc3339(&start_time_str) {
-                Ok(dt) => dt.with_timezone(&Utc),
-                Err(e) => {
-                    tracing::warn!(session_id, error = %e, "skipping session with malformed start_time");
-                    continue;
-                }
-            };
+        Ok(sessions)
+    }
 
-            let end_time = match end_time_str {
-                Some(s) => match DateTime::parse_from_rfc3339(&s) {
-                    Ok(dt) => Some(dt.with_timezone(&Utc)),
-                    Err(e) => {
-                        tracing::warn!(session_id, error = %e, "skipping session with malformed end_time");
-                        continue;
-                    }
-                },
-                None => None,
-            };
+    /// Links each agent session to the stream most of its events are assigned to.
+    ///
+    /// Only events from the session's own machine count (events without a
+    /// `machine_id` belong to `''` sessions), since machines can mint the same
+    /// session ID. Ties are broken by stream ID so the result is deterministic.
+    /// Sessions with no assigned events are unlinked (`stream_id` set to `NULL`).
+    ///
+    /// Returns the number of sessions updated.
+    pub fn link_sessions_to_streams(&self) -> Result<usize, DbError> {
+        let updated = self.conn.execute(
+            "UPDATE agent_sessions SET stream_id = (
+                SELECT e.stream_id FROM events e
+                WHERE e.session_id = agent_sessions.session_id
+                  AND COALESCE(e.machine_id, '') = agent_sessions.machine_id
+                  AND e.stream_id IS NOT NULL
+                GROUP BY e.stream_id
+                ORDER BY COUNT(*) DESC, e.stream_id
+                LIMIT 1
+             )",
+            [],
+        )?;
+        Ok(updated)
+    }
 
-            let user_prompts: Vec<String> = user_prompts_str
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or_default();
+    /// Get agent sessions linked to a stream, ordered by `start_time` ascending.
+    ///
+    /// Links are established by [`Database::link_sessions_to_streams`].
+    pub fn sessions_for_stream(
+        &self,
+        stream_id: &str,
+    ) -> Result<Vec<tt_core::session::AgentSession>, DbError> {
+        let sql = format!(
+            "SELECT {AGENT_SESSION_COLUMNS}
+             FROM agent_sessions
+             WHERE stream_id = ?1
+             ORDER BY start_time"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
 
-            sessions.push(tt_core::session::AgentSession {
-                session_id,
-                source: source_str.parse().unwrap_or_default(),
-                parent_session_id: row.get(2)?,
-                session_type: row.get::<_, String>(13)?.parse().unwrap_or_default(),
-                project_path: row.get(3)?,
-                project_name: row.get(4)?,
-                start_time,
-                end_time,
-                message_count: row.get(7)?,
-                summary: row.get(8)?,
-                user_prompts,
-                starting_prompt: row.get(10)?,
-                assistant_message_count: row.get(11)?,
-                tool_call_count: row.get(12)?,
-                // Not stored in database - events are created during indexing
-                user_message_timestamps: Vec::new(),
-                tool_call_timestamps: Vec::new(),
-            });
+        let mut sessions = Vec::new();
+        let mut rows = stmt.query(params![stream_id])?;
+        while let Some(row) = rows.next()? {
+            if let Some(session) = Self::row_to_agent_session(row)? {
+                sessions.push(session);
+            }
+        }
+
+        Ok(sessions)
+    }
+
+    /// Deletes agent sessions that finished before a cutoff.
+    ///
+    /// A session's age is its `end_time`, or its `start_time` when it has no end
+    /// yet, so ongoing sessions that started recently are kept.
+    /// Returns the number of sessions deleted.
+    pub fn delete_sessions_before(&self, cutoff: DateTime<Utc>) -> Result<u64, DbError> {
+        let count = self.conn.execute(
+            "DELETE FROM agent_sessions WHERE COALESCE(end_time, start_time) < ?1",
+            params![format_timestamp(cutoff)],
+        )?;
+        Ok(count as u64)
+    }
+
+    /// Get the direct subagent sessions spawned by a parent session.
+    ///
+    /// Sessions are returned ordered by `start_time` ascending.
+    pub fn get_subagents(
+        &self,
+        parent_session_id: &str,
+    ) -> Result<Vec<tt_core::session::AgentSession>, DbError> {
+        let sql = format!(
+            "SELECT {AGENT_SESSION_COLUMNS}
+             FROM agent_sessions
+             WHERE parent_session_id = ?1
+             ORDER BY start_time"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+
+        let mut sessions = Vec::new();
+        let mut rows = stmt.query(params![parent_session_id])?;
+        while let Some(row) = rows.next()? {
+            if let Some(session) = Self::row_to_agent_session(row)? {
+                sessions.push(session);
+            }
         }
 
         Ok(sessions)
     }
 
+    /// Returns distinct `session_id`s referenced by events but missing from
+    /// `agent_sessions`, sorted ascending.
+    ///
+    /// A non-empty result means session metadata import lagged event import.
+    pub fn events_with_unknown_session(&self) -> Result<Vec<String>, DbError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT e.session_id
+             FROM events e
+             LEFT JOIN agent_sessions s ON s.session_id = e.session_id
+             WHERE e.session_id IS NOT NULL AND s.session_id IS NULL
+             ORDER BY e.session_id",
+        )?;
+
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect::<Result<Vec<String>, _>>().map_err(Into::into)
+    }
+
+    /// Converts a row selected with [`AGENT_SESSION_COLUMNS`] into an `AgentSession`.
+    ///
+    /// Returns `None` (with a warning) for rows with malformed timestamps.
+    fn row_to_agent_session(
+        row: &rusqlite::Row<'_>,
+    ) -> Result<Option<tt_core::session::AgentSession>, rusqlite::Error> {
+        let session_id: String = row.get(0)?;
+        let source_str: String = row.get(1)?;
+        let start_time_str: String = row.get(5)?;
+        let end_time_str: Option<String> = row.get(6)?;
+        let user_prompts_str: Option<String> = row.get(9)?;
+
+        let start_time = match DateTime::parse_from_rfc3339(&start_time_str) {
+            Ok(dt) => dt.with_timezone(&Utc),
+            Err(e) => {
+                tracing::warn!(skipped = true, session_id, error = %e, "skipping session with malformed start_time");
+                return Ok(None);
+            }
+        };
+
+        let end_time = match end_time_str {
+            Some(s) => match DateTime::parse_from_rfc3339(&s) {
+                Ok(dt) => Some(dt.with_timezone(&Utc)),
+                Err(e) => {
+                    tracing::warn!(skipped = true, session_id, error = %e, "skipping session with malformed end_time");
+                    return Ok(None);
+                }
+            },
+            None => None,
+        };
+
+        let user_prompts: Vec<String> = user_prompts_str
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+
+        Ok(Some(tt_core::session::AgentSession {
+            session_id,
+            source: source_str.parse().unwrap_or_default(),
+            parent_session_id: row.get(2)?,
+            session_type: row.get::<_, String>(13)?.parse().unwrap_or_default(),
+            project_path: row.get(3)?,
+            project_name: row.get(4)?,
+            start_time,
+            end_time,
+            message_count: row.get(7)?,
+            summary: row.get(8)?,
+            user_prompts,
+            starting_prompt: row.get(10)?,
+            assistant_message_count: row.get(11)?,
+            tool_call_count: row.get(12)?,
+            user_message_timestamps: timestamps_from_json(
+                row.get::<_, Option<String>>(14)?.as_deref(),
+            ),
+            tool_call_timestamps: timestamps_from_json(
+                row.get::<_, Option<String>>(15)?.as_deref(),
+            ),
+        }))
+    }
+
     /// Retrieves streams that overlap with a time range.
     ///
     /// A stream overlaps if:
@@ -1490,7 +2311,7 @@ impl Database {
             let last_timestamp = match DateTime::parse_from_rfc3339(&timestamp_str) {
                 Ok(dt) => dt.with_timezone(&Utc),
                 Err(e) => {
-                    tracing::warn!(source = %source, error = %e, "skipping source with malformed timestamp");
+                    tracing::warn!(skipped = true, source = %source, error = %e, "skipping source with malformed timestamp");
                     continue;
                 }
             };
@@ -1504,6 +2325,132 @@ impl Database {
         Ok(statuses)
     }
 
+    /// Returns the most recent event timestamp for each machine, ordered by machine ID.
+    ///
+    /// Events without a `machine_id` are ignored.
+    pub fn get_last_event_per_machine(&self) -> Result<Vec<(String, DateTime<Utc>)>, DbError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT machine_id, MAX(timestamp)
+             FROM events
+             WHERE machine_id IS NOT NULL
+             GROUP BY machine_id
+             ORDER BY machine_id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut latest = Vec::new();
+        for row_result in rows {
+            let (machine_id, timestamp_str) = row_result?;
+            match DateTime::parse_from_rfc3339(&timestamp_str) {
+                Ok(dt) => latest.push((machine_id, dt.with_timezone(&Utc))),
+                Err(e) => {
+                    tracing::warn!(skipped = true, machine_id = %machine_id, error = %e, "skipping machine with malformed timestamp");
+                }
+            }
+        }
+
+        Ok(latest)
+    }
+
+    /// Returns the number of events stored per source, ordered by source.
+    pub fn count_events_by_source(&self) -> Result<Vec<(String, usize)>, DbError> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT source, COUNT(*) FROM events GROUP BY source ORDER BY source")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Returns the earliest and latest event timestamps.
+    ///
+    /// Returns `None` if the database has no events or either bound is malformed.
+    pub fn event_time_range(&self) -> Result<Option<EventTimeRange>, DbError> {
+        let (min, max): (Option<String>, Option<String>) = self.conn.query_row(
+            "SELECT MIN(timestamp), MAX(timestamp) FROM events",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        let (Some(min), Some(max)) = (min, max) else {
+            return Ok(None);
+        };
+        if let (Ok(min), Ok(max)) = (
+            DateTime::parse_from_rfc3339(&min),
+            DateTime::parse_from_rfc3339(&max),
+        ) {
+            Ok(Some((min.with_timezone(&Utc), max.with_timezone(&Utc))))
+        } else {
+            tracing::warn!(%min, %max, "malformed event timestamp bounds");
+            Ok(None)
+        }
+    }
+
+    /// Returns the most recent event timestamp across all sources.
+    ///
+    /// Returns `None` if the database has no events or the latest timestamp is malformed.
+    pub fn latest_event_timestamp(&self) -> Result<Option<DateTime<Utc>>, DbError> {
+        let max: Option<String> =
+            self.conn
+                .query_row("SELECT MAX(timestamp) FROM events", [], |row| row.get(0))?;
+        let Some(max) = max else {
+            return Ok(None);
+        };
+        match DateTime::parse_from_rfc3339(&max) {
+            Ok(dt) => Ok(Some(dt.with_timezone(&Utc))),
+            Err(e) => {
+                tracing::warn!(%max, error = %e, "malformed latest event timestamp");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns each stream's most frequent `git_project`, keyed by stream ID.
+    ///
+    /// Ties go to the alphabetically first project. Streams whose events carry
+    /// no project are absent from the map.
+    pub fn dominant_git_projects(
+        &self,
+    ) -> Result<std::collections::HashMap<String, String>, DbError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT stream_id, git_project, COUNT(*) AS n FROM events
+             WHERE stream_id IS NOT NULL AND git_project IS NOT NULL
+             GROUP BY stream_id, git_project
+             ORDER BY stream_id, n DESC, git_project ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut projects = std::collections::HashMap::new();
+        for row in rows {
+            let (stream_id, project) = row?;
+            projects.entry(stream_id).or_insert(project);
+        }
+        Ok(projects)
+    }
+
+    /// Returns each distinct `git_project` with its event count.
+    ///
+    /// Events without a project are excluded. Ordered by count descending, ties
+    /// alphabetically.
+    pub fn get_distinct_projects(&self) -> Result<Vec<(String, u64)>, DbError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT git_project, COUNT(*) AS n FROM events
+             WHERE git_project IS NOT NULL
+             GROUP BY git_project
+             ORDER BY n DESC, git_project ASC",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Returns the number of stored agent sessions.
+    pub fn count_agent_sessions(&self) -> Result<usize, DbError> {
+        Ok(self
+            .conn
+            .query_row("SELECT COUNT(*) FROM agent_sessions", [], |row| row.get(0))?)
+    }
+
     /// Inserts or updates a machine entry, including sync position.
     pub fn upsert_machine(
         &self,
@@ -1636,6 +2583,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: None,
             cwd: Some("/home/sami/project-x".to_string()),
             session_id: None,
@@ -1672,6 +2620,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: None,
             cwd: Some("/home/sami/project".to_string()),
             session_id: Some("abc123".to_string()),
@@ -1822,6 +2771,91 @@ mod tests {
         assert_eq!(events[0].id, "e2");
     }
 
+    #[test]
+    fn test_count_events_in_range_matches_get_events() {
+        let db = Database::open_in_memory().unwrap();
+
+        for (id, hour) in [("e1", 10), ("e2", 11), ("e3", 12), ("e4", 13)] {
+            let ts = Utc.with_ymd_and_hms(2025, 1, 15, hour, 0, 0).unwrap();
+            db.insert_event(&make_event(id, ts, tt_core::EventType::TmuxPaneFocus))
+                .unwrap();
+        }
+
+        let after = Some(Utc.with_ymd_and_hms(2025, 1, 15, 10, 30, 0).unwrap());
+        let before = Some(Utc.with_ymd_and_hms(2025, 1, 15, 12, 30, 0).unwrap());
+        for (after, before) in [(None, None), (after, None), (None, before), (after, before)] {
+            assert_eq!(
+                db.count_events_in_range(after, before).unwrap(),
+                db.get_events(after, before).unwrap().len(),
+                "after={after:?} before={before:?}"
+            );
+        }
+        assert_eq!(db.count_events_in_range(after, before).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_count_events_in_range_skips_malformed_rows() {
+        let db = Database::open_in_memory().unwrap();
+
+        let ts = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        db.insert_event(&make_event("valid", ts, tt_core::EventType::TmuxPaneFocus))
+            .unwrap();
+        db.conn
+            .execute(
+                "INSERT INTO events (id, timestamp, type, source, schema_version)
+                 VALUES ('bad-ts', 'not a valid timestamp', 'tmux_pane_focus', 'test', 1),
+                        ('bad-type', '2025-01-15T11:00:00Z', 'unknown_type', 'test', 1)",
+                [],
+            )
+            .unwrap();
+
+        let after = Some(Utc.with_ymd_and_hms(2025, 1, 15, 9, 0, 0).unwrap());
+        for (after, before) in [(None, None), (after, None)] {
+            assert_eq!(db.get_events(after, before).unwrap().len(), 1);
+            assert_eq!(db.count_events_in_range(after, before).unwrap(), 1);
+        }
+    }
+
+    #[test]
+    fn test_export_events_jsonl_round_trips_into_fresh_database() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_stream(&make_stream("s1", Some("assigned")))
+            .unwrap();
+
+        let ts1 = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        let ts2 = Utc.with_ymd_and_hms(2025, 1, 15, 11, 0, 0).unwrap();
+        let mut focus = make_event("e1", ts1, tt_core::EventType::TmuxPaneFocus);
+        focus.stream_id = Some("s1".to_string());
+        focus.assignment_source = Some("user".to_string());
+        let mut afk = make_event("e2", ts2, tt_core::EventType::AfkChange);
+        afk.status = Some("idle".to_string());
+        afk.idle_duration_ms = Some(60_000);
+        db.insert_events(&[afk, focus]).unwrap();
+
+        let mut dump = Vec::new();
+        assert_eq!(db.export_events_jsonl(&mut dump).unwrap(), 2);
+
+        let restored = Database::open_in_memory().unwrap();
+        restored
+            .insert_stream(&make_stream("s1", Some("assigned")))
+            .unwrap();
+        let events: Vec<StoredEvent> = String::from_utf8(dump)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let mut event: StoredEvent = serde_json::from_str(line).unwrap();
+                event.data = event.build_data_json();
+                event
+            })
+            .collect();
+        restored.insert_events(&events).unwrap();
+
+        let original = db.get_events(None, None).unwrap();
+        assert_eq!(original[0].stream_id.as_deref(), Some("s1"));
+        assert_eq!(original[0].assignment_source.as_deref(), Some("user"));
+        assert_eq!(restored.get_events(None, None).unwrap(), original);
+    }
+
     #[test]
     fn test_get_events_ordered_by_timestamp() {
         let db = Database::open_in_memory().unwrap();
@@ -1871,36 +2905,269 @@ mod tests {
     }
 
     #[test]
-    fn test_get_events_in_range_ordered() {
+    fn test_get_event_by_id() {
         let db = Database::open_in_memory().unwrap();
-
-        // Insert out of order
-        let ts2 = Utc.with_ymd_and_hms(2025, 1, 15, 11, 0, 0).unwrap();
-        let ts1 = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
-        let ts3 = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
-
-        db.insert_event(&make_event("e2", ts2, tt_core::EventType::TmuxPaneFocus))
-            .unwrap();
-        db.insert_event(&make_event("e1", ts1, tt_core::EventType::TmuxPaneFocus))
-            .unwrap();
-        db.insert_event(&make_event("e3", ts3, tt_core::EventType::TmuxPaneFocus))
+        let ts = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        let mut event = make_event("e1", ts, tt_core::EventType::TmuxPaneFocus);
+        event.cwd = Some("/home/user/project".to_string());
+        db.insert_event(&event).unwrap();
+        db.insert_event(&make_event("e2", ts, tt_core::EventType::AfkChange))
             .unwrap();
 
-        let start = Utc.with_ymd_and_hms(2025, 1, 15, 9, 0, 0).unwrap();
-        let end = Utc.with_ymd_and_hms(2025, 1, 15, 13, 0, 0).unwrap();
-        let events = db.get_events_in_range(start, end).unwrap();
+        let fetched = db.get_event("e1").unwrap().expect("event should exist");
+        assert_eq!(fetched.id, "e1");
+        assert_eq!(fetched.timestamp, ts);
+        assert_eq!(fetched.event_type, tt_core::EventType::TmuxPaneFocus);
+        assert_eq!(fetched.cwd.as_deref(), Some("/home/user/project"));
 
-        assert_eq!(events.len(), 3);
-        assert_eq!(events[0].id, "e1");
-        assert_eq!(events[1].id, "e2");
-        assert_eq!(events[2].id, "e3");
+        assert!(db.get_event("missing").unwrap().is_none());
     }
 
     #[test]
-    fn test_get_events_in_range_empty() {
-        let db = Database::open_in_memory().unwrap();
-
-        let ts = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+    fn test_from_export_line_covers_each_event_type() {
+        const MACHINE: &str = "11111111-2222-4333-8444-555555555555";
+        let line = |event_type: &str, source: &str, fields: &str| {
+            format!(
+                r#"{{"id":"{MACHINE}:{source}:{event_type}:2025-01-29T12:00:00.000Z:x","timestamp":"2025-01-29T12:00:00.000Z","source":"{source}","type":"{event_type}"{fields}}}"#
+            )
+        };
+        let parse = |event_type, source, fields| {
+            StoredEvent::from_export_line(&line(event_type, source, fields)).unwrap()
+        };
+
+        let focus = parse(
+            "tmux_pane_focus",
+            "remote.tmux",
+            r#","pane_id":"%1","tmux_session":"main","window_index":2,"cwd":"/tmp","git_project":"tt""#,
+        );
+        assert_eq!(focus.event_type, tt_core::EventType::TmuxPaneFocus);
+        assert_eq!(focus.machine_id.as_deref(), Some(MACHINE));
+        assert_eq!(
+            (focus.pane_id.as_deref(), focus.tmux_session.as_deref()),
+            (Some("%1"), Some("main"))
+        );
+        assert_eq!(focus.window_index, Some(2));
+        assert_eq!(focus.git_project.as_deref(), Some("tt"));
+        assert_eq!(focus.data["cwd"], "/tmp");
+
+        let scroll = parse(
+            "tmux_scroll",
+            "remote.tmux",
+            r#","pane_id":"%2","cwd":"/src""#,
+        );
+        assert_eq!(scroll.event_type, tt_core::EventType::TmuxScroll);
+        assert_eq!(scroll.cwd.as_deref(), Some("/src"));
+
+        let afk = parse(
+            "afk_change",
+            "remote.afk",
+            r#","status":"idle","idle_duration_ms":300000"#,
+        );
+        assert_eq!(afk.status.as_deref(), Some("idle"));
+        assert_eq!(afk.idle_duration_ms, Some(300_000));
+
+        let window = parse(
+            "window_focus",
+            "local.window",
+            r#","window_app_id":"firefox","window_title":"Docs""#,
+        );
+        assert_eq!(window.window_app_id.as_deref(), Some("firefox"));
+        assert_eq!(window.window_title.as_deref(), Some("Docs"));
+
+        let tab = parse(
+            "browser_tab",
+            "local.browser",
+            r#","url":"https://example.com""#,
+        );
+        assert_eq!(tab.url.as_deref(), Some("https://example.com"));
+
+        let session = parse(
+            "agent_session",
+            "remote.agent",
+            r#","action":"started","agent":"claude","session_id":"sess-1","cwd":"/w""#,
+        );
+        assert_eq!(session.action.as_deref(), Some("started"));
+        assert_eq!(session.session_id.as_deref(), Some("sess-1"));
+        assert_eq!(session.cwd.as_deref(), Some("/w"));
+
+        let tool = parse(
+            "agent_tool_use",
+            "remote.agent",
+            r#","agent":"claude","session_id":"sess-1","tool":"Edit","cwd":"/w""#,
+        );
+        assert_eq!(tool.event_type, tt_core::EventType::AgentToolUse);
+        assert_eq!(tool.session_id.as_deref(), Some("sess-1"));
+
+        let message = parse(
+            "user_message",
+            "remote.agent",
+            r#","session_id":"sess-1","length":12,"stream_id":"s1","assignment_source":"user""#,
+        );
+        assert_eq!(message.event_type, tt_core::EventType::UserMessage);
+        // Streams are local to each database
+        assert_eq!((message.stream_id, message.assignment_source), (None, None));
+    }
+
+    #[test]
+    fn test_from_export_line_rewrites_legacy_session_types_and_rejects_garbage() {
+        let event = StoredEvent::from_export_line(
+            r#"{"id":"e1","timestamp":"2025-01-29T12:00:00Z","source":"remote.agent","type":"session_end","session_id":"s"}"#,
+        )
+        .unwrap();
+        assert_eq!(event.event_type, tt_core::EventType::AgentSession);
+        assert_eq!(event.action.as_deref(), Some("ended"));
+        assert_eq!(event.machine_id, None);
+
+        assert!(StoredEvent::from_export_line("not json").is_err());
+        assert!(StoredEvent::from_export_line(r#"{"id":"e2"}"#).is_err());
+    }
+
+    #[test]
+    fn test_find_and_delete_duplicate_events() {
+        let db = Database::open_in_memory().unwrap();
+        let ts = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        let event = |id: &str, session: &str| {
+            let mut event = make_event(id, ts, tt_core::EventType::UserMessage);
+            event.session_id = Some(session.to_string());
+            event
+        };
+        db.insert_events(&[
+            event("old-format-id", "sess-1"),
+            event("new-format-id", "sess-1"),
+            event("other-session", "sess-2"),
+        ])
+        .unwrap();
+
+        let groups = db.find_duplicate_events().unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].event_type, "user_message");
+        assert_eq!(groups[0].session_id.as_deref(), Some("sess-1"));
+        assert_eq!(groups[0].stream_id, None);
+        assert_eq!(groups[0].ids, vec!["new-format-id", "old-format-id"]);
+
+        let deleted = db.delete_events(&["old-format-id".to_string()]).unwrap();
+        assert_eq!(deleted, 1);
+        assert!(db.find_duplicate_events().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_event_counts_and_time_range() {
+        let db = Database::open_in_memory().unwrap();
+        assert!(db.event_time_range().unwrap().is_none());
+        assert!(db.count_events_by_source().unwrap().is_empty());
+
+        let ts1 = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        let ts2 = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+        db.insert_event(&make_event("e1", ts2, tt_core::EventType::TmuxPaneFocus))
+            .unwrap();
+        db.insert_event(&make_event("e2", ts1, tt_core::EventType::TmuxPaneFocus))
+            .unwrap();
+        db.insert_event(&make_event_with_source("e3", ts1, "local.window"))
+            .unwrap();
+
+        assert_eq!(db.event_time_range().unwrap(), Some((ts1, ts2)));
+        assert_eq!(
+            db.count_events_by_source().unwrap(),
+            vec![
+                ("local.window".to_string(), 1),
+                ("remote.tmux".to_string(), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dominant_git_projects() {
+        let db = Database::open_in_memory().unwrap();
+        let ts = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        db.insert_stream(&make_stream("s1", Some("one"))).unwrap();
+        db.insert_stream(&make_stream("s2", Some("two"))).unwrap();
+        db.insert_stream(&make_stream("s3", Some("three"))).unwrap();
+        let event = |id: &str, stream: &str, project: Option<&str>| {
+            let mut event = make_event(id, ts, tt_core::EventType::TmuxPaneFocus);
+            event.stream_id = Some(stream.to_string());
+            event.git_project = project.map(str::to_string);
+            event
+        };
+        db.insert_events(&[
+            event("e1", "s1", Some("beta")),
+            event("e2", "s1", Some("alpha")),
+            event("e3", "s1", Some("beta")),
+            event("e4", "s1", None),
+            event("e5", "s2", Some("zeta")),
+            event("e6", "s2", Some("gamma")),
+            event("e7", "s3", None),
+        ])
+        .unwrap();
+
+        let projects = db.dominant_git_projects().unwrap();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects["s1"], "beta");
+        // Tie broken alphabetically
+        assert_eq!(projects["s2"], "gamma");
+    }
+
+    #[test]
+    fn test_get_distinct_projects() {
+        let db = Database::open_in_memory().unwrap();
+        let ts = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        let event = |id: &str, project: Option<&str>| {
+            let mut event = make_event(id, ts, tt_core::EventType::TmuxPaneFocus);
+            event.git_project = project.map(str::to_string);
+            event
+        };
+        db.insert_events(&[
+            event("e1", Some("beta")),
+            event("e2", Some("alpha")),
+            event("e3", Some("beta")),
+            event("e4", None),
+            event("e5", Some("gamma")),
+            event("e6", None),
+            event("e7", Some("beta")),
+        ])
+        .unwrap();
+
+        let projects = db.get_distinct_projects().unwrap();
+        assert_eq!(
+            projects,
+            vec![
+                ("beta".to_string(), 3),
+                ("alpha".to_string(), 1),
+                ("gamma".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_events_in_range_ordered() {
+        let db = Database::open_in_memory().unwrap();
+
+        // Insert out of order
+        let ts2 = Utc.with_ymd_and_hms(2025, 1, 15, 11, 0, 0).unwrap();
+        let ts1 = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        let ts3 = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap();
+
+        db.insert_event(&make_event("e2", ts2, tt_core::EventType::TmuxPaneFocus))
+            .unwrap();
+        db.insert_event(&make_event("e1", ts1, tt_core::EventType::TmuxPaneFocus))
+            .unwrap();
+        db.insert_event(&make_event("e3", ts3, tt_core::EventType::TmuxPaneFocus))
+            .unwrap();
+
+        let start = Utc.with_ymd_and_hms(2025, 1, 15, 9, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 1, 15, 13, 0, 0).unwrap();
+        let events = db.get_events_in_range(start, end).unwrap();
+
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].id, "e1");
+        assert_eq!(events[1].id, "e2");
+        assert_eq!(events[2].id, "e3");
+    }
+
+    #[test]
+    fn test_get_events_in_range_empty() {
+        let db = Database::open_in_memory().unwrap();
+
+        let ts = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
         db.insert_event(&make_event("e1", ts, tt_core::EventType::TmuxPaneFocus))
             .unwrap();
 
@@ -1997,6 +3264,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: None,
             cwd: None,
             session_id: None,
@@ -2102,6 +3370,85 @@ mod tests {
         assert_eq!(events[0].id, "valid");
     }
 
+    #[test]
+    fn test_backup_to_file_roundtrip() {
+        let db = Database::open_in_memory().unwrap();
+        let ts = Utc.with_ymd_and_hms(2026, 1, 29, 10, 0, 0).unwrap();
+        db.insert_event(&make_event("e1", ts, tt_core::EventType::TmuxPaneFocus))
+            .unwrap();
+        db.insert_stream(&make_stream("s1", Some("backed-up")))
+            .unwrap();
+        db.add_tag("s1", "acme").unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let backup_path = temp_dir.path().join("backup.db");
+        db.backup_to(&backup_path).unwrap();
+
+        let restored = Database::open(&backup_path).unwrap();
+        let integrity: String = restored
+            .conn
+            .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(integrity, "ok");
+        assert_eq!(restored.get_events(None, None).unwrap().len(), 1);
+        assert_eq!(
+            restored.get_stream("s1").unwrap().unwrap().name.as_deref(),
+            Some("backed-up")
+        );
+        assert_eq!(restored.get_tags("s1").unwrap(), vec!["acme"]);
+    }
+
+    #[test]
+    fn test_open_with_attached_merges_events_and_streams() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let primary_path = temp_dir.path().join("client-a.db");
+        let other_path = temp_dir.path().join("client-b.db");
+        let ts1 = Utc.with_ymd_and_hms(2026, 1, 29, 10, 0, 0).unwrap();
+        let ts2 = Utc.with_ymd_and_hms(2026, 1, 29, 11, 0, 0).unwrap();
+        let ts3 = Utc.with_ymd_and_hms(2026, 1, 29, 12, 0, 0).unwrap();
+        {
+            let primary = Database::open(&primary_path).unwrap();
+            primary
+                .insert_event(&make_event("a1", ts1, tt_core::EventType::TmuxPaneFocus))
+                .unwrap();
+            primary
+                .insert_event(&make_event("a2", ts3, tt_core::EventType::TmuxPaneFocus))
+                .unwrap();
+            primary
+                .insert_stream(&make_stream("sa", Some("a")))
+                .unwrap();
+            let other = Database::open(&other_path).unwrap();
+            other
+                .insert_event(&make_event("b1", ts2, tt_core::EventType::TmuxPaneFocus))
+                .unwrap();
+            other.insert_stream(&make_stream("sb", Some("b"))).unwrap();
+        }
+
+        let db = Database::open_with_attached(&primary_path, &[other_path.as_path()]).unwrap();
+
+        let ids: Vec<String> = db
+            .get_events_in_range(ts1, ts3)
+            .unwrap()
+            .into_iter()
+            .map(|event| event.id)
+            .collect();
+        assert_eq!(ids, vec!["a1", "b1", "a2"]);
+        let mut streams: Vec<String> = db
+            .get_streams()
+            .unwrap()
+            .into_iter()
+            .map(|stream| stream.id)
+            .collect();
+        streams.sort();
+        assert_eq!(streams, vec!["sa", "sb"]);
+
+        // Writes still go to the primary database only.
+        db.insert_event(&make_event("a3", ts3, tt_core::EventType::TmuxPaneFocus))
+            .unwrap();
+        let other = Database::open(&other_path).unwrap();
+        assert_eq!(other.get_events(None, None).unwrap().len(), 1);
+    }
+
     #[test]
     fn test_schema_version_check() {
         // Create a temporary database file
@@ -2128,7 +3475,7 @@ mod tests {
                 assert_eq!(found, 1);
                 assert_eq!(expected, SCHEMA_VERSION);
             }
-            DbError::Sqlite(_) => panic!("expected SchemaVersionMismatch error"),
+            DbError::Sqlite(_) | DbError::Io(_) => panic!("expected SchemaVersionMismatch error"),
         }
     }
 
@@ -2198,20 +3545,21 @@ mod tests {
     #[test]
     fn test_open_fails_on_newer_schema() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let db_path = temp_dir.path().join("v10.db");
+        let db_path = temp_dir.path().join("newer.db");
+        let newer = SCHEMA_VERSION + 1;
 
         {
             let conn = Connection::open(&db_path).unwrap();
-            conn.execute_batch(
+            conn.execute_batch(&format!(
                 "CREATE TABLE schema_info (version INTEGER NOT NULL);
-                 INSERT INTO schema_info (version) VALUES (10);",
-            )
+                 INSERT INTO schema_info (version) VALUES ({newer});"
+            ))
             .unwrap();
         }
 
         assert!(matches!(
             Database::open(&db_path),
-            Err(DbError::SchemaVersionMismatch { found: 10, .. })
+            Err(DbError::SchemaVersionMismatch { found, .. }) if found == newer
         ));
     }
 
@@ -2232,6 +3580,7 @@ mod tests {
             idle_duration_ms: None,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: None,
             cwd: None,
             session_id: None,
@@ -2241,6 +3590,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_latest_event_timestamp() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.latest_event_timestamp().unwrap(), None);
+
+        let earlier = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        let latest = Utc.with_ymd_and_hms(2025, 1, 15, 11, 30, 0).unwrap();
+        db.insert_events(&[
+            make_event_with_source("e1", latest, "remote.agent"),
+            make_event_with_source("e2", earlier, "remote.tmux"),
+        ])
+        .unwrap();
+
+        assert_eq!(db.latest_event_timestamp().unwrap(), Some(latest));
+    }
+
     #[test]
     fn test_get_last_event_per_source_empty() {
         let db = Database::open_in_memory().unwrap();
@@ -2267,6 +3632,29 @@ mod tests {
         assert_eq!(statuses[0].last_timestamp, ts2); // Should be the later timestamp
     }
 
+    #[test]
+    fn test_get_last_event_per_machine_ignores_events_without_machine() {
+        let db = Database::open_in_memory().unwrap();
+        let at = |h| Utc.with_ymd_and_hms(2025, 1, 15, h, 0, 0).unwrap();
+        let with_machine = |id: &str, h, machine: Option<&str>| {
+            let mut event = make_event_with_source(id, at(h), "remote.tmux");
+            event.machine_id = machine.map(String::from);
+            event
+        };
+        db.insert_events(&[
+            with_machine("e1", 9, Some("m-b")),
+            with_machine("e2", 11, Some("m-b")),
+            with_machine("e3", 10, Some("m-a")),
+            with_machine("e4", 12, None),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            db.get_last_event_per_machine().unwrap(),
+            vec![("m-a".to_string(), at(10)), ("m-b".to_string(), at(11))]
+        );
+    }
+
     #[test]
     fn test_get_last_event_per_source_multiple_sources() {
         let db = Database::open_in_memory().unwrap();
@@ -2339,6 +3727,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_stream_total_ms() {
+        let mut stream = make_stream("s1", None);
+        assert_eq!(stream.total_ms(), 0);
+
+        stream.time_direct_ms = 1_500;
+        stream.time_delegated_ms = 2_500;
+        assert_eq!(stream.total_ms(), 4_000);
+    }
+
+    #[test]
+    fn test_stream_direct_ratio() {
+        let mut stream = make_stream("s1", None);
+        assert!(
+            stream.direct_ratio().abs() < f64::EPSILON,
+            "zero total is 0.0"
+        );
+
+        stream.time_direct_ms = 1_000;
+        stream.time_delegated_ms = 3_000;
+        assert!((stream.direct_ratio() - 0.25).abs() < f64::EPSILON);
+
+        stream.time_delegated_ms = 0;
+        assert!((stream.direct_ratio() - 1.0).abs() < f64::EPSILON);
+
+        stream.time_direct_ms = 0;
+        stream.time_delegated_ms = 5_000;
+        assert!(stream.direct_ratio().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_stream_duration() {
+        let first = Utc.with_ymd_and_hms(2026, 1, 29, 10, 0, 0).unwrap();
+        let last = Utc.with_ymd_and_hms(2026, 1, 29, 11, 30, 0).unwrap();
+
+        let mut stream = make_stream("s1", None);
+        assert_eq!(stream.duration(), None);
+
+        stream.first_event_at = Some(first);
+        assert_eq!(stream.duration(), None, "missing last_event_at");
+
+        stream.last_event_at = Some(last);
+        assert_eq!(stream.duration(), Some(chrono::Duration::minutes(90)));
+
+        stream.last_event_at = Some(first);
+        assert_eq!(stream.duration(), Some(chrono::Duration::zero()));
+    }
+
     #[test]
     fn test_insert_and_get_stream() {
         let db = Database::open_in_memory().unwrap();
@@ -2381,6 +3817,31 @@ mod tests {
         assert_eq!(streams.len(), 3);
     }
 
+    #[test]
+    fn test_get_streams_paginated_pages_in_updated_at_order() {
+        let db = Database::open_in_memory().unwrap();
+        let base = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        for i in 0..5 {
+            let mut stream = make_stream(&format!("s{i}"), None);
+            stream.updated_at = base + chrono::Duration::minutes(i);
+            db.insert_stream(&stream).unwrap();
+        }
+
+        let ids = |limit, offset| -> Vec<String> {
+            db.get_streams_paginated(limit, offset)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.id)
+                .collect()
+        };
+
+        assert_eq!(db.count_streams().unwrap(), 5);
+        assert_eq!(ids(2, 0), vec!["s4", "s3"]);
+        assert_eq!(ids(2, 2), vec!["s2", "s1"]);
+        assert_eq!(ids(2, 4), vec!["s0"]);
+        assert!(ids(2, 6).is_empty());
+    }
+
     #[test]
     fn test_assign_event_to_stream() {
         let db = Database::open_in_memory().unwrap();
@@ -2539,6 +4000,56 @@ mod tests {
         assert_eq!(events.len(), 2);
     }
 
+    #[test]
+    fn test_reassign_events_by_session_moves_all_session_events() {
+        let db = Database::open_in_memory().unwrap();
+        let ts = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        db.insert_stream(&make_stream("s1", Some("scattered-a")))
+            .unwrap();
+        db.insert_stream(&make_stream("s2", Some("scattered-b")))
+            .unwrap();
+        db.insert_stream(&make_stream("target", Some("target")))
+            .unwrap();
+        let event = |id: &str, session: &str, stream: &str, source: &str| {
+            let mut event = make_event(id, ts, tt_core::EventType::UserMessage);
+            event.session_id = Some(session.to_string());
+            event.stream_id = Some(stream.to_string());
+            event.assignment_source = Some(source.to_string());
+            event
+        };
+        db.insert_events(&[
+            event("e1", "sess-1", "s1", "inferred"),
+            event("e2", "sess-1", "s2", "user"),
+            event("e3", "sess-2", "s1", "inferred"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            db.stream_ids_for_session("sess-1").unwrap(),
+            vec!["s1", "s2"]
+        );
+        let count = db
+            .reassign_events_by_session("sess-1", "target", "user")
+            .unwrap();
+
+        assert_eq!(count, 2);
+        let moved: Vec<_> = db
+            .get_events_by_stream("target")
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.id, e.assignment_source))
+            .collect();
+        assert_eq!(
+            moved,
+            vec![
+                ("e1".to_string(), Some("user".to_string())),
+                ("e2".to_string(), Some("user".to_string())),
+            ]
+        );
+        // Other sessions are untouched
+        assert_eq!(db.get_events_by_stream("s1").unwrap()[0].id, "e3");
+    }
+
     #[test]
     fn test_assign_events_by_ids_assigns_requested_ids_only() {
         let db = Database::open_in_memory().unwrap();
@@ -2666,6 +4177,58 @@ mod tests {
         assert_eq!(assigned[0].id, "e2");
     }
 
+    #[test]
+    fn test_clear_inferred_assignments_for_project() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_stream(&make_stream("s1", Some("acme"))).unwrap();
+        db.insert_stream(&make_stream("s2", Some("other"))).unwrap();
+
+        let base = Utc.with_ymd_and_hms(2025, 1, 15, 10, 0, 0).unwrap();
+        let events = [
+            ("acme-inferred", "acme", "s1", "inferred"),
+            ("acme-auto", "acme", "s1", "auto"),
+            ("acme-user", "acme", "s1", "user"),
+            ("other-inferred", "other", "s2", "inferred"),
+        ];
+        for (i, (id, project, stream, source)) in events.iter().enumerate() {
+            let mut event = make_event(
+                id,
+                base + chrono::Duration::minutes(i64::try_from(i).unwrap()),
+                tt_core::EventType::TmuxPaneFocus,
+            );
+            event.git_project = Some((*project).to_string());
+            db.insert_event(&event).unwrap();
+            db.assign_event_to_stream(id, stream, source).unwrap();
+        }
+
+        let cleared = db.clear_inferred_assignments_for_project("acme").unwrap();
+        assert_eq!(cleared, 1);
+
+        let unassigned: Vec<String> = db
+            .get_events_without_stream()
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(unassigned, vec!["acme-inferred"]);
+        let acme: Vec<String> = db
+            .get_events_by_git_project("acme")
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(acme, vec!["acme-inferred", "acme-auto", "acme-user"]);
+        let ids = |stream: &str| -> Vec<String> {
+            db.get_events_by_stream(stream)
+                .unwrap()
+                .into_iter()
+                .map(|e| e.id)
+                .collect()
+        };
+        assert_eq!(ids("s1"), vec!["acme-auto", "acme-user"]);
+        assert_eq!(ids("s2"), vec!["other-inferred"]);
+    }
+
     // ========== Tag Tests ==========
 
     #[test]
@@ -2733,6 +4296,69 @@ mod tests {
         assert_eq!(tags, vec!["urgent"]);
     }
 
+    #[test]
+    fn test_clear_tags() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_stream(&make_stream("s1", Some("project-x")))
+            .unwrap();
+        db.insert_stream(&make_stream("s2", Some("project-y")))
+            .unwrap();
+        db.add_tag("s1", "acme-webapp").unwrap();
+        db.add_tag("s1", "urgent").unwrap();
+        db.add_tag("s2", "urgent").unwrap();
+
+        assert_eq!(db.clear_tags("s1").unwrap(), 2);
+        assert!(db.get_tags("s1").unwrap().is_empty());
+        assert_eq!(db.get_tags("s2").unwrap(), vec!["urgent"]);
+        assert_eq!(db.clear_tags("s1").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_rename_tag_for_stream_leaves_other_streams_untouched() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_stream(&make_stream("s1", Some("project-x")))
+            .unwrap();
+        db.insert_stream(&make_stream("s2", Some("project-y")))
+            .unwrap();
+        db.add_tag("s1", "acme").unwrap();
+        db.add_tag("s2", "acme").unwrap();
+
+        assert!(db.rename_tag_for_stream("s1", "acme", "acme-corp").unwrap());
+        assert_eq!(db.get_tags("s1").unwrap(), vec!["acme-corp"]);
+        assert_eq!(db.get_tags("s2").unwrap(), vec!["acme"]);
+
+        // Missing `old` changes nothing; `new` already present collapses into it
+        assert!(!db.rename_tag_for_stream("s1", "acme", "other").unwrap());
+        db.add_tag("s2", "acme-corp").unwrap();
+        assert!(db.rename_tag_for_stream("s2", "acme", "acme-corp").unwrap());
+        assert_eq!(db.get_tags("s2").unwrap(), vec!["acme-corp"]);
+    }
+
+    #[test]
+    fn test_merge_tags_collapses_from_into_to() {
+        let db = Database::open_in_memory().unwrap();
+        for id in ["from-only", "to-only", "both", "neither"] {
+            db.insert_stream(&make_stream(id, None)).unwrap();
+        }
+        db.add_tag("from-only", "acme_webapp").unwrap();
+        db.add_tag("to-only", "acme-webapp").unwrap();
+        db.add_tag("both", "acme_webapp").unwrap();
+        db.add_tag("both", "acme-webapp").unwrap();
+        db.add_tag("neither", "other").unwrap();
+
+        assert_eq!(db.merge_tags("acme_webapp", "acme-webapp").unwrap(), 2);
+
+        for id in ["from-only", "to-only", "both"] {
+            assert_eq!(db.get_tags(id).unwrap(), vec!["acme-webapp"], "{id}");
+        }
+        assert_eq!(db.get_tags("neither").unwrap(), vec!["other"]);
+
+        // Idempotent once `from` is gone; a self-merge keeps the tag
+        assert_eq!(db.merge_tags("acme_webapp", "acme-webapp").unwrap(), 0);
+        assert_eq!(db.merge_tags("acme-webapp", "acme-webapp").unwrap(), 0);
+        assert_eq!(db.get_tags("both").unwrap(), vec!["acme-webapp"]);
+    }
+
     #[test]
     fn test_delete_stream_cascades_to_tags() {
         let db = Database::open_in_memory().unwrap();
@@ -2773,6 +4399,33 @@ mod tests {
         assert_eq!(s2_tags.1, vec!["internal"]);
     }
 
+    #[test]
+    fn test_get_stream_ids_with_any_tag() {
+        let db = Database::open_in_memory().unwrap();
+        for id in ["s1", "s2", "s3"] {
+            db.insert_stream(&make_stream(id, None)).unwrap();
+        }
+        db.add_tag("s1", "acme").unwrap();
+        db.add_tag("s1", "learning").unwrap();
+        db.add_tag("s2", "learning").unwrap();
+
+        assert_eq!(
+            db.get_stream_ids_with_any_tag(&["learning", "acme"])
+                .unwrap(),
+            vec!["s1", "s2"]
+        );
+        assert_eq!(
+            db.get_stream_ids_with_any_tag(&["acme"]).unwrap(),
+            vec!["s1"]
+        );
+        assert!(
+            db.get_stream_ids_with_any_tag(&["missing"])
+                .unwrap()
+                .is_empty()
+        );
+        assert!(db.get_stream_ids_with_any_tag(&[]).unwrap().is_empty());
+    }
+
     #[test]
     fn test_get_streams_with_tags() {
         let db = Database::open_in_memory().unwrap();
@@ -2854,7 +4507,7 @@ mod tests {
 
         let start = chrono::Utc.with_ymd_and_hms(2026, 1, 29, 9, 0, 0).unwrap();
         let end = chrono::Utc.with_ymd_and_hms(2026, 1, 29, 12, 0, 0).unwrap();
-        let sessions = db.agent_sessions_in_range(start, end).unwrap();
+        let sessions = db.agent_sessions_in_range(start, end, None).unwrap();
 
         assert_eq!(sessions.len(), 1);
         assert_eq!(sessions[0].project_name, "project");
@@ -2901,15 +4554,447 @@ mod tests {
 
         let start = chrono::Utc.with_ymd_and_hms(2026, 1, 29, 9, 0, 0).unwrap();
         let end = chrono::Utc.with_ymd_and_hms(2026, 1, 29, 12, 0, 0).unwrap();
-        let sessions = db.agent_sessions_in_range(start, end).unwrap();
+        let sessions = db.agent_sessions_in_range(start, end, None).unwrap();
 
         assert_eq!(sessions.len(), 1);
         assert_eq!(sessions[0].session_id, "ses_opencode_test");
         assert_eq!(sessions[0].source, SessionSource::OpenCode);
     }
 
-    // NOTE: Migration tests removed. Schema v7 is a breaking change - old databases
-    // must be deleted and re-imported from events.jsonl.
+    #[test]
+    fn test_agent_sessions_in_range_filters_by_source() {
+        use chrono::TimeZone;
+        use tt_core::session::{AgentSession, SessionSource};
+
+        let db = Database::open_in_memory().unwrap();
+
+        let make_session = |session_id: &str, source: SessionSource, hour: u32| AgentSession {
+            source,
+            ..make_agent_session(
+                session_id,
+                chrono::Utc
+                    .with_ymd_and_hms(2026, 1, 29, hour, 0, 0)
+                    .unwrap(),
+            )
+        };
+
+        db.upsert_agent_session(&make_session("claude-1", SessionSource::Claude, 10), None)
+            .unwrap();
+        db.upsert_agent_session(&make_session("ses_oc_1", SessionSource::OpenCode, 10), None)
+            .unwrap();
+        db.upsert_agent_session(&make_session("claude-2", SessionSource::Claude, 11), None)
+            .unwrap();
+
+        let start = chrono::Utc.with_ymd_and_hms(2026, 1, 29, 9, 0, 0).unwrap();
+        let end = chrono::Utc.with_ymd_and_hms(2026, 1, 29, 12, 0, 0).unwrap();
+
+        let all = db.agent_sessions_in_range(start, end, None).unwrap();
+        assert_eq!(all.len(), 3);
+
+        let claude = db
+            .agent_sessions_in_range(start, end, Some(SessionSource::Claude))
+            .unwrap();
+        let ids: Vec<&str> = claude.iter().map(|s| s.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["claude-1", "claude-2"]);
+
+        let opencode = db
+            .agent_sessions_in_range(start, end, Some(SessionSource::OpenCode))
+            .unwrap();
+        assert_eq!(opencode.len(), 1);
+        assert_eq!(opencode[0].session_id, "ses_oc_1");
+        assert_eq!(opencode[0].source, SessionSource::OpenCode);
+    }
+
+    fn make_agent_session(
+        session_id: &str,
+        start_time: DateTime<Utc>,
+    ) -> tt_core::session::AgentSession {
+        tt_core::session::AgentSession {
+            session_id: session_id.to_string(),
+            source: tt_core::session::SessionSource::Claude,
+            parent_session_id: None,
+            session_type: tt_core::session::SessionType::User,
+            project_path: "/home/user/project".to_string(),
+            project_name: "project".to_string(),
+            start_time,
+            end_time: None,
+            message_count: 1,
+            summary: None,
+            user_prompts: vec![],
+            starting_prompt: None,
+            assistant_message_count: 0,
+            tool_call_count: 0,
+            user_message_timestamps: Vec::new(),
+            tool_call_timestamps: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_link_sessions_to_streams_uses_dominant_stream() {
+        let db = Database::open_in_memory().unwrap();
+        let base = Utc.with_ymd_and_hms(2026, 1, 29, 10, 0, 0).unwrap();
+
+        db.insert_stream(&make_stream("stream-a", Some("a")))
+            .unwrap();
+        db.insert_stream(&make_stream("stream-b", Some("b")))
+            .unwrap();
+        db.upsert_agent_session(&make_agent_session("sess-1", base), None)
+            .unwrap();
+        db.upsert_agent_session(&make_agent_session("sess-2", base), None)
+            .unwrap();
+
+        // sess-1: two events in stream-a, one in stream-b
+        for (i, stream_id) in ["stream-a", "stream-a", "stream-b"].iter().enumerate() {
+            let mut event = make_event(
+                &format!("s1-{i}"),
+                base + chrono::Duration::minutes(i64::try_from(i).unwrap()),
+                tt_core::EventType::AgentToolUse,
+            );
+            event.session_id = Some("sess-1".to_string());
+            db.insert_event(&event).unwrap();
+            db.assign_event_to_stream(&event.id, stream_id, "inferred")
+                .unwrap();
+        }
+        // sess-2: no assigned events
+        let mut event = make_event("s2-0", base, tt_core::EventType::AgentToolUse);
+        event.session_id = Some("sess-2".to_string());
+        db.insert_event(&event).unwrap();
+
+        db.link_sessions_to_streams().unwrap();
+
+        let linked = db.sessions_for_stream("stream-a").unwrap();
+        assert_eq!(linked.len(), 1);
+        assert_eq!(linked[0].session_id, "sess-1");
+        assert!(db.sessions_for_stream("stream-b").unwrap().is_empty());
+
+        // Upserting session metadata must not clear the link
+        db.upsert_agent_session(&make_agent_session("sess-1", base), None)
+            .unwrap();
+        assert_eq!(db.sessions_for_stream("stream-a").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_events_with_unknown_session_reports_missing_metadata() {
+        let db = Database::open_in_memory().unwrap();
+        let base = Utc.with_ymd_and_hms(2026, 1, 29, 10, 0, 0).unwrap();
+
+        db.upsert_agent_session(&make_agent_session("known", base), None)
+            .unwrap();
+        for (id, session_id) in [
+            ("e1", Some("known")),
+            ("e2", Some("orphan")),
+            ("e3", Some("orphan")),
+            ("e4", None),
+        ] {
+            let mut event = make_event(id, base, tt_core::EventType::AgentToolUse);
+            event.session_id = session_id.map(String::from);
+            db.insert_event(&event).unwrap();
+        }
+
+        assert_eq!(db.events_with_unknown_session().unwrap(), vec!["orphan"]);
+
+        db.upsert_agent_session(&make_agent_session("orphan", base), None)
+            .unwrap();
+        assert!(db.events_with_unknown_session().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_subagents_returns_children_in_start_order() {
+        let db = Database::open_in_memory().unwrap();
+        let base = Utc.with_ymd_and_hms(2026, 1, 29, 10, 0, 0).unwrap();
+
+        db.upsert_agent_session(&make_agent_session("parent", base), None)
+            .unwrap();
+        for (id, minutes) in [("sub-b", 20), ("sub-a", 5)] {
+            let mut sub = make_agent_session(id, base + chrono::Duration::minutes(minutes));
+            sub.parent_session_id = Some("parent".to_string());
+            sub.session_type = tt_core::session::SessionType::Subagent;
+            db.upsert_agent_session(&sub, None).unwrap();
+        }
+
+        let subagents = db.get_subagents("parent").unwrap();
+        let ids: Vec<&str> = subagents.iter().map(|s| s.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["sub-a", "sub-b"]);
+        assert!(db.get_subagents("sub-a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_sessions_before_keeps_recent_and_ongoing() {
+        let db = Database::open_in_memory().unwrap();
+        let cutoff = Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap();
+
+        let mut old = make_agent_session("old", Utc.with_ymd_and_hms(2026, 1, 1, 9, 0, 0).unwrap());
+        old.end_time = Some(Utc.with_ymd_and_hms(2026, 1, 1, 10, 0, 0).unwrap());
+        // Started before the cutoff but ended after it
+        let mut recent = make_agent_session(
+            "recent",
+            Utc.with_ymd_and_hms(2026, 1, 14, 23, 0, 0).unwrap(),
+        );
+        recent.end_time = Some(Utc.with_ymd_and_hms(2026, 1, 15, 1, 0, 0).unwrap());
+        let ongoing = make_agent_session(
+            "ongoing",
+            Utc.with_ymd_and_hms(2026, 1, 20, 9, 0, 0).unwrap(),
+        );
+
+        for session in [&old, &recent, &ongoing] {
+            db.upsert_agent_session(session, None).unwrap();
+        }
+
+        assert_eq!(db.delete_sessions_before(cutoff).unwrap(), 1);
+
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap();
+        let remaining = db.agent_sessions_in_range(start, end, None).unwrap();
+        let ids: Vec<&str> = remaining.iter().map(|s| s.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["recent", "ongoing"]);
+    }
+
+    #[test]
+    fn test_agent_session_timestamps_roundtrip() {
+        use chrono::TimeZone;
+        use tt_core::session::AgentSession;
+
+        let db = Database::open_in_memory().unwrap();
+
+        let user_ts = vec![
+            chrono::Utc.with_ymd_and_hms(2026, 1, 29, 10, 0, 0).unwrap(),
+            chrono::Utc
+                .with_ymd_and_hms(2026, 1, 29, 10, 15, 0)
+                .unwrap()
+                + chrono::Duration::milliseconds(250),
+        ];
+        let tool_ts = vec![
+            chrono::Utc.with_ymd_and_hms(2026, 1, 29, 10, 1, 0).unwrap(),
+            chrono::Utc.with_ymd_and_hms(2026, 1, 29, 10, 2, 0).unwrap(),
+            chrono::Utc
+                .with_ymd_and_hms(2026, 1, 29, 10, 16, 0)
+                .unwrap(),
+        ];
+
+        let entry = AgentSession {
+            end_time: Some(chrono::Utc.with_ymd_and_hms(2026, 1, 29, 11, 0, 0).unwrap()),
+            message_count: 4,
+            assistant_message_count: 2,
+            tool_call_count: 3,
+            user_message_timestamps: user_ts.clone(),
+            tool_call_timestamps: tool_ts.clone(),
+            ..make_agent_session(
+                "ts-session",
+                chrono::Utc.with_ymd_and_hms(2026, 1, 29, 10, 0, 0).unwrap(),
+            )
+        };
+
+        db.upsert_agent_session(&entry, None).unwrap();
+
+        let start = chrono::Utc.with_ymd_and_hms(2026, 1, 29, 9, 0, 0).unwrap();
+        let end = chrono::Utc.with_ymd_and_hms(2026, 1, 29, 12, 0, 0).unwrap();
+        let sessions = db.agent_sessions_in_range(start, end, None).unwrap();
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].user_message_timestamps, user_ts);
+        assert_eq!(sessions[0].tool_call_timestamps, tool_ts);
+    }
+
+    #[test]
+    fn test_migration_v9_to_v10_adds_session_timestamp_columns() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("v9.db");
+
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE schema_info (version INTEGER NOT NULL);
+                 INSERT INTO schema_info (version) VALUES (9);
+                 CREATE TABLE streams (
+                   id TEXT PRIMARY KEY,
+                   created_at TEXT NOT NULL,
+                   updated_at TEXT NOT NULL,
+                   name TEXT,
+                   time_direct_ms INTEGER DEFAULT 0,
+                   time_delegated_ms INTEGER DEFAULT 0,
+                   first_event_at TEXT,
+                   last_event_at TEXT,
+                   needs_recompute INTEGER DEFAULT 0
+                 );
+                 CREATE TABLE agent_sessions (
+                   session_id TEXT PRIMARY KEY,
+                   source TEXT NOT NULL DEFAULT 'claude',
+                   parent_session_id TEXT,
+                   session_type TEXT NOT NULL DEFAULT 'user',
+                   project_path TEXT NOT NULL,
+                   project_name TEXT NOT NULL,
+                   start_time TEXT NOT NULL,
+                   end_time TEXT,
+                   message_count INTEGER NOT NULL,
+                   summary TEXT,
+                   user_prompts TEXT DEFAULT '[]',
+                   starting_prompt TEXT,
+                   assistant_message_count INTEGER DEFAULT 0,
+                   tool_call_count INTEGER DEFAULT 0,
+                   machine_id TEXT
+                 );
+                 INSERT INTO agent_sessions (session_id, project_path, project_name, start_time, message_count)
+                 VALUES ('old-session', '/p', 'p', '2026-01-29T10:00:00.000Z', 1);",
+            )
+            .unwrap();
+        }
+
+        let db = Database::open(&db_path).unwrap();
+        let start = Utc.with_ymd_and_hms(2026, 1, 29, 9, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2026, 1, 29, 12, 0, 0).unwrap();
+        let sessions = db.agent_sessions_in_range(start, end, None).unwrap();
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, "old-session");
+        assert!(sessions[0].user_message_timestamps.is_empty());
+        assert!(sessions[0].tool_call_timestamps.is_empty());
+
+        let version = db
+            .conn
+            .query_row("SELECT version FROM schema_info LIMIT 1", [], |row| {
+                row.get::<_, i32>(0)
+            })
+            .unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migration_v7_preserves_streams_tags_and_sessions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("v7.db");
+
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE schema_info (version INTEGER NOT NULL);
+                 INSERT INTO schema_info (version) VALUES (7);
+                 CREATE TABLE events (
+                   id TEXT PRIMARY KEY,
+                   timestamp TEXT NOT NULL,
+                   type TEXT NOT NULL,
+                   source TEXT NOT NULL,
+                   schema_version INTEGER DEFAULT 1,
+                   cwd TEXT,
+                   git_project TEXT,
+                   git_workspace TEXT,
+                   pane_id TEXT,
+                   tmux_session TEXT,
+                   window_index INTEGER,
+                   status TEXT,
+                   idle_duration_ms INTEGER,
+                   action TEXT,
+                   session_id TEXT,
+                   stream_id TEXT,
+                   assignment_source TEXT DEFAULT 'inferred',
+                   FOREIGN KEY (stream_id) REFERENCES streams(id) ON DELETE SET NULL
+                 );
+                 CREATE TABLE streams (
+                   id TEXT PRIMARY KEY,
+                   created_at TEXT NOT NULL,
+                   updated_at TEXT NOT NULL,
+                   name TEXT,
+                   time_direct_ms INTEGER DEFAULT 0,
+                   time_delegated_ms INTEGER DEFAULT 0,
+                   first_event_at TEXT,
+                   last_event_at TEXT,
+                   needs_recompute INTEGER DEFAULT 0
+                 );
+                 CREATE TABLE stream_tags (
+                   stream_id TEXT NOT NULL,
+                   tag TEXT NOT NULL,
+                   PRIMARY KEY (stream_id, tag),
+                   FOREIGN KEY (stream_id) REFERENCES streams(id) ON DELETE CASCADE
+                 );
+                 CREATE TABLE agent_sessions (
+                   session_id TEXT PRIMARY KEY,
+                   source TEXT NOT NULL DEFAULT 'claude',
+                   parent_session_id TEXT,
+                   session_type TEXT NOT NULL DEFAULT 'user',
+                   project_path TEXT NOT NULL,
+                   project_name TEXT NOT NULL,
+                   start_time TEXT NOT NULL,
+                   end_time TEXT,
+                   message_count INTEGER NOT NULL,
+                   summary TEXT,
+                   user_prompts TEXT DEFAULT '[]',
+                   starting_prompt TEXT,
+                   assistant_message_count INTEGER DEFAULT 0,
+                   tool_call_count INTEGER DEFAULT 0
+                 );
+                 INSERT INTO streams (id, created_at, updated_at, name)
+                 VALUES ('s1', '2026-01-01T00:00:00.000Z', '2026-01-01T00:00:00.000Z', 'hand-named');
+                 INSERT INTO stream_tags (stream_id, tag) VALUES ('s1', 'acme');
+                 INSERT INTO events (id, timestamp, type, source, stream_id, assignment_source)
+                 VALUES ('e1', '2026-01-01T10:00:00.000Z', 'tmux_pane_focus', 'remote.tmux', 's1', 'user');
+                 INSERT INTO agent_sessions (session_id, project_path, project_name, start_time, message_count)
+                 VALUES ('sess', '/p', 'p', '2026-01-01T10:00:00.000Z', 2);",
+            )
+            .unwrap();
+        }
+
+        let db = Database::open(&db_path).unwrap();
+
+        let stream = db.get_stream("s1").unwrap().unwrap();
+        assert_eq!(stream.name.as_deref(), Some("hand-named"));
+        assert_eq!(db.get_tags("s1").unwrap(), vec!["acme"]);
+
+        let events = db.get_events(None, None).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].stream_id.as_deref(), Some("s1"));
+        assert_eq!(events[0].machine_id, None);
+
+        let start = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2026, 1, 2, 0, 0, 0).unwrap();
+        let sessions = db.agent_sessions_in_range(start, end, None).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].message_count, 2);
+
+        // New columns and tables are usable after the upgrade
+        db.upsert_agent_session(&sessions[0], Some("machine-1"))
+            .unwrap();
+        db.upsert_machine("machine-1", "laptop", None).unwrap();
+
+        let version = db
+            .conn
+            .query_row("SELECT version FROM schema_info LIMIT 1", [], |row| {
+                row.get::<_, i32>(0)
+            })
+            .unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migration_from_previous_version_preserves_data() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("previous.db");
+
+        // Build a current-schema DB, then roll it back to look like v(N-1).
+        {
+            let db = Database::open(&db_path).unwrap();
+            db.insert_stream(&make_stream("s1", Some("kept"))).unwrap();
+            db.add_tag("s1", "tag").unwrap();
+            db.conn
+                .execute(
+                    "UPDATE schema_info SET version = ?1",
+                    params![SCHEMA_VERSION - 1],
+                )
+                .unwrap();
+        }
+
+        let db = Database::open(&db_path).unwrap();
+        assert_eq!(
+            db.get_stream("s1").unwrap().unwrap().name.as_deref(),
+            Some("kept")
+        );
+        assert_eq!(db.get_tags("s1").unwrap(), vec!["tag"]);
+    }
+
+    #[test]
+    fn test_migrations_cover_every_version() {
+        let targets: Vec<i32> = MIGRATIONS.iter().map(|(target, _)| *target).collect();
+        let expected: Vec<i32> = (OLDEST_MIGRATABLE_VERSION + 1..=SCHEMA_VERSION).collect();
+        assert_eq!(targets, expected);
+    }
 
     // ========== streams_in_range Tests ==========
 
@@ -3297,18 +5382,99 @@ mod tests {
             .unwrap();
         assert_eq!(row, "test-machine-uuid");
 
-        // Upsert same session with machine_id = None (should overwrite)
+        // Upsert same session with machine_id = None (updates the existing row)
         db.upsert_agent_session(&session, None).unwrap();
 
-        // Query and verify machine_id is now NULL
-        let result: Option<String> = db
-            .conn
-            .query_row(
-                "SELECT machine_id FROM agent_sessions WHERE session_id = ?1",
-                ["test-session-1"],
-                |row| row.get(0),
-            )
+        let machines = |db: &Database| -> Vec<String> {
+            db.conn
+                .prepare(
+                    "SELECT machine_id FROM agent_sessions WHERE session_id = ?1 ORDER BY machine_id",
+                )
+                .unwrap()
+                .query_map(["test-session-1"], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+        assert_eq!(machines(&db), vec!["test-machine-uuid"]);
+
+        // A legacy row with an unknown machine is claimed on re-import, not duplicated
+        let db = Database::open_in_memory().unwrap();
+        db.upsert_agent_session(&session, None).unwrap();
+        db.upsert_agent_session(&session, Some("test-machine-uuid"))
+            .unwrap();
+        assert_eq!(machines(&db), vec!["test-machine-uuid"]);
+    }
+
+    #[test]
+    fn test_same_session_id_from_two_machines_kept_distinct() {
+        let db = Database::open_in_memory().unwrap();
+        let start = Utc.with_ymd_and_hms(2026, 1, 29, 9, 0, 0).unwrap();
+        let session = |project_path: &str, message_count| tt_core::session::AgentSession {
+            source: tt_core::session::SessionSource::OpenCode,
+            project_path: project_path.to_string(),
+            end_time: Some(start + chrono::Duration::hours(1)),
+            message_count,
+            ..make_agent_session("ses_collide", start)
+        };
+
+        db.upsert_agent_session(&session("/laptop/project", 1), Some("machine-a"))
+            .unwrap();
+        db.upsert_agent_session(&session("/desktop/project", 2), Some("machine-b"))
+            .unwrap();
+        // Re-importing one machine's session still updates it in place
+        db.upsert_agent_session(&session("/laptop/project", 3), Some("machine-a"))
+            .unwrap();
+
+        assert_eq!(db.count_agent_sessions().unwrap(), 2);
+        let mut sessions = db
+            .agent_sessions_in_range(start, start + chrono::Duration::hours(2), None)
             .unwrap();
-        assert_eq!(result, None);
+        sessions.sort_by(|a, b| a.project_path.cmp(&b.project_path));
+        let kept: Vec<(&str, i32)> = sessions
+            .iter()
+            .map(|s| (s.project_path.as_str(), s.message_count))
+            .collect();
+        assert_eq!(kept, vec![("/desktop/project", 2), ("/laptop/project", 3)]);
+    }
+
+    #[test]
+    fn test_link_sessions_to_streams_matches_machine() {
+        let db = Database::open_in_memory().unwrap();
+        let base = Utc.with_ymd_and_hms(2026, 1, 29, 10, 0, 0).unwrap();
+
+        db.insert_stream(&make_stream("stream-a", Some("a")))
+            .unwrap();
+        db.insert_stream(&make_stream("stream-b", Some("b")))
+            .unwrap();
+        for machine in ["machine-a", "machine-b"] {
+            db.upsert_agent_session(&make_agent_session("ses_collide", base), Some(machine))
+                .unwrap();
+        }
+        // machine-b has more events, but they must not decide machine-a's link
+        for (i, (machine, stream_id)) in [
+            ("machine-a", "stream-a"),
+            ("machine-b", "stream-b"),
+            ("machine-b", "stream-b"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut event = make_event(
+                &format!("e{i}"),
+                base + chrono::Duration::minutes(i64::try_from(i).unwrap()),
+                tt_core::EventType::AgentToolUse,
+            );
+            event.machine_id = Some(machine.to_string());
+            event.session_id = Some("ses_collide".to_string());
+            db.insert_event(&event).unwrap();
+            db.assign_event_to_stream(&event.id, stream_id, "inferred")
+                .unwrap();
+        }
+
+        db.link_sessions_to_streams().unwrap();
+
+        assert_eq!(db.sessions_for_stream("stream-a").unwrap().len(), 1);
+        assert_eq!(db.sessions_for_stream("stream-b").unwrap().len(), 1);
     }
 }
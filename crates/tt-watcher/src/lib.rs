@@ -140,6 +140,7 @@ impl EmitState {
             idle_duration_ms: None,
             window_app_id: Some(window.app_id.clone()),
             window_title: Some(window.title.clone()),
+            url: None,
             action: None,
             cwd: None,
             session_id: None,
@@ -169,6 +170,7 @@ impl EmitState {
             idle_duration_ms,
             window_app_id: None,
             window_title: None,
+            url: None,
             action: None,
             cwd: None,
             session_id: None,